@@ -30,11 +30,17 @@ async fn main() -> Result<()> {
         flush_interval_ms: 100,
         num_workers: 2,
         enable_compression: true,
+        short_validator_tag: false,
+        instance: None,
+        spool_dir: "./data/influx_spool".to_string(),
+        spool_max_mb: 0,
+        spool_max_batches: 100_000,
+        spool_replay_interval_secs: 5,
     };
     
     // Create storage instance
     println!("Connecting to InfluxDB...");
-    let mut storage = InfluxDBStorage::new(config).await?;
+    let storage = InfluxDBStorage::new(config, "devnet".to_string()).await?;
     println!("✅ Connected successfully!\n");
     
     // Create test vote latencies
@@ -23,6 +23,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         raw_data: vec![],
         voted_on_slots: vec![995, 996, 997, 998], // Voting on these slots
         landed_slot: Some(1000), // Landing in slot 1000
+        landing_status: Default::default(),
     };
     
     println!("Test Vote Transaction:");
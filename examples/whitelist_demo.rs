@@ -1,4 +1,4 @@
-use svlm::config::{Config, DiscoveryConfig, AppConfig, SolanaConfig, GrpcConfig, InfluxConfig, MetricsConfig, LatencyConfig};
+use svlm::config::{Config, DiscoveryConfig, AppConfig, SolanaConfig, GrpcConfig, InfluxConfig, MetricsConfig, LatencyConfig, SubscriptionMode, EndpointMode, GrpcCommitment, OverflowPolicy, AdmissionPriority};
 
 fn main() {
     println!("Demonstrating whitelist filtering that accepts both identity and vote account pubkeys\n");
@@ -34,10 +34,34 @@ fn main() {
             endpoint: None,
             access_token: None,
             max_subscriptions: 50,
+            admission_priority: AdmissionPriority::Stake,
+            priority_validators: vec![],
+            anomaly_window_secs: 600,
             connection_timeout_secs: 30,
             reconnect_interval_secs: 5,
+            reconnect_max_delay_secs: 60,
             buffer_size: 10000,
+            overflow_policy: OverflowPolicy::Block,
+            spill_dir: "./data/vote_spill".to_string(),
+            spill_max_mb: 512,
             enable_tls: false,
+            tls_ca_cert_path: None,
+            tls_client_cert_path: None,
+            tls_client_key_path: None,
+            tls_domain_name: None,
+            subscription_mode: SubscriptionMode::PerValidator,
+            stream_pool_size: 1,
+            replay_on_reconnect: true,
+            stall_timeout_secs: 30,
+            max_slot_lag: 150,
+            endpoints: Vec::new(),
+            endpoint_mode: EndpointMode::Failover,
+            commitment: GrpcCommitment::Processed,
+            track_finality: false,
+            subscribe_block_meta: false,
+            track_missed_votes: false,
+            missed_vote_tolerance_slots: 32,
+            track_root_lag: false,
         },
         influxdb: InfluxConfig {
             url: "http://localhost:8086".to_string(),
@@ -48,6 +72,12 @@ fn main() {
             flush_interval_ms: 100,
             num_workers: 2,
             enable_compression: false,
+            short_validator_tag: false,
+            instance: None,
+            spool_dir: "./data/influx_spool".to_string(),
+            spool_max_mb: 0,
+            spool_max_batches: 100_000,
+            spool_replay_interval_secs: 5,
         },
         metrics: MetricsConfig {
             enabled: false,
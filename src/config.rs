@@ -75,20 +75,264 @@ pub struct GrpcConfig {
     /// Optional access token for gRPC authentication
     pub access_token: Option<String>,
     
-    /// Maximum number of concurrent subscriptions
+    /// Maximum number of concurrent per-validator subscriptions
+    ///
+    /// Not applied in multiplexed mode, which tracks every discovered validator.
     pub max_subscriptions: usize,
     
+    /// How validators are ranked when there are more than `max_subscriptions`
+    #[serde(default)]
+    pub admission_priority: AdmissionPriority,
+    
+    /// Validators (identity or vote pubkeys) ranked first by the `whitelist` priority
+    #[serde(default)]
+    pub priority_validators: Vec<String>,
+    
+    /// Seconds a reported anomaly keeps ranking a validator first under the `anomalies` priority
+    #[serde(default = "default_anomaly_window_secs")]
+    pub anomaly_window_secs: u64,
+    
     /// Connection timeout in seconds
     pub connection_timeout_secs: u64,
     
-    /// Reconnection interval in seconds
+    /// Reconnection interval in seconds (initial reconnect backoff)
     pub reconnect_interval_secs: u64,
     
+    /// Upper bound for the reconnect backoff in seconds
+    #[serde(default = "default_reconnect_max_delay_secs")]
+    pub reconnect_max_delay_secs: u64,
+    
     /// Buffer size for incoming transactions
     pub buffer_size: usize,
     
-    /// Enable TLS for gRPC connections
+    /// What to do with new votes when the buffer is full
+    #[serde(default)]
+    pub overflow_policy: OverflowPolicy,
+    
+    /// Directory for the spill queue used by the `spill` overflow policy
+    #[serde(default = "default_spill_dir")]
+    pub spill_dir: String,
+    
+    /// Maximum size of the spill queue in megabytes
+    #[serde(default = "default_spill_max_mb")]
+    pub spill_max_mb: u64,
+    
+    /// Enable TLS for gRPC connections (`http://` endpoints never use TLS)
     pub enable_tls: bool,
+    
+    /// PEM CA bundle to trust instead of the system roots
+    #[serde(default)]
+    pub tls_ca_cert_path: Option<String>,
+    
+    /// PEM client certificate for mutual TLS
+    #[serde(default)]
+    pub tls_client_cert_path: Option<String>,
+    
+    /// PEM private key for the client certificate
+    #[serde(default)]
+    pub tls_client_key_path: Option<String>,
+    
+    /// Server name to verify instead of the endpoint host
+    #[serde(default)]
+    pub tls_domain_name: Option<String>,
+    
+    /// How validators are mapped onto gRPC streams
+    #[serde(default)]
+    pub subscription_mode: SubscriptionMode,
+    
+    /// Number of shared streams in multiplexed mode
+    #[serde(default = "default_stream_pool_size")]
+    pub stream_pool_size: usize,
+    
+    /// Replay missed updates with `from_slot` after a reconnect, when supported
+    #[serde(default = "default_replay_on_reconnect")]
+    pub replay_on_reconnect: bool,
+    
+    /// Seconds without any message before a stream is considered stalled
+    #[serde(default = "default_stall_timeout_secs")]
+    pub stall_timeout_secs: u64,
+    
+    /// Slots a stream may fall behind the highest slot seen before it is reconnected
+    #[serde(default = "default_max_slot_lag")]
+    pub max_slot_lag: u64,
+    
+    /// Ordered list of endpoints; when set, replaces `endpoint`/`access_token`
+    #[serde(default)]
+    pub endpoints: Vec<GrpcEndpointConfig>,
+    
+    /// How streams use multiple endpoints
+    #[serde(default)]
+    pub endpoint_mode: EndpointMode,
+    
+    /// Commitment level for vote transaction updates
+    #[serde(default)]
+    pub commitment: GrpcCommitment,
+    
+    /// Hold votes until their landing slot is finalized or skipped
+    #[serde(default)]
+    pub track_finality: bool,
+    
+    /// Subscribe to block metadata for block times and landing slot leaders
+    #[serde(default)]
+    pub subscribe_block_meta: bool,
+    
+    /// Detect confirmed slots monitored validators never voted on
+    #[serde(default)]
+    pub track_missed_votes: bool,
+    
+    /// Slots past a confirmed slot before a validator that has not voted on it is counted as missing it
+    #[serde(default = "default_missed_vote_tolerance_slots")]
+    pub missed_vote_tolerance_slots: u64,
+    
+    /// Track how far each validator's root trails the cluster's finalized slot
+    #[serde(default)]
+    pub track_root_lag: bool,
+}
+
+/// A Yellowstone gRPC endpoint with its own credentials
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GrpcEndpointConfig {
+    /// Name used in logs and metrics (defaults to the URL)
+    #[serde(default)]
+    pub name: Option<String>,
+    
+    /// Endpoint URL
+    pub url: String,
+    
+    /// Access token for this endpoint
+    #[serde(default)]
+    pub access_token: Option<String>,
+}
+
+impl GrpcEndpointConfig {
+    /// Name used in logs and metrics
+    pub fn label(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.url)
+    }
+}
+
+/// Strategy for streaming from multiple gRPC endpoints
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EndpointMode {
+    /// Stream from one endpoint at a time, moving down the list when it fails
+    #[default]
+    Failover,
+    
+    /// Stream from every endpoint at once and keep the first copy of each vote
+    Race,
+}
+
+/// Ranking used to pick which validators get a subscription
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdmissionPriority {
+    /// Highest activated stake first
+    #[default]
+    Stake,
+    
+    /// Validators in `priority_validators` first, then by stake
+    Whitelist,
+    
+    /// Delinquent validators and validators with recent anomalies first, then by stake
+    Anomalies,
+}
+
+/// Behaviour of the vote buffer when the processing pipeline falls behind
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    /// Wait for room, stalling the gRPC streams
+    #[default]
+    Block,
+    
+    /// Drop the incoming vote
+    DropNewest,
+    
+    /// Evict the oldest buffered vote
+    DropOldest,
+    
+    /// Spill votes to a local file and drain it once there is room
+    Spill,
+}
+
+/// Commitment level requested from the gRPC stream
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GrpcCommitment {
+    /// Votes as soon as their slot is processed (may later be forked out)
+    #[default]
+    Processed,
+    
+    /// Votes once their slot is optimistically confirmed
+    Confirmed,
+    
+    /// Votes once their slot is finalized
+    Finalized,
+}
+
+/// Strategy for mapping monitored validators onto gRPC streams
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubscriptionMode {
+    /// One dedicated connection and subscription per validator
+    #[default]
+    PerValidator,
+    
+    /// A small pool of shared vote streams, demultiplexed by vote account
+    Multiplexed,
+}
+
+fn default_stream_pool_size() -> usize {
+    1
+}
+
+fn default_reconnect_max_delay_secs() -> u64 {
+    60
+}
+
+fn default_replay_on_reconnect() -> bool {
+    true
+}
+
+fn default_stall_timeout_secs() -> u64 {
+    30
+}
+
+fn default_max_slot_lag() -> u64 {
+    150
+}
+
+fn default_anomaly_window_secs() -> u64 {
+    600
+}
+
+fn default_missed_vote_tolerance_slots() -> u64 {
+    32
+}
+
+fn default_spill_dir() -> String {
+    "./data/vote_spill".to_string()
+}
+
+fn default_spill_max_mb() -> u64 {
+    512
+}
+
+fn default_influx_spool_dir() -> String {
+    "./data/influx_spool".to_string()
+}
+
+fn default_influx_spool_max_mb() -> u64 {
+    1024
+}
+
+fn default_influx_spool_max_batches() -> usize {
+    100_000
+}
+
+fn default_influx_spool_replay_interval_secs() -> u64 {
+    5
 }
 
 
@@ -118,6 +362,31 @@ pub struct InfluxConfig {
     
     /// Enable compression for writes
     pub enable_compression: bool,
+    
+    /// Also tag points with the first 8 characters of the validator identity
+    #[serde(default)]
+    pub short_validator_tag: bool,
+    
+    /// Monitor instance or region, tagged as `instance` on every point and
+    /// matched by every query; for several monitors writing one bucket
+    #[serde(default)]
+    pub instance: Option<String>,
+    
+    /// Directory for batches spooled while InfluxDB is unavailable
+    #[serde(default = "default_influx_spool_dir")]
+    pub spool_dir: String,
+    
+    /// Maximum size of the write spool in megabytes (0 disables spooling)
+    #[serde(default = "default_influx_spool_max_mb")]
+    pub spool_max_mb: u64,
+    
+    /// Maximum number of batches in the write spool
+    #[serde(default = "default_influx_spool_max_batches")]
+    pub spool_max_batches: usize,
+    
+    /// Seconds between attempts to replay spooled batches
+    #[serde(default = "default_influx_spool_replay_interval_secs")]
+    pub spool_replay_interval_secs: u64,
 }
 
 /// Metrics configuration
@@ -246,15 +515,102 @@ impl Config {
             return Err(anyhow::anyhow!("InfluxDB bucket cannot be empty"));
         }
         
+        if self.influxdb.num_workers == 0 {
+            return Err(anyhow::anyhow!("InfluxDB write workers must be greater than 0"));
+        }
+        
+        if self.influxdb.spool_max_mb > 0 {
+            if self.influxdb.spool_max_batches == 0 {
+                return Err(anyhow::anyhow!("InfluxDB spool batch cap must be greater than 0"));
+            }
+            if self.influxdb.spool_replay_interval_secs == 0 {
+                return Err(anyhow::anyhow!("InfluxDB spool replay interval must be greater than 0"));
+            }
+            security::validate_path(&self.influxdb.spool_dir, None)
+                .map_err(|e| anyhow::anyhow!("Invalid InfluxDB spool directory: {}", e))?;
+        }
+        
+        if let Some(instance) = &self.influxdb.instance {
+            let valid = !instance.is_empty()
+                && instance.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+            if !valid {
+                return Err(anyhow::anyhow!(
+                    "Invalid InfluxDB instance label: {:?}. Use letters, digits, '-', '_' or '.'",
+                    instance
+                ));
+            }
+        }
+        
         // Validate InfluxDB URL (allows localhost)
         security::validate_influxdb_url(&self.influxdb.url, Some(&["http", "https"]))
             .map_err(|e| anyhow::anyhow!("Invalid InfluxDB URL: {}", e))?;
         
+        if self.grpc.track_missed_votes && self.grpc.missed_vote_tolerance_slots == 0 {
+            return Err(anyhow::anyhow!("gRPC missed vote tolerance must be greater than 0"));
+        }
+        
+        // Validate subscription admission
+        if self.grpc.max_subscriptions == 0 {
+            return Err(anyhow::anyhow!("gRPC max subscriptions must be greater than 0"));
+        }
+        
+        for pubkey in &self.grpc.priority_validators {
+            security::validate_pubkey(pubkey)
+                .map_err(|e| anyhow::anyhow!("Invalid pubkey in gRPC priority validators: {}", e))?;
+        }
+        
         // Validate gRPC buffer size
         if self.grpc.buffer_size == 0 {
             return Err(anyhow::anyhow!("gRPC buffer size must be greater than 0"));
         }
         
+        // Validate the spill queue
+        if self.grpc.overflow_policy == OverflowPolicy::Spill {
+            if self.grpc.spill_max_mb == 0 {
+                return Err(anyhow::anyhow!("gRPC spill queue size must be greater than 0"));
+            }
+            security::validate_path(&self.grpc.spill_dir, None)
+                .map_err(|e| anyhow::anyhow!("Invalid gRPC spill directory: {}", e))?;
+        }
+        
+        // Validate stream pool size
+        if self.grpc.stream_pool_size == 0 {
+            return Err(anyhow::anyhow!("gRPC stream pool size must be greater than 0"));
+        }
+        
+        // Validate the endpoint list
+        for endpoint in &self.grpc.endpoints {
+            security::validate_url(&endpoint.url, Some(&["http", "https"]))
+                .map_err(|e| anyhow::anyhow!("Invalid gRPC endpoint URL {}: {}", endpoint.url, e))?;
+        }
+        
+        if self.grpc.reconnect_max_delay_secs < self.grpc.reconnect_interval_secs {
+            return Err(anyhow::anyhow!("gRPC reconnect max delay must not be less than the reconnect interval"));
+        }
+        
+        if self.grpc.stall_timeout_secs == 0 {
+            return Err(anyhow::anyhow!("gRPC stall timeout must be greater than 0"));
+        }
+        
+        // Validate TLS files
+        for (name, path) in [
+            ("CA certificate", &self.grpc.tls_ca_cert_path),
+            ("client certificate", &self.grpc.tls_client_cert_path),
+            ("client key", &self.grpc.tls_client_key_path),
+        ] {
+            if let Some(path) = path {
+                let path = security::validate_path(path, None)
+                    .map_err(|e| anyhow::anyhow!("Invalid gRPC TLS {} path: {}", name, e))?;
+                if !path.is_file() {
+                    return Err(anyhow::anyhow!("gRPC TLS {} not found: {}", name, path.display()));
+                }
+            }
+        }
+        
+        if self.grpc.tls_client_cert_path.is_some() != self.grpc.tls_client_key_path.is_some() {
+            return Err(anyhow::anyhow!("gRPC TLS client certificate and key must be set together"));
+        }
+        
         // Validate gRPC endpoint if provided
         if let Some(endpoint) = &self.grpc.endpoint {
             security::validate_url(endpoint, Some(&["http", "https"]))
@@ -301,10 +657,34 @@ impl Default for Config {
                 endpoint: None,
                 access_token: None,
                 max_subscriptions: 100,
+                admission_priority: AdmissionPriority::Stake,
+                priority_validators: vec![],
+                anomaly_window_secs: default_anomaly_window_secs(),
                 connection_timeout_secs: 30,
                 reconnect_interval_secs: 5,
+                reconnect_max_delay_secs: 60,
                 buffer_size: 10000,
+                overflow_policy: OverflowPolicy::Block,
+                spill_dir: default_spill_dir(),
+                spill_max_mb: default_spill_max_mb(),
                 enable_tls: true,
+                tls_ca_cert_path: None,
+                tls_client_cert_path: None,
+                tls_client_key_path: None,
+                tls_domain_name: None,
+                subscription_mode: SubscriptionMode::PerValidator,
+                stream_pool_size: 1,
+                replay_on_reconnect: true,
+                stall_timeout_secs: 30,
+                max_slot_lag: 150,
+                endpoints: Vec::new(),
+                endpoint_mode: EndpointMode::Failover,
+                commitment: GrpcCommitment::Processed,
+                track_finality: false,
+                subscribe_block_meta: false,
+                track_missed_votes: false,
+                missed_vote_tolerance_slots: default_missed_vote_tolerance_slots(),
+                track_root_lag: false,
             },
             metrics: MetricsConfig {
                 enabled: true,
@@ -335,6 +715,12 @@ impl Default for Config {
                 flush_interval_ms: 100,
                 num_workers: 2,
                 enable_compression: true,
+                short_validator_tag: false,
+                instance: None,
+                spool_dir: default_influx_spool_dir(),
+                spool_max_mb: default_influx_spool_max_mb(),
+                spool_max_batches: default_influx_spool_max_batches(),
+                spool_replay_interval_secs: default_influx_spool_replay_interval_secs(),
             },
         }
     }
@@ -373,6 +759,15 @@ mod tests {
         config = Config::default();
         config.latency.window_size = 0;
         assert!(config.validate().is_err());
+        
+        // Instance labels end up in tags and Flux strings
+        config = Config::default();
+        config.influxdb.instance = Some("eu-west.1".to_string());
+        assert!(config.validate().is_ok());
+        config.influxdb.instance = Some("eu \"west\"".to_string());
+        assert!(config.validate().is_err());
+        config.influxdb.instance = Some(String::new());
+        assert!(config.validate().is_err());
     }
     
     #[test]
@@ -400,6 +795,11 @@ mod tests {
         // Empty InfluxDB token should fail
         config.influxdb.token = String::new();
         assert!(config.validate().is_err());
+        
+        // No write workers should fail
+        config = Config::default();
+        config.influxdb.num_workers = 0;
+        assert!(config.validate().is_err());
     }
     
     #[test]
@@ -413,6 +813,84 @@ mod tests {
         // Valid buffer size should pass
         config.grpc.buffer_size = 1000;
         assert!(config.validate().is_ok());
+        
+        // Spilling needs a usable directory and size
+        config.grpc.overflow_policy = OverflowPolicy::Spill;
+        assert!(config.validate().is_ok());
+        config.grpc.spill_max_mb = 0;
+        assert!(config.validate().is_err());
+        config.grpc.spill_max_mb = 512;
+        config.grpc.spill_dir = "../spill".to_string();
+        assert!(config.validate().is_err());
+        config.grpc.spill_dir = default_spill_dir();
+        config.grpc.overflow_policy = OverflowPolicy::Block;
+        
+        // Empty stream pool should fail
+        config.grpc.stream_pool_size = 0;
+        assert!(config.validate().is_err());
+        
+        config.grpc.stream_pool_size = 1;
+        config.grpc.stall_timeout_secs = 0;
+        assert!(config.validate().is_err());
+        
+        config.grpc.stall_timeout_secs = 30;
+        config.grpc.reconnect_max_delay_secs = 1;
+        assert!(config.validate().is_err());
+        
+        config.grpc.reconnect_max_delay_secs = 60;
+        config.grpc.max_subscriptions = 0;
+        assert!(config.validate().is_err());
+        
+        config.grpc.max_subscriptions = 100;
+        config.grpc.priority_validators = vec!["not a pubkey".to_string()];
+        assert!(config.validate().is_err());
+        
+        config.grpc.priority_validators.clear();
+        config.grpc.track_missed_votes = true;
+        config.grpc.missed_vote_tolerance_slots = 0;
+        assert!(config.validate().is_err());
+        
+        config.grpc.missed_vote_tolerance_slots = 32;
+        assert!(config.validate().is_ok());
+        
+        config.grpc.endpoints = vec![GrpcEndpointConfig {
+            name: Some("broken".to_string()),
+            url: "not a url".to_string(),
+            access_token: None,
+        }];
+        assert!(config.validate().is_err());
+        
+        // Only http(s) endpoints can be streamed from
+        config.grpc.endpoints[0].url = "ftp://grpc.example.com".to_string();
+        assert!(config.validate().is_err());
+    }
+    
+    #[test]
+    fn test_config_validation_grpc_tls() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let cert_path = temp_dir.path().join("client.pem");
+        let key_path = temp_dir.path().join("client.key");
+        std::fs::write(&cert_path, "cert").unwrap();
+        std::fs::write(&key_path, "key").unwrap();
+        
+        let mut config = Config::default();
+        
+        // Missing CA bundle should fail
+        config.grpc.tls_ca_cert_path = Some(temp_dir.path().join("missing.pem").display().to_string());
+        assert!(config.validate().is_err());
+        
+        config.grpc.tls_ca_cert_path = Some(cert_path.display().to_string());
+        assert!(config.validate().is_ok());
+        
+        // Client certificate without key should fail
+        config.grpc.tls_client_cert_path = Some(cert_path.display().to_string());
+        assert!(config.validate().is_err());
+        
+        config.grpc.tls_client_key_path = Some(key_path.display().to_string());
+        assert!(config.validate().is_ok());
+        
+        config.grpc.tls_client_key_path = Some("../client.key".to_string());
+        assert!(config.validate().is_err());
     }
     
     #[test]
@@ -436,10 +914,32 @@ mod tests {
     fn test_grpc_config_defaults() {
         let config = Config::default();
         assert_eq!(config.grpc.max_subscriptions, 100);
+        assert_eq!(config.grpc.admission_priority, AdmissionPriority::Stake);
+        assert!(config.grpc.priority_validators.is_empty());
+        assert_eq!(config.grpc.anomaly_window_secs, 600);
         assert_eq!(config.grpc.connection_timeout_secs, 30);
         assert_eq!(config.grpc.reconnect_interval_secs, 5);
+        assert_eq!(config.grpc.reconnect_max_delay_secs, 60);
         assert_eq!(config.grpc.buffer_size, 10000);
+        assert_eq!(config.grpc.overflow_policy, OverflowPolicy::Block);
+        assert_eq!(config.grpc.spill_max_mb, 512);
         assert!(config.grpc.enable_tls);
+        assert!(config.grpc.tls_ca_cert_path.is_none());
+        assert!(config.grpc.tls_client_cert_path.is_none());
+        assert!(config.grpc.tls_domain_name.is_none());
+        assert_eq!(config.grpc.subscription_mode, SubscriptionMode::PerValidator);
+        assert_eq!(config.grpc.stream_pool_size, 1);
+        assert!(config.grpc.replay_on_reconnect);
+        assert_eq!(config.grpc.stall_timeout_secs, 30);
+        assert_eq!(config.grpc.max_slot_lag, 150);
+        assert!(config.grpc.endpoints.is_empty());
+        assert_eq!(config.grpc.endpoint_mode, EndpointMode::Failover);
+        assert_eq!(config.grpc.commitment, GrpcCommitment::Processed);
+        assert!(!config.grpc.track_finality);
+        assert!(!config.grpc.subscribe_block_meta);
+        assert!(!config.grpc.track_missed_votes);
+        assert_eq!(config.grpc.missed_vote_tolerance_slots, 32);
+        assert!(!config.grpc.track_root_lag);
     }
     
     #[test]
@@ -450,6 +950,10 @@ mod tests {
         assert_eq!(config.influxdb.bucket, "vote-latencies-raw");
         assert_eq!(config.influxdb.batch_size, 5000);
         assert_eq!(config.influxdb.flush_interval_ms, 100);
+        assert!(!config.influxdb.short_validator_tag);
+        assert!(config.influxdb.instance.is_none());
+        assert_eq!(config.influxdb.spool_dir, "./data/influx_spool");
+        assert_eq!(config.influxdb.spool_max_mb, 1024);
     }
     
     #[test]
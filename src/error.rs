@@ -140,15 +140,21 @@ impl Error {
     }
 
     /// Check if this is a retryable error
+    ///
+    /// gRPC statuses that retrying can't fix (bad credentials, rejected or
+    /// unsupported requests) are not retryable.
     pub fn is_retryable(&self) -> bool {
-        matches!(
-            self,
-            Error::Network(_)
-                | Error::Rpc(_)
-                | Error::Grpc(_)
-                | Error::Timeout(_)
-                | Error::RateLimit(_)
-        )
+        match self {
+            Error::Grpc(status) => !matches!(
+                status.code(),
+                tonic::Code::Unauthenticated
+                    | tonic::Code::PermissionDenied
+                    | tonic::Code::InvalidArgument
+                    | tonic::Code::Unimplemented
+            ),
+            Error::Network(_) | Error::Rpc(_) | Error::Timeout(_) | Error::RateLimit(_) => true,
+            _ => false,
+        }
     }
 
     /// Get the error category for metrics/logging
@@ -284,6 +290,16 @@ mod tests {
         assert!(!Error::invalid_vote("test").is_retryable());
     }
     
+    #[test]
+    fn test_grpc_status_retryability() {
+        assert!(Error::Grpc(tonic::Status::unavailable("down")).is_retryable());
+        assert!(Error::Grpc(tonic::Status::resource_exhausted("slow down")).is_retryable());
+        
+        assert!(!Error::Grpc(tonic::Status::unauthenticated("bad token")).is_retryable());
+        assert!(!Error::Grpc(tonic::Status::permission_denied("no access")).is_retryable());
+        assert!(!Error::Grpc(tonic::Status::invalid_argument("bad filter")).is_retryable());
+    }
+    
     #[test]
     fn test_error_constructors() {
         let err = Error::validator_not_found("test-pubkey");
@@ -11,14 +11,13 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::broadcast;
 use tokio::signal;
-use tracing::{info, error, trace};
+use tracing::{info, warn, error, trace};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use svlm::config::Config;
+use svlm::models::PipelineEvent;
 use svlm::modules::{ShutdownSignal, Shutdown};
 use svlm::modules::discovery::ValidatorDiscoveryTrait;
-use svlm::modules::subscription::SubscriptionManagerTrait;
-use svlm::modules::parser::VoteParserTrait;
 use svlm::modules::calculator::LatencyCalculatorTrait;
 
 #[derive(Parser)]
@@ -57,6 +56,16 @@ enum Commands {
         #[arg(long)]
         rpc_url: Option<String>,
     },
+    /// Rewrite stored points tagged with truncated pubkeys to use full keys
+    MigrateTags {
+        /// RPC endpoint to resolve the truncated keys against
+        #[arg(long)]
+        rpc_url: Option<String>,
+        
+        /// Only count the points that would be rewritten
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 #[tokio::main]
@@ -94,6 +103,10 @@ async fn main() -> Result<()> {
             info!("Listing validators from: {}", endpoint);
             list_validators(&endpoint).await?;
         }
+        Some(Commands::MigrateTags { rpc_url, dry_run }) => {
+            let endpoint = rpc_url.unwrap_or_else(|| config.solana.rpc_endpoint.clone());
+            migrate_tags(&config, &endpoint, dry_run).await?;
+        }
         None => {
             // Default to running the monitor
             info!("Starting Solana Vote Latency Monitor (default mode)...");
@@ -132,9 +145,10 @@ async fn run_monitor(config: Config) -> Result<()> {
     
     // Initialize storage
     info!("Initializing InfluxDB storage...");
-    let storage = Arc::new(
-        svlm::storage::InfluxDBStorage::new(config.influxdb.clone()).await?
+    let influx_storage = Arc::new(
+        svlm::storage::InfluxDBStorage::new(config.influxdb.clone(), config.solana.network.clone()).await?
     );
+    let storage = Arc::clone(&influx_storage);
     info!("InfluxDB storage initialized successfully");
     
     // Step 2: Create and start the discovery module to fetch validators
@@ -150,44 +164,51 @@ async fn run_monitor(config: Config) -> Result<()> {
     
     // Start the discovery background task
     discovery.start().await?;
-    let discovery = Arc::new(tokio::sync::RwLock::new(discovery));
     
-    // Step 3: Initialize the parser
-    info!("Initializing vote parser...");
-    let parser = Arc::new(svlm::modules::parser::VoteParser::new()?);
+    // Load the leader schedule over the discovery RPC client
+    let mut leader_tracker = svlm::modules::leader_schedule::LeaderScheduleTracker::new(
+        discovery.rpc_client(),
+        shutdown_tx.subscribe(),
+    );
+    if let Err(e) = leader_tracker.start().await {
+        warn!("Leader schedule unavailable, votes will not be attributed to leaders: {}", e);
+    }
+    let leader_schedule = leader_tracker.schedule();
+    let discovery = Arc::new(tokio::sync::RwLock::new(discovery));
     
-    // Step 4: Initialize the calculator with storage
+    // Step 3: Initialize the calculator with storage
     info!("Initializing latency calculator...");
     let mut calculator = svlm::modules::calculator::LatencyCalculator::new(
         config.clone(),
         Some(storage.clone()),
         shutdown_tx.subscribe(),
     ).await?;
-    calculator.start().await?;
-    let calculator = Arc::new(tokio::sync::RwLock::new(calculator));
     
-    // Step 5: Create and start the subscription manager
+    // Step 4: Create and start the subscription manager
     info!("Initializing subscription manager...");
     let subscription_manager = svlm::modules::subscription::SubscriptionManager::new(
         config.clone(),
         shutdown_tx.subscribe(),
     ).await?;
     
-    // Subscribe to all discovered validators
-    let validator_count = validators.len();
-    for validator in validators {
-        if let Err(e) = subscription_manager.subscribe(&validator).await {
-            error!("Failed to subscribe to validator {}: {}", validator.pubkey, e);
-        }
+    // Report vote coverage alongside latency once missed votes are tracked
+    if let Some(detector) = subscription_manager.missed_votes() {
+        calculator.set_missed_vote_detector(detector);
     }
+    calculator.start().await?;
+    let calculator = Arc::new(tokio::sync::RwLock::new(calculator));
+    
+    // Subscribe to the best ranked validators, up to max_subscriptions
+    let plan = subscription_manager.reconcile(&validators).await?;
+    let validator_count = plan.admit.len();
     
     subscription_manager.start().await?;
     let subscription_manager = Arc::new(tokio::sync::RwLock::new(subscription_manager));
     
-    // Step 6: Wire up the data processing pipeline
+    // Step 5: Wire up the data processing pipeline
     // Task 1: Process votes from subscription manager
-    let parser_clone = parser.clone();
     let calculator_clone = calculator.clone();
+    let track_root_lag = config.grpc.track_root_lag;
     let storage = storage as Arc<dyn svlm::modules::storage::StorageManagerTrait>;
     let storage_clone = storage.clone();
     let subscription_manager_for_processor = Arc::clone(&subscription_manager);
@@ -197,10 +218,18 @@ async fn run_monitor(config: Config) -> Result<()> {
         if let Some(mut receiver) = sub_manager.take_receiver() {
             drop(sub_manager); // Release the lock
             
-            while let Some(vote_tx) = receiver.recv().await {
-                // Parse the vote transaction
-                match parser_clone.parse(&vote_tx).await {
-                    Ok(vote_latency) => {
+            while let Some(event) = receiver.recv().await {
+                match event {
+                    PipelineEvent::Vote(vote) => {
+                        // Votes arrive parsed from the stream
+                        let mut vote_latency = vote.latency;
+                        
+                        // Attribute the landing slot to its scheduled leader
+                        // unless block metadata already named it
+                        if vote_latency.leader.is_none() {
+                            vote_latency.leader = leader_schedule.leader(vote_latency.landed_slot);
+                        }
+                        
                         // Calculate metrics (non-blocking, just updates in-memory data)
                         let calc = calculator_clone.read().await;
                         if let Err(e) = calc.calculate(&vote_latency).await {
@@ -210,18 +239,50 @@ async fn run_monitor(config: Config) -> Result<()> {
                         
                         // Store in database using a separate task to avoid blocking the channel
                         let storage_for_task = storage_clone.clone();
-                        let vote_latency_clone = vote_latency.clone();
                         tokio::spawn(async move {
-                            if let Err(e) = storage_for_task.store_vote_latency(&vote_latency_clone).await {
+                            if let Err(e) = storage_for_task.store_vote_latency(&vote_latency).await {
                                 error!("Failed to store vote latency: {}", e);
                             } else {
                                 trace!("Stored vote latency for validator {} slot {}", 
-                                    vote_latency_clone.validator_pubkey, vote_latency_clone.slot);
+                                    vote_latency.validator_pubkey, vote_latency.slot);
+                            }
+                        });
+                    }
+                    PipelineEvent::MissedVote(missed) => {
+                        let storage_for_task = storage_clone.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = storage_for_task.store_missed_vote(&missed).await {
+                                error!("Failed to store missed vote: {}", e);
+                            }
+                        });
+                    }
+                    PipelineEvent::ForkVote(fork_vote) => {
+                        let storage_for_task = storage_clone.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = storage_for_task.store_fork_vote(&fork_vote).await {
+                                error!("Failed to store fork vote: {}", e);
+                            }
+                        });
+                    }
+                    PipelineEvent::HashMismatch(mismatch) => {
+                        let storage_for_task = storage_clone.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = storage_for_task.store_hash_mismatch(&mismatch).await {
+                                error!("Failed to store hash mismatch: {}", e);
                             }
                         });
                     }
-                    Err(e) => {
-                        error!("Failed to parse vote transaction: {}", e);
+                    PipelineEvent::VoteAccount(mut update) => {
+                        if track_root_lag {
+                            calculator_clone.read().await.record_root_lag(&mut update);
+                        }
+                        
+                        let storage_for_task = storage_clone.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = storage_for_task.store_vote_account_update(&update).await {
+                                error!("Failed to store vote account state: {}", e);
+                            }
+                        });
                     }
                 }
             }
@@ -230,6 +291,7 @@ async fn run_monitor(config: Config) -> Result<()> {
     
     // Task 2: Periodically check for new validators
     let discovery_clone = discovery.clone();
+    let calculator_for_updater = calculator.clone();
     let subscription_manager_clone = Arc::clone(&subscription_manager);
     let validator_updater = tokio::spawn(async move {
         let mut interval = tokio::time::interval(Duration::from_secs(60));
@@ -242,10 +304,32 @@ async fn run_monitor(config: Config) -> Result<()> {
                     drop(disc); // Release the lock
                     
                     let sub_mgr = subscription_manager_clone.write().await;
-                    for validator in new_validators {
-                        if let Err(e) = sub_mgr.subscribe(&validator).await {
-                            error!("Failed to subscribe to new validator {}: {}", validator.pubkey, e);
+                    
+                    // Flag monitored validators whose latency tail is beyond the network's
+                    let calc = calculator_for_updater.read().await;
+                    let global = calc.get_global_metrics().await;
+                    if global.p99_ms > 0.0 {
+                        let admission = sub_mgr.admission();
+                        for pubkey in sub_mgr.subscribed_validators() {
+                            if let Some(metrics) = calc.get_validator_metrics(&pubkey).await {
+                                if metrics.p95_ms > global.p99_ms {
+                                    admission.report_anomaly(pubkey);
+                                }
+                            }
+                        }
+                    }
+                    drop(calc);
+                    
+                    // Evict validators that dropped out of discovery or were outranked
+                    match sub_mgr.reconcile(&new_validators).await {
+                        Ok(plan) if track_root_lag => {
+                            let calc = calculator_for_updater.read().await;
+                            for pubkey in &plan.evict {
+                                calc.remove_root_lag(pubkey);
+                            }
                         }
+                        Ok(_) => {}
+                        Err(e) => error!("Failed to update subscriptions: {}", e),
                     }
                 }
                 Err(e) => {
@@ -271,7 +355,6 @@ async fn run_monitor(config: Config) -> Result<()> {
     }
     
     // Cancel background tasks
-    vote_processor.abort();
     validator_updater.abort();
     
     // Stop all modules
@@ -281,12 +364,28 @@ async fn run_monitor(config: Config) -> Result<()> {
     }
     drop(sub_mgr);
     
+    // The vote queue is closed; let the pipeline process what is left in it
+    let mut vote_processor = vote_processor;
+    if tokio::time::timeout(Duration::from_secs(10), &mut vote_processor).await.is_err() {
+        warn!("Vote processor did not drain the vote queue in time");
+        vote_processor.abort();
+    }
+    
     let mut calc = calculator.write().await;
     if let Err(e) = calc.shutdown().await {
         error!("Error shutting down calculator: {}", e);
     }
     drop(calc);
     
+    // Flush buffered points once nothing produces new ones
+    if let Err(e) = influx_storage.shutdown().await {
+        error!("Error shutting down InfluxDB storage: {}", e);
+    }
+    
+    if let Err(e) = leader_tracker.shutdown().await {
+        error!("Error shutting down leader schedule tracker: {}", e);
+    }
+    
     let mut disc = discovery.write().await;
     if let Err(e) = disc.shutdown().await {
         error!("Error shutting down discovery: {}", e);
@@ -370,6 +469,42 @@ async fn list_validators(rpc_url: &str) -> Result<()> {
     Ok(())
 }
 
+/// Rewrite truncated pubkey tags in InfluxDB using the cluster's vote accounts
+async fn migrate_tags(config: &Config, rpc_url: &str, dry_run: bool) -> Result<()> {
+    use svlm::modules::discovery::ValidatorDiscovery;
+    use svlm::storage::tag_migration::ShortKeyMap;
+    
+    info!("Resolving truncated keys against vote accounts from: {}", rpc_url);
+    let vote_accounts: Vec<_> = ValidatorDiscovery::fetch_validators(rpc_url)
+        .await?
+        .into_iter()
+        .map(|(info, _)| (info.pubkey, info.vote_account))
+        .collect();
+    
+    let keys = ShortKeyMap::new(&vote_accounts);
+    println!(
+        "Resolved {} of {} vote accounts ({} with colliding prefixes are left as they are)",
+        keys.pairs.len(),
+        vote_accounts.len(),
+        keys.ambiguous
+    );
+    
+    let storage = svlm::storage::InfluxDBStorage::new(config.influxdb.clone(), config.solana.network.clone()).await?;
+    let report = storage.migrate_short_tags(&keys, dry_run).await?;
+    storage.shutdown().await?;
+    
+    if dry_run {
+        println!("{} points would be rewritten", report.points);
+    } else {
+        println!(
+            "Rewrote {} points and deleted {} truncated series",
+            report.points, report.deleted_series
+        );
+    }
+    
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -12,6 +12,7 @@ use prometheus::{
 };
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 use tracing::{error, info};
 use warp::Filter;
 
@@ -22,6 +23,24 @@ const LATENCY_BUCKETS: &[f64] = &[
     10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0, 10000.0,
 ];
 
+/// Reconnect gap histogram buckets (in slots)
+const GAP_BUCKETS: &[f64] = &[
+    1.0, 2.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0,
+];
+
+/// Root lag histogram buckets (in slots)
+const ROOT_LAG_BUCKETS: &[f64] = &[
+    1.0, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0, 128.0, 256.0, 512.0,
+];
+
+/// InfluxDB write batch size histogram buckets (in points)
+const BATCH_POINTS_BUCKETS: &[f64] = &[
+    10.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0, 10000.0, 25000.0,
+];
+
+/// Failed attempts per InfluxDB write batch histogram buckets
+const WRITE_FAILURE_BUCKETS: &[f64] = &[0.0, 1.0, 2.0, 3.0, 4.0];
+
 /// Global metrics registry
 pub static METRICS: Lazy<Metrics> = Lazy::new(|| {
     Metrics::new().expect("Failed to initialize metrics")
@@ -64,6 +83,54 @@ pub struct Metrics {
     
     /// Current CPU usage
     pub cpu_usage: GaugeVec,
+    
+    /// Slots missed while a gRPC stream was reconnecting
+    pub reconnect_gap_slots: HistogramVec,
+    
+    /// Votes received during from_slot replay after a reconnect
+    pub replay_votes: IntCounterVec,
+    
+    /// Race mode vote deliveries per endpoint (first or late)
+    pub endpoint_votes: IntCounterVec,
+    
+    /// How far late race mode deliveries trailed the first copy
+    pub endpoint_vote_delay: HistogramVec,
+    
+    /// Forwarded votes by landing slot status
+    pub vote_landings: IntCounterVec,
+    
+    /// Votes dropped by the vote queue overflow policy
+    pub vote_queue_drops: IntCounterVec,
+    
+    /// Votes waiting in the vote queue
+    pub vote_queue_depth: IntGaugeVec,
+    
+    /// Slots validator roots trailed the cluster's finalized slot
+    pub root_lag: HistogramVec,
+    
+    /// Latest root lag per validator
+    pub root_lag_current: IntGaugeVec,
+    
+    /// Votes whose bank hash disagreed with the cluster's
+    pub hash_mismatches: IntCounterVec,
+    
+    /// Batches and bytes waiting in the InfluxDB write spool
+    pub influx_spool_depth: IntGaugeVec,
+    
+    /// InfluxDB batches spooled, replayed and evicted
+    pub influx_spool_batches: IntCounterVec,
+    
+    /// InfluxDB batches dropped because InfluxDB rejected them
+    pub influx_rejected_batches: IntCounterVec,
+    
+    /// Time per InfluxDB write request, per write worker
+    pub influx_write_duration: HistogramVec,
+    
+    /// Points per InfluxDB write batch, per write worker
+    pub influx_write_batch_points: HistogramVec,
+    
+    /// Failed attempts per InfluxDB write batch, per write worker
+    pub influx_write_failures: HistogramVec,
 }
 
 impl Metrics {
@@ -142,6 +209,108 @@ impl Metrics {
                 "Current CPU usage percentage",
                 &["core"]
             )?,
+            
+            reconnect_gap_slots: register_histogram_vec!(
+                "svlm_reconnect_gap_slots",
+                "Slots missed while a gRPC stream was reconnecting",
+                &["outcome"],
+                GAP_BUCKETS.to_vec()
+            )?,
+            
+            replay_votes: register_int_counter_vec!(
+                "svlm_replay_votes_total",
+                "Votes received during from_slot replay after a reconnect",
+                &["result"]
+            )?,
+            
+            endpoint_votes: register_int_counter_vec!(
+                "svlm_endpoint_votes_total",
+                "Vote deliveries per gRPC endpoint in race mode",
+                &["endpoint", "result"]
+            )?,
+            
+            endpoint_vote_delay: register_histogram_vec!(
+                "svlm_endpoint_vote_delay_ms",
+                "Delay of late vote deliveries behind the first endpoint in race mode",
+                &["endpoint"],
+                LATENCY_BUCKETS.to_vec()
+            )?,
+            
+            vote_landings: register_int_counter_vec!(
+                "svlm_vote_landings_total",
+                "Forwarded votes by commitment status of their landing slot",
+                &["status"]
+            )?,
+            
+            vote_queue_drops: register_int_counter_vec!(
+                "svlm_vote_queue_drops_total",
+                "Votes dropped because the vote queue was full",
+                &["validator", "reason"]
+            )?,
+            
+            vote_queue_depth: register_int_gauge_vec!(
+                "svlm_vote_queue_depth",
+                "Votes waiting in the vote queue",
+                &["location"]
+            )?,
+            
+            root_lag: register_histogram_vec!(
+                "svlm_root_lag_slots",
+                "Slots a validator's root trailed the cluster's finalized slot",
+                &["validator"],
+                ROOT_LAG_BUCKETS.to_vec()
+            )?,
+            
+            root_lag_current: register_int_gauge_vec!(
+                "svlm_root_lag_current_slots",
+                "Latest slots a validator's root trailed the cluster's finalized slot",
+                &["validator"]
+            )?,
+            
+            hash_mismatches: register_int_counter_vec!(
+                "svlm_hash_mismatches_total",
+                "Votes whose bank hash disagreed with the hash the cluster voted for",
+                &["validator"]
+            )?,
+            
+            influx_spool_depth: register_int_gauge_vec!(
+                "svlm_influx_spool_depth",
+                "Batches and bytes waiting in the InfluxDB write spool",
+                &["unit"]
+            )?,
+            
+            influx_spool_batches: register_int_counter_vec!(
+                "svlm_influx_spool_batches_total",
+                "InfluxDB write batches spooled to disk, replayed from it or evicted from it",
+                &["outcome"]
+            )?,
+            
+            influx_rejected_batches: register_int_counter_vec!(
+                "svlm_influx_rejected_batches_total",
+                "InfluxDB write batches dropped because InfluxDB rejected them",
+                &["source"]
+            )?,
+            
+            influx_write_duration: register_histogram_vec!(
+                "svlm_influx_write_duration_ms",
+                "Time per InfluxDB write request",
+                &["worker", "result"],
+                LATENCY_BUCKETS.to_vec()
+            )?,
+            
+            influx_write_batch_points: register_histogram_vec!(
+                "svlm_influx_write_batch_points",
+                "Points per InfluxDB write batch",
+                &["worker"],
+                BATCH_POINTS_BUCKETS.to_vec()
+            )?,
+            
+            influx_write_failures: register_histogram_vec!(
+                "svlm_influx_write_failed_attempts",
+                "Failed write attempts per InfluxDB write batch",
+                &["worker"],
+                WRITE_FAILURE_BUCKETS.to_vec()
+            )?,
         })
     }
 
@@ -173,6 +342,20 @@ impl Metrics {
             .with_label_values(&["active"])
             .set(count);
     }
+    
+    /// Update the number of validators queued for a subscription
+    pub fn set_queued_subscriptions(&self, count: i64) {
+        self.active_subscriptions
+            .with_label_values(&["queued"])
+            .set(count);
+    }
+    
+    /// Update the number of gRPC streams in a given state
+    pub fn set_streams_in_state(&self, state: &str, count: i64) {
+        self.active_subscriptions
+            .with_label_values(&[state])
+            .set(count);
+    }
 
     /// Update total validators count
     pub fn set_validators_total(&self, active: i64, inactive: i64) {
@@ -236,6 +419,125 @@ impl Metrics {
             .with_label_values(&[core])
             .set(percent);
     }
+    
+    /// Record the slot gap left by a reconnect
+    pub fn record_reconnect_gap(&self, replayed: bool, gap_slots: u64) {
+        let outcome = if replayed { "replayed" } else { "unrecoverable" };
+        self.reconnect_gap_slots
+            .with_label_values(&[outcome])
+            .observe(gap_slots as f64);
+    }
+    
+    /// Record a vote received while replaying (`recovered` or `duplicate`)
+    pub fn record_replay_vote(&self, result: &str) {
+        self.replay_votes
+            .with_label_values(&[result])
+            .inc();
+    }
+    
+    /// Record a forwarded vote by its landing slot status
+    pub fn record_vote_landing(&self, status: &str) {
+        self.vote_landings
+            .with_label_values(&[status])
+            .inc();
+    }
+    
+    /// Record a vote dropped by the vote queue overflow policy
+    pub fn record_vote_drop(&self, validator: &str, reason: &str) {
+        self.vote_queue_drops
+            .with_label_values(&[validator, reason])
+            .inc();
+    }
+    
+    /// Update the vote queue depth, in memory and spilled to disk
+    pub fn set_vote_queue_depth(&self, memory: usize, spilled: usize) {
+        self.vote_queue_depth
+            .with_label_values(&["memory"])
+            .set(memory as i64);
+        self.vote_queue_depth
+            .with_label_values(&["spill"])
+            .set(spilled as i64);
+    }
+    
+    /// Record how far a validator's root trailed the cluster's finalized slot
+    pub fn record_root_lag(&self, validator: &str, lag_slots: u64) {
+        self.root_lag
+            .with_label_values(&[validator])
+            .observe(lag_slots as f64);
+        self.root_lag_current
+            .with_label_values(&[validator])
+            .set(lag_slots as i64);
+    }
+    
+    /// Drop the root lag series of a validator that is no longer monitored
+    pub fn remove_root_lag(&self, validator: &str) {
+        // Never recorded if the validator's root was unknown
+        let _ = self.root_lag.remove_label_values(&[validator]);
+        let _ = self.root_lag_current.remove_label_values(&[validator]);
+    }
+    
+    /// Record a vote whose bank hash disagreed with the cluster's
+    pub fn record_hash_mismatch(&self, validator: &str) {
+        self.hash_mismatches
+            .with_label_values(&[validator])
+            .inc();
+    }
+    
+    /// Update the InfluxDB write spool depth
+    pub fn set_influx_spool_depth(&self, batches: usize, bytes: u64) {
+        self.influx_spool_depth
+            .with_label_values(&["batches"])
+            .set(batches as i64);
+        self.influx_spool_depth
+            .with_label_values(&["bytes"])
+            .set(bytes as i64);
+    }
+    
+    /// Record InfluxDB batches spooled, replayed or evicted
+    pub fn record_influx_spool(&self, outcome: &str, batches: usize) {
+        self.influx_spool_batches
+            .with_label_values(&[outcome])
+            .inc_by(batches as u64);
+    }
+    
+    /// Record an InfluxDB batch dropped because InfluxDB rejected it
+    pub fn record_influx_rejected(&self, source: &str) {
+        self.influx_rejected_batches
+            .with_label_values(&[source])
+            .inc();
+    }
+    
+    /// Record one InfluxDB write request of a write worker
+    pub fn record_influx_write(&self, worker: usize, success: bool, duration: Duration) {
+        let result = if success { "success" } else { "error" };
+        self.influx_write_duration
+            .with_label_values(&[&worker.to_string(), result])
+            .observe(duration.as_secs_f64() * 1000.0);
+    }
+    
+    /// Record a batch handled by a write worker and its failed attempts
+    pub fn record_influx_batch(&self, worker: usize, points: usize, failed_attempts: u32) {
+        let worker = worker.to_string();
+        self.influx_write_batch_points
+            .with_label_values(&[&worker])
+            .observe(points as f64);
+        self.influx_write_failures
+            .with_label_values(&[&worker])
+            .observe(f64::from(failed_attempts));
+    }
+    
+    /// Record a race mode vote delivery; `behind` is set for late copies
+    pub fn record_endpoint_vote(&self, endpoint: &str, behind: Option<Duration>) {
+        let result = if behind.is_some() { "late" } else { "first" };
+        self.endpoint_votes
+            .with_label_values(&[endpoint, result])
+            .inc();
+        if let Some(behind) = behind {
+            self.endpoint_vote_delay
+                .with_label_values(&[endpoint])
+                .observe(behind.as_secs_f64() * 1000.0);
+        }
+    }
 }
 
 /// Metrics server for Prometheus scraping
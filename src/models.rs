@@ -7,6 +7,26 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 
+/// Latency in slots up to which a vote earns the maximum credits
+pub const VOTE_CREDITS_GRACE_SLOTS: u8 = 2;
+
+/// Credits earned by a vote that lands within the grace period
+pub const VOTE_CREDITS_MAXIMUM_PER_SLOT: u64 = 16;
+
+/// Credits a vote earns under timely vote credits
+///
+/// Votes landing within `VOTE_CREDITS_GRACE_SLOTS` earn the maximum; each
+/// further slot of latency costs one credit, down to a minimum of one.
+pub fn vote_credits(latency_slots: u8) -> u64 {
+    // Same-slot votes are not possible on chain and are credited as legacy votes
+    if latency_slots == 0 {
+        return 1;
+    }
+    
+    let late_by = latency_slots.saturating_sub(VOTE_CREDITS_GRACE_SLOTS) as u64;
+    VOTE_CREDITS_MAXIMUM_PER_SLOT.saturating_sub(late_by).max(1)
+}
+
 /// Information about a validator
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidatorInfo {
@@ -27,6 +47,14 @@ pub struct ValidatorInfo {
     
     /// gRPC endpoint for subscriptions
     pub grpc_endpoint: Option<String>,
+    
+    /// Activated stake in lamports at the last discovery
+    #[serde(default)]
+    pub activated_stake: u64,
+    
+    /// Whether the validator was delinquent at the last discovery
+    #[serde(default)]
+    pub delinquent: bool,
 }
 
 /// A vote transaction from a validator
@@ -59,6 +87,298 @@ pub struct VoteTransaction {
     /// The slot where this vote transaction will land
     #[serde(default)]
     pub landed_slot: Option<u64>,
+    
+    /// Commitment status of the landing slot when the vote was forwarded
+    #[serde(default)]
+    pub landing_status: LandingStatus,
+}
+
+/// Event sent from the subscription streams to the processing pipeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PipelineEvent {
+    /// A vote parsed on the stream
+    Vote(VoteEvent),
+    
+    /// A confirmed slot a monitored validator never voted on
+    MissedVote(MissedVote),
+    
+    /// A decoded vote account update
+    VoteAccount(VoteAccountUpdate),
+    
+    /// A vote for slots that ended up off the finalized chain
+    ForkVote(ForkVote),
+    
+    /// A vote whose bank hash disagrees with the cluster's for the same slot
+    HashMismatch(HashMismatch),
+}
+
+/// A parsed vote with the stream metadata it arrived with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoteEvent {
+    /// Parsed vote and its latency
+    pub latency: VoteLatency,
+    
+    /// When the provider emitted the update (`SubscribeUpdate.created_at`)
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    
+    /// When the update was received from the stream
+    pub received_at: DateTime<Utc>,
+    
+    /// Highest slot seen across streams when the vote arrived
+    pub tip_slot: u64,
+    
+    /// Endpoint that delivered the vote
+    pub endpoint: String,
+}
+
+/// Vote state decoded from a vote account update
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VoteAccountState {
+    /// Validator identity pubkey
+    pub validator_pubkey: Pubkey,
+    
+    /// Vote account pubkey
+    pub vote_pubkey: Pubkey,
+    
+    /// Slot of the account update
+    pub slot: u64,
+    
+    /// Node identity recorded in the vote account
+    pub node_pubkey: Pubkey,
+    
+    /// Commission in percent
+    pub commission: u8,
+    
+    /// Highest rooted slot
+    pub root_slot: Option<u64>,
+    
+    /// Most recent slot in the tower
+    pub last_voted_slot: Option<u64>,
+    
+    /// Number of votes in the tower
+    pub lockout_depth: u32,
+    
+    /// Epoch of the latest epoch credits entry
+    pub epoch: Option<u64>,
+    
+    /// Credits earned in that epoch
+    pub epoch_credits: u64,
+    
+    /// Credits earned over the account's lifetime
+    pub total_credits: u64,
+    
+    /// Slot of the last vote timestamp
+    pub last_timestamp_slot: u64,
+    
+    /// Last vote timestamp (Unix seconds)
+    pub last_timestamp: i64,
+    
+    /// When the update was received
+    pub observed_at: DateTime<Utc>,
+}
+
+/// Changes between two consecutive updates of a vote account
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct VoteStateChange {
+    /// Slots between the two updates
+    pub slots_elapsed: u64,
+    
+    /// Slots the root advanced by
+    pub root_advance_slots: u64,
+    
+    /// Root advancement per elapsed slot (about 1.0 for a healthy validator)
+    pub root_advance_rate: f64,
+    
+    /// Lifetime credits earned between the updates
+    pub credits_delta: u64,
+    
+    /// Commission before the update, when it changed
+    pub previous_commission: Option<u8>,
+}
+
+/// A vote account update with the changes since the previous one
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VoteAccountUpdate {
+    /// Decoded vote state
+    pub state: VoteAccountState,
+    
+    /// Changes since the previous update, unless this is the first one seen
+    pub change: Option<VoteStateChange>,
+    
+    /// Highest cluster finalized slot seen when the update arrived
+    #[serde(default)]
+    pub finalized_slot: Option<u64>,
+    
+    /// Slots the root trails the finalized slot (filled by the calculator)
+    #[serde(default)]
+    pub root_lag_slots: Option<u64>,
+}
+
+/// A vote for slots that did not make it onto the finalized chain
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ForkVote {
+    /// Validator identity pubkey
+    pub validator_pubkey: Pubkey,
+    
+    /// Vote account pubkey
+    pub vote_pubkey: Pubkey,
+    
+    /// Highest abandoned slot the vote was cast for
+    pub slot: u64,
+    
+    /// All abandoned slots the vote was cast for
+    pub fork_slots: Vec<u64>,
+    
+    /// Bank hash the vote was cast for
+    pub vote_hash: Option<String>,
+    
+    /// Slot the vote transaction landed in
+    pub landed_slot: u64,
+    
+    /// Commitment status of the landing slot
+    pub landing_status: LandingStatus,
+    
+    /// Vote transaction signature
+    pub signature: String,
+    
+    /// When the fork was detected
+    pub detected_at: DateTime<Utc>,
+}
+
+impl ForkVote {
+    /// Fork vote event for a vote with abandoned voted-on slots
+    pub fn from_vote(vote: &VoteLatency) -> Option<Self> {
+        let slot = vote.fork_slots.iter().max().copied()?;
+        Some(Self {
+            validator_pubkey: vote.validator_pubkey,
+            vote_pubkey: vote.vote_pubkey,
+            slot,
+            fork_slots: vote.fork_slots.clone(),
+            vote_hash: vote.vote_hash.clone(),
+            landed_slot: vote.landed_slot,
+            landing_status: vote.landing_status,
+            signature: vote.signature.clone(),
+            detected_at: Utc::now(),
+        })
+    }
+}
+
+/// A vote whose bank hash disagrees with the hash the cluster voted for the same slot
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HashMismatch {
+    /// Validator identity pubkey
+    pub validator_pubkey: Pubkey,
+    
+    /// Vote account pubkey
+    pub vote_pubkey: Pubkey,
+    
+    /// Slot the hashes belong to
+    pub slot: u64,
+    
+    /// Bank hash the validator voted for
+    pub vote_hash: String,
+    
+    /// Bank hash most monitored validators voted for
+    pub cluster_hash: String,
+    
+    /// Validators that agreed on the cluster hash
+    pub agreeing_votes: u64,
+    
+    /// Vote transaction signature
+    pub signature: String,
+    
+    /// When the mismatch was detected
+    pub detected_at: DateTime<Utc>,
+}
+
+/// A confirmed slot a validator did not vote on within the tolerance window
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MissedVote {
+    /// Validator identity pubkey
+    pub validator_pubkey: Pubkey,
+    
+    /// Vote account pubkey
+    pub vote_pubkey: Pubkey,
+    
+    /// Confirmed slot that was not voted on
+    pub slot: u64,
+    
+    /// Validator's vote coverage including this miss
+    pub coverage: VoteCoverage,
+    
+    /// When the miss was detected
+    pub detected_at: DateTime<Utc>,
+}
+
+/// Confirmed slots a validator was expected to vote on, and how many it did
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct VoteCoverage {
+    /// Confirmed slots checked since the validator was first seen voting
+    pub expected_slots: u64,
+    
+    /// Of those, slots the validator voted on
+    pub voted_slots: u64,
+    
+    /// Confirmed slots not counted because no stream carrying the validator was up
+    #[serde(default)]
+    pub unobserved_slots: u64,
+}
+
+impl VoteCoverage {
+    /// Confirmed slots the validator never voted on
+    pub fn missed_votes(&self) -> u64 {
+        self.expected_slots - self.voted_slots
+    }
+    
+    /// Share of confirmed slots voted on, in percent (100 before any slot is checked)
+    pub fn coverage_pct(&self) -> f64 {
+        if self.expected_slots == 0 {
+            return 100.0;
+        }
+        self.voted_slots as f64 * 100.0 / self.expected_slots as f64
+    }
+}
+
+/// Commitment status of the slot a vote landed in
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum LandingStatus {
+    /// Landing slot was processed; it may still be forked out
+    #[default]
+    Processed,
+    
+    /// Landing slot was optimistically confirmed
+    Confirmed,
+    
+    /// Landing slot was finalized
+    Finalized,
+    
+    /// Landing slot was skipped by the finalized chain
+    Skipped,
+}
+
+impl LandingStatus {
+    /// Label used in storage tags and metrics
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LandingStatus::Processed => "processed",
+            LandingStatus::Confirmed => "confirmed",
+            LandingStatus::Finalized => "finalized",
+            LandingStatus::Skipped => "skipped",
+        }
+    }
+    
+    /// Parse a label produced by `as_str`
+    pub fn from_label(label: &str) -> Option<Self> {
+        match label {
+            "processed" => Some(LandingStatus::Processed),
+            "confirmed" => Some(LandingStatus::Confirmed),
+            "finalized" => Some(LandingStatus::Finalized),
+            "skipped" => Some(LandingStatus::Skipped),
+            _ => None,
+        }
+    }
 }
 
 /// Calculated vote latency information
@@ -73,13 +393,14 @@ pub struct VoteLatency {
     /// Slot number
     pub slot: u64,
     
-    /// When the vote was created
+    /// When the vote landed (the start of its landing slot), or when it was
+    /// received if the slot start wasn't seen
     pub vote_timestamp: DateTime<Utc>,
     
     /// When we received the vote
     pub received_timestamp: DateTime<Utc>,
     
-    /// Calculated latency in milliseconds
+    /// Calculated latency in milliseconds, from landing to receipt
     /// @deprecated Use latency_slots instead for accurate slot-based latency
     pub latency_ms: u64,
     
@@ -97,6 +418,71 @@ pub struct VoteLatency {
     /// Each value represents the latency in slots, capped at 255
     /// @deprecated Use latency_slot for single value storage
     pub latency_slots: Vec<u8>,
+    
+    /// Commitment status of the landing slot
+    #[serde(default)]
+    pub landing_status: LandingStatus,
+    
+    /// Wall-clock breakdown of `latency_ms`, where the timestamps are known
+    #[serde(default)]
+    pub breakdown: LatencyBreakdown,
+    
+    /// Slot latency in milliseconds, from the voted slot to the landing slot
+    #[serde(default)]
+    pub slot_latency_ms: Option<i64>,
+    
+    /// Leader of the landing slot
+    #[serde(default)]
+    pub leader: Option<Pubkey>,
+    
+    /// Bank hash the vote was cast for (the hash of its highest voted slot)
+    #[serde(default)]
+    pub vote_hash: Option<String>,
+    
+    /// Voted-on slots that did not make it onto the finalized chain
+    #[serde(default)]
+    pub fork_slots: Vec<u64>,
+}
+
+/// Wall-clock latency of a vote split at the landing slot and the provider
+///
+/// Each stage is measured between clocks on different machines (the voting
+/// validator, the provider's node and this host), so small negative values
+/// are possible when the clocks disagree.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LatencyBreakdown {
+    /// Vote creation (the vote's own timestamp) to the start of its landing slot
+    ///
+    /// Only accurate to the second: validators truncate the timestamp to
+    /// whole seconds and only include it when the second changes, so this
+    /// reads up to 999 ms high and is missing for many votes.
+    pub create_to_land_ms: Option<i64>,
+    
+    /// Start of the landing slot to the provider emitting the update
+    pub land_to_emit_ms: Option<i64>,
+    
+    /// Provider emitting the update to it being received here
+    pub emit_to_receive_ms: Option<i64>,
+}
+
+impl LatencyBreakdown {
+    /// Split the latency at whichever timestamps are known
+    pub fn from_timestamps(
+        vote_created_at: Option<DateTime<Utc>>,
+        landed_at: Option<DateTime<Utc>>,
+        emitted_at: Option<DateTime<Utc>>,
+        received_at: DateTime<Utc>,
+    ) -> Self {
+        let between = |from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>| {
+            Some((to? - from?).num_milliseconds())
+        };
+        
+        Self {
+            create_to_land_ms: between(vote_created_at, landed_at),
+            land_to_emit_ms: between(landed_at, emitted_at),
+            emit_to_receive_ms: between(emitted_at, Some(received_at)),
+        }
+    }
 }
 
 /// Aggregated latency metrics
@@ -153,6 +539,50 @@ pub struct LatencyMetrics {
     /// Number of votes with 3+ slots latency
     pub votes_3plus_slots: u64,
     
+    /// Mean slot latency in milliseconds, from slot times where known
+    #[serde(default)]
+    pub mean_slot_latency_ms: f64,
+    
+    /// Median slot latency in milliseconds
+    #[serde(default)]
+    pub median_slot_latency_ms: f64,
+    
+    /// 95th percentile slot latency in milliseconds
+    #[serde(default)]
+    pub p95_slot_latency_ms: f64,
+    
+    /// Mean credits earned per voted slot
+    #[serde(default)]
+    pub mean_credits: f32,
+    
+    /// Epoch the credit totals below belong to
+    #[serde(default)]
+    pub epoch: u64,
+    
+    /// Credits earned by landed votes so far this epoch
+    #[serde(default)]
+    pub epoch_credits: u64,
+    
+    /// Credits those votes could have earned had all landed within the grace period
+    #[serde(default)]
+    pub epoch_max_credits: u64,
+    
+    /// Mean slots the root trailed the cluster's finalized slot
+    #[serde(default)]
+    pub mean_root_lag_slots: f64,
+    
+    /// Maximum slots the root trailed the cluster's finalized slot
+    #[serde(default)]
+    pub max_root_lag_slots: u64,
+    
+    /// Confirmed slots not voted on within the tolerance window
+    #[serde(default)]
+    pub missed_votes: u64,
+    
+    /// Share of confirmed slots voted on, in percent
+    #[serde(default)]
+    pub vote_coverage_pct: f64,
+    
     /// Number of samples
     pub sample_count: u64,
     
@@ -326,6 +756,8 @@ impl ValidatorInfo {
             description: None,
             website: None,
             grpc_endpoint: None,
+            activated_stake: 0,
+            delinquent: false,
         }
     }
 }
@@ -341,7 +773,7 @@ impl VoteLatency {
         received_timestamp: DateTime<Utc>,
         signature: String,
     ) -> Self {
-        let latency_ms = (received_timestamp - vote_timestamp).num_milliseconds() as u64;
+        let latency_ms = (received_timestamp - vote_timestamp).num_milliseconds().max(0) as u64;
         Self {
             validator_pubkey,
             vote_pubkey,
@@ -353,6 +785,12 @@ impl VoteLatency {
             voted_on_slots: vec![slot], // Assume single slot for backward compatibility
             landed_slot: slot, // Assume same slot for backward compatibility
             latency_slots: vec![0], // Zero latency for backward compatibility
+            landing_status: LandingStatus::default(),
+            breakdown: LatencyBreakdown::default(),
+            slot_latency_ms: None,
+            leader: None,
+            vote_hash: None,
+            fork_slots: Vec::new(),
         }
     }
     
@@ -367,7 +805,7 @@ impl VoteLatency {
         signature: String,
         landed_slot: u64,
     ) -> Self {
-        let latency_ms = (received_timestamp - vote_timestamp).num_milliseconds() as u64;
+        let latency_ms = (received_timestamp - vote_timestamp).num_milliseconds().max(0) as u64;
         
         // Calculate single latency value
         let latency_slot = if landed_slot >= voted_on_slot {
@@ -387,6 +825,12 @@ impl VoteLatency {
             voted_on_slots: vec![voted_on_slot],
             landed_slot,
             latency_slots: vec![latency_slot],
+            landing_status: LandingStatus::default(),
+            breakdown: LatencyBreakdown::default(),
+            slot_latency_ms: None,
+            leader: None,
+            vote_hash: None,
+            fork_slots: Vec::new(),
         }
     }
     
@@ -401,7 +845,7 @@ impl VoteLatency {
         voted_on_slots: Vec<u64>,
         landed_slot: u64,
     ) -> Self {
-        let latency_ms = (received_timestamp - vote_timestamp).num_milliseconds() as u64;
+        let latency_ms = (received_timestamp - vote_timestamp).num_milliseconds().max(0) as u64;
         
         // Calculate latency for each voted slot
         let latency_slots: Vec<u8> = voted_on_slots
@@ -429,9 +873,20 @@ impl VoteLatency {
             voted_on_slots,
             landed_slot,
             latency_slots,
+            landing_status: LandingStatus::default(),
+            breakdown: LatencyBreakdown::default(),
+            slot_latency_ms: None,
+            leader: None,
+            vote_hash: None,
+            fork_slots: Vec::new(),
         }
     }
     
+    /// Whether the vote was cast for slots that did not make it onto the finalized chain
+    pub fn is_fork_vote(&self) -> bool {
+        !self.fork_slots.is_empty()
+    }
+    
     /// Get the maximum latency in slots across all voted slots
     pub fn max_latency_slots(&self) -> u8 {
         self.latency_slots.iter().copied().max().unwrap_or(0)
@@ -446,6 +901,16 @@ impl VoteLatency {
         sum as f32 / self.latency_slots.len() as f32
     }
     
+    /// Credits earned across all voted slots
+    pub fn credits(&self) -> u64 {
+        self.latency_slots.iter().map(|&latency| vote_credits(latency)).sum()
+    }
+    
+    /// Credits the voted slots could have earned had they landed within the grace period
+    pub fn max_credits(&self) -> u64 {
+        self.latency_slots.len() as u64 * VOTE_CREDITS_MAXIMUM_PER_SLOT
+    }
+    
     /// Get the single voted slot (for new single-value schema)
     /// Returns the most recent (last) voted slot if multiple exist
     pub fn voted_on_slot(&self) -> u64 {
@@ -460,7 +925,7 @@ impl VoteLatency {
     
     /// Verify that the stored latency matches the calculated latency
     pub fn verify_latency(&self) -> bool {
-        let calculated = (self.received_timestamp - self.vote_timestamp).num_milliseconds() as u64;
+        let calculated = (self.received_timestamp - self.vote_timestamp).num_milliseconds().max(0) as u64;
         calculated == self.latency_ms
     }
     
@@ -540,6 +1005,29 @@ mod tests {
         latency.latency_ms = 100;
         assert!(!latency.verify_latency());
     }
+    
+    #[test]
+    fn test_vote_credits() {
+        assert_eq!(vote_credits(1), 16);
+        assert_eq!(vote_credits(2), 16);
+        assert_eq!(vote_credits(3), 15);
+        assert_eq!(vote_credits(17), 1);
+        assert_eq!(vote_credits(255), 1);
+        
+        let latency = VoteLatency::new_with_slots(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            102,
+            Utc::now(),
+            Utc::now(),
+            "test_signature".to_string(),
+            vec![100, 101, 102],
+            105,
+        );
+        assert_eq!(latency.latency_slots, vec![5, 4, 3]);
+        assert_eq!(latency.credits(), 13 + 14 + 15);
+        assert_eq!(latency.max_credits(), 48);
+    }
 
     #[test]
     fn test_alert_severity_ordering() {
@@ -585,6 +1073,7 @@ mod tests {
             raw_data: vec![1, 2, 3, 4],
             voted_on_slots: vec![12343, 12344, 12345],
             landed_slot: Some(12350),
+            landing_status: LandingStatus::Processed,
         };
         
         assert_eq!(vote_tx.signature, "test_sig");
@@ -760,4 +1249,46 @@ mod tests {
         assert_eq!(latency.latency_slots, vec![0]);
         assert_eq!(latency.latency_ms, 150);
     }
+    
+    #[test]
+    fn test_pipeline_event_round_trip() {
+        let now = Utc::now();
+        let event = PipelineEvent::Vote(VoteEvent {
+            latency: VoteLatency::new_single_vote(
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                999,
+                now,
+                now,
+                "sig".to_string(),
+                1000,
+            ),
+            created_at: Some(now),
+            received_at: now,
+            tip_slot: 1001,
+            endpoint: "http://localhost:10000".to_string(),
+        });
+        
+        // Events are spilled to disk as JSON lines
+        let json = serde_json::to_string(&event).unwrap();
+        let PipelineEvent::Vote(decoded) = serde_json::from_str(&json).unwrap() else {
+            panic!("expected a vote event");
+        };
+        assert_eq!(decoded.latency.landed_slot, 1000);
+        assert_eq!(decoded.created_at, Some(now));
+        assert_eq!(decoded.tip_slot, 1001);
+        
+        let missed = MissedVote {
+            validator_pubkey: Pubkey::new_unique(),
+            vote_pubkey: Pubkey::new_unique(),
+            slot: 1002,
+            coverage: VoteCoverage { expected_slots: 10, voted_slots: 9, unobserved_slots: 0 },
+            detected_at: now,
+        };
+        let json = serde_json::to_string(&PipelineEvent::MissedVote(missed.clone())).unwrap();
+        let PipelineEvent::MissedVote(decoded) = serde_json::from_str(&json).unwrap() else {
+            panic!("expected a missed vote event");
+        };
+        assert_eq!(decoded, missed);
+    }
 }
\ No newline at end of file
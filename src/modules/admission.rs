@@ -0,0 +1,256 @@
+//! Subscription Admission Module
+//!
+//! Decides which discovered validators get one of the `max_subscriptions`
+//! slots. Candidates are ranked by the configured priority (stake, an explicit
+//! priority list, or recent anomalies) with stake as the tie breaker, and the
+//! top of the ranking is monitored. Lower-ranked validators are queued and
+//! reported; when a discovery refresh brings better candidates, the
+//! lowest-ranked active subscriptions are evicted to make room.
+//!
+//! The cap only applies in per-validator mode. Multiplexed validators are
+//! filters on the shared stream pool rather than streams of their own, so
+//! every candidate is admitted.
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use solana_sdk::pubkey::Pubkey;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use crate::config::{AdmissionPriority, GrpcConfig, SubscriptionMode};
+use crate::models::ValidatorInfo;
+
+/// Result of ranking the discovered validators against the active subscriptions
+#[derive(Debug, Clone, Default)]
+pub struct AdmissionPlan {
+    /// Validators to subscribe to
+    pub admit: Vec<ValidatorInfo>,
+    
+    /// Active validators to unsubscribe, by identity pubkey
+    pub evict: Vec<Pubkey>,
+    
+    /// Validators that did not make the cut, best ranked first
+    pub queued: Vec<ValidatorInfo>,
+}
+
+/// Caps active subscriptions and ranks candidates for them
+pub struct AdmissionController {
+    max_subscriptions: usize,
+    priority: AdmissionPriority,
+    priority_validators: HashSet<String>,
+    anomaly_window: Duration,
+    /// Last reported anomaly per validator identity
+    anomalies: Mutex<HashMap<Pubkey, DateTime<Utc>>>,
+    /// Validators waiting for a subscription, best ranked first
+    queued: Mutex<Vec<ValidatorInfo>>,
+}
+
+impl AdmissionController {
+    /// Create a controller from the gRPC configuration
+    pub fn new(grpc: &GrpcConfig) -> Self {
+        Self {
+            max_subscriptions: match grpc.subscription_mode {
+                SubscriptionMode::PerValidator => grpc.max_subscriptions,
+                SubscriptionMode::Multiplexed => usize::MAX,
+            },
+            priority: grpc.admission_priority,
+            priority_validators: grpc.priority_validators.iter().cloned().collect(),
+            anomaly_window: Duration::from_secs(grpc.anomaly_window_secs),
+            anomalies: Mutex::new(HashMap::new()),
+            queued: Mutex::new(Vec::new()),
+        }
+    }
+    
+    /// Maximum number of active subscriptions, unbounded in multiplexed mode
+    pub fn max_subscriptions(&self) -> usize {
+        self.max_subscriptions
+    }
+    
+    /// Record a latency anomaly for a validator
+    pub fn report_anomaly(&self, pubkey: Pubkey) {
+        self.anomalies.lock().insert(pubkey, Utc::now());
+    }
+    
+    /// Whether the validator had an anomaly within the anomaly window
+    fn has_recent_anomaly(&self, pubkey: &Pubkey, now: DateTime<Utc>) -> bool {
+        self.anomalies.lock().get(pubkey).is_some_and(|at| {
+            (now - *at).to_std().unwrap_or_default() <= self.anomaly_window
+        })
+    }
+    
+    /// Whether a validator is on the explicit priority list
+    fn is_prioritized(&self, validator: &ValidatorInfo) -> bool {
+        self.priority_validators.contains(&validator.pubkey.to_string())
+            || self.priority_validators.contains(&validator.vote_account.to_string())
+    }
+    
+    /// Rank candidates against the active subscriptions
+    ///
+    /// The best `max_subscriptions` candidates are kept. Active validators win
+    /// ties so equally ranked validators don't churn between refreshes, and
+    /// active validators that are no longer candidates are evicted.
+    pub fn plan(&self, candidates: &[ValidatorInfo], active: &HashSet<Pubkey>) -> AdmissionPlan {
+        let now = Utc::now();
+        self.anomalies.lock().retain(|_, at| {
+            (now - *at).to_std().unwrap_or_default() <= self.anomaly_window
+        });
+        
+        let mut seen = HashSet::new();
+        let mut ranked: Vec<&ValidatorInfo> = candidates
+            .iter()
+            .filter(|validator| seen.insert(validator.pubkey))
+            .collect();
+        ranked.sort_by_key(|validator| {
+            let preferred = match self.priority {
+                AdmissionPriority::Stake => false,
+                AdmissionPriority::Whitelist => self.is_prioritized(validator),
+                AdmissionPriority::Anomalies => {
+                    validator.delinquent || self.has_recent_anomaly(&validator.pubkey, now)
+                }
+            };
+            (
+                Reverse(preferred),
+                Reverse(validator.activated_stake),
+                Reverse(active.contains(&validator.pubkey)),
+                validator.pubkey,
+            )
+        });
+        
+        let queued_from = ranked.len().min(self.max_subscriptions);
+        let (selected, rest) = ranked.split_at(queued_from);
+        let selected_keys: HashSet<Pubkey> = selected.iter().map(|validator| validator.pubkey).collect();
+        
+        let plan = AdmissionPlan {
+            admit: selected
+                .iter()
+                .filter(|validator| !active.contains(&validator.pubkey))
+                .map(|validator| (*validator).clone())
+                .collect(),
+            evict: active
+                .iter()
+                .filter(|pubkey| !selected_keys.contains(pubkey))
+                .copied()
+                .collect(),
+            queued: rest.iter().map(|validator| (*validator).clone()).collect(),
+        };
+        
+        self.queued.lock().clone_from(&plan.queued);
+        plan
+    }
+    
+    /// Queue a validator that was refused for lack of capacity
+    pub fn queue(&self, validator: &ValidatorInfo) {
+        let mut queued = self.queued.lock();
+        if !queued.iter().any(|entry| entry.pubkey == validator.pubkey) {
+            queued.push(validator.clone());
+        }
+    }
+    
+    /// Drop a validator from the queue once it is subscribed
+    pub fn dequeue(&self, pubkey: &Pubkey) {
+        self.queued.lock().retain(|entry| entry.pubkey != *pubkey);
+    }
+    
+    /// Validators queued but not monitored, best ranked first
+    pub fn queued_validators(&self) -> Vec<ValidatorInfo> {
+        self.queued.lock().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    
+    fn validator(stake: u64) -> ValidatorInfo {
+        let mut info = ValidatorInfo::new(Pubkey::new_unique(), Pubkey::new_unique());
+        info.activated_stake = stake;
+        info
+    }
+    
+    fn controller(max_subscriptions: usize, priority: AdmissionPriority) -> AdmissionController {
+        let mut config = Config::default();
+        config.grpc.max_subscriptions = max_subscriptions;
+        config.grpc.admission_priority = priority;
+        AdmissionController::new(&config.grpc)
+    }
+    
+    #[test]
+    fn test_stake_priority_caps_and_queues() {
+        let controller = controller(2, AdmissionPriority::Stake);
+        let candidates = vec![validator(10), validator(30), validator(20)];
+        
+        let plan = controller.plan(&candidates, &HashSet::new());
+        let admitted: Vec<u64> = plan.admit.iter().map(|v| v.activated_stake).collect();
+        assert_eq!(admitted, vec![30, 20]);
+        assert!(plan.evict.is_empty());
+        assert_eq!(plan.queued.len(), 1);
+        assert_eq!(controller.queued_validators()[0].pubkey, candidates[0].pubkey);
+    }
+    
+    #[test]
+    fn test_better_candidates_evict_lowest() {
+        let controller = controller(2, AdmissionPriority::Stake);
+        let low = validator(10);
+        let mid = validator(20);
+        let high = validator(30);
+        let active: HashSet<Pubkey> = [low.pubkey, mid.pubkey].into_iter().collect();
+        
+        let plan = controller.plan(&[low.clone(), mid.clone(), high.clone()], &active);
+        assert_eq!(plan.admit.len(), 1);
+        assert_eq!(plan.admit[0].pubkey, high.pubkey);
+        assert_eq!(plan.evict, vec![low.pubkey]);
+        
+        // Active validators that disappeared from discovery are evicted too
+        let plan = controller.plan(&[high.clone()], &[high.pubkey, mid.pubkey].into_iter().collect());
+        assert!(plan.admit.is_empty());
+        assert_eq!(plan.evict, vec![mid.pubkey]);
+        assert!(plan.queued.is_empty());
+    }
+    
+    #[test]
+    fn test_whitelist_and_anomaly_priorities() {
+        let listed = validator(1);
+        let big = validator(100);
+        let candidates = vec![big.clone(), listed.clone()];
+        
+        let mut config = Config::default();
+        config.grpc.max_subscriptions = 1;
+        config.grpc.admission_priority = AdmissionPriority::Whitelist;
+        config.grpc.priority_validators = vec![listed.vote_account.to_string()];
+        let plan = AdmissionController::new(&config.grpc).plan(&candidates, &HashSet::new());
+        assert_eq!(plan.admit[0].pubkey, listed.pubkey);
+        
+        let controller = controller(1, AdmissionPriority::Anomalies);
+        assert_eq!(controller.plan(&candidates, &HashSet::new()).admit[0].pubkey, big.pubkey);
+        controller.report_anomaly(listed.pubkey);
+        assert_eq!(controller.plan(&candidates, &HashSet::new()).admit[0].pubkey, listed.pubkey);
+    }
+    
+    #[test]
+    fn test_multiplexed_not_capped() {
+        let mut config = Config::default();
+        config.grpc.max_subscriptions = 1;
+        config.grpc.subscription_mode = SubscriptionMode::Multiplexed;
+        let controller = AdmissionController::new(&config.grpc);
+        
+        let candidates: Vec<ValidatorInfo> = (0..1_500).map(validator).collect();
+        let plan = controller.plan(&candidates, &HashSet::new());
+        assert_eq!(plan.admit.len(), 1_500);
+        assert!(plan.queued.is_empty());
+    }
+    
+    #[test]
+    fn test_equal_rank_keeps_active() {
+        let controller = controller(1, AdmissionPriority::Stake);
+        let a = validator(10);
+        let b = validator(10);
+        
+        for active in [a.pubkey, b.pubkey] {
+            let plan = controller.plan(&[a.clone(), b.clone()], &[active].into_iter().collect());
+            assert!(plan.admit.is_empty());
+            assert!(plan.evict.is_empty());
+        }
+    }
+}
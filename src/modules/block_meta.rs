@@ -0,0 +1,350 @@
+//! Block Meta Module
+//!
+//! Keeps a short in-memory index of recent slots built from the gRPC streams:
+//! when each slot started (from interslot slot updates) and, with the opt-in
+//! block-meta subscription, its block time, blockhash, leader and parent slot.
+//! The index converts slot latency into milliseconds and attributes landing
+//! slots to their leaders.
+//!
+//! Block metadata is only emitted once a block is complete, after the votes
+//! that landed in it, so with the subscription enabled votes are held until
+//! their landing slot is indexed. Votes whose landing slot never gets metadata
+//! (dead or skipped slots) are released after `MAX_META_WAIT_SLOTS`, and
+//! every held vote is released on shutdown.
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::BTreeMap;
+use yellowstone_grpc_proto::prelude::{RewardType, SubscribeUpdateBlockMeta};
+
+use crate::models::{VoteEvent, VoteLatency};
+
+/// Slots behind the newest one that are kept in the index
+pub const SLOT_HISTORY: u64 = 1024;
+
+/// Slots a vote waits for its landing slot's metadata before it is released
+pub const MAX_META_WAIT_SLOTS: u64 = 32;
+
+/// Metadata of a produced block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockMeta {
+    /// Slot of the block
+    pub slot: u64,
+    
+    /// Slot of the parent block
+    pub parent_slot: u64,
+    
+    /// Blockhash, base58 encoded
+    pub blockhash: String,
+    
+    /// Block time set by the leader (second precision)
+    pub block_time: Option<DateTime<Utc>>,
+    
+    /// Leader that produced the block, from its fee reward
+    pub leader: Option<Pubkey>,
+}
+
+impl BlockMeta {
+    /// Build from a Yellowstone block meta update
+    pub fn from_update(update: &SubscribeUpdateBlockMeta) -> Self {
+        let leader = update
+            .rewards
+            .as_ref()
+            .and_then(|rewards| {
+                rewards
+                    .rewards
+                    .iter()
+                    .find(|reward| reward.reward_type == RewardType::Fee as i32)
+            })
+            .and_then(|reward| reward.pubkey.parse().ok());
+        
+        Self {
+            slot: update.slot,
+            parent_slot: update.parent_slot,
+            blockhash: update.blockhash.clone(),
+            block_time: update
+                .block_time
+                .as_ref()
+                .and_then(|time| DateTime::from_timestamp(time.timestamp, 0)),
+            leader,
+        }
+    }
+}
+
+#[derive(Default)]
+struct SlotEntry {
+    /// Earliest time the slot was seen on any stream
+    started_at: Option<DateTime<Utc>>,
+    /// Block metadata, once the block is complete
+    meta: Option<BlockMeta>,
+}
+
+#[derive(Default)]
+struct IndexState {
+    slots: BTreeMap<u64, SlotEntry>,
+    /// Votes waiting for their landing slot's metadata, keyed by landing slot
+    pending: BTreeMap<u64, Vec<VoteEvent>>,
+    highest_slot: u64,
+}
+
+/// Index of recent slot start times and block metadata
+pub struct BlockMetaIndex {
+    state: Mutex<IndexState>,
+    /// Whether block metadata is subscribed, and votes should wait for it
+    hold_votes: bool,
+}
+
+impl BlockMetaIndex {
+    /// Create an empty index; votes are only held when block meta is subscribed
+    pub fn new(hold_votes: bool) -> Self {
+        Self {
+            state: Mutex::new(IndexState::default()),
+            hold_votes,
+        }
+    }
+    
+    /// Record that a slot was seen at `at`, keeping the earliest sighting
+    ///
+    /// Returns held votes that gave up waiting for their metadata.
+    pub fn record_slot_start(&self, slot: u64, at: DateTime<Utc>) -> Vec<VoteEvent> {
+        let mut state = self.state.lock();
+        let entry = state.slots.entry(slot).or_default();
+        entry.started_at = Some(entry.started_at.map_or(at, |seen| seen.min(at)));
+        state.advance(slot)
+    }
+    
+    /// Index a block's metadata
+    ///
+    /// Returns the votes that were waiting for it, enriched, along with held
+    /// votes that gave up waiting.
+    pub fn insert(&self, meta: BlockMeta) -> Vec<VoteEvent> {
+        let mut state = self.state.lock();
+        let slot = meta.slot;
+        state.slots.entry(slot).or_default().meta = Some(meta);
+        
+        let mut released = state.pending.remove(&slot).unwrap_or_default();
+        for vote in &mut released {
+            state.enrich(&mut vote.latency);
+        }
+        released.extend(state.advance(slot));
+        released
+    }
+    
+    /// Enrich a vote, holding it if its landing slot's metadata is still due
+    pub fn hold(&self, mut vote: VoteEvent) -> Option<VoteEvent> {
+        let mut state = self.state.lock();
+        let landed_slot = vote.latency.landed_slot;
+        let indexed = state.slots.get(&landed_slot).is_some_and(|entry| entry.meta.is_some());
+        
+        if self.hold_votes && !indexed && landed_slot + MAX_META_WAIT_SLOTS >= state.highest_slot {
+            state.pending.entry(landed_slot).or_default().push(vote);
+            return None;
+        }
+        
+        state.enrich(&mut vote.latency);
+        Some(vote)
+    }
+    
+    /// When a slot was first seen, if it is recent
+    pub fn started_at(&self, slot: u64) -> Option<DateTime<Utc>> {
+        self.state.lock().slots.get(&slot).and_then(|entry| entry.started_at)
+    }
+    
+    /// Metadata of a recent block
+    pub fn get(&self, slot: u64) -> Option<BlockMeta> {
+        self.state.lock().slots.get(&slot).and_then(|entry| entry.meta.clone())
+    }
+    
+    /// Leader of a recent block
+    pub fn leader(&self, slot: u64) -> Option<Pubkey> {
+        self.state.lock().slots.get(&slot).and_then(|entry| entry.meta.as_ref()?.leader)
+    }
+    
+    /// Release every held vote, enriched with whatever is indexed, e.g. on shutdown
+    pub fn drain(&self) -> Vec<VoteEvent> {
+        let mut state = self.state.lock();
+        let pending = std::mem::take(&mut state.pending);
+        let mut released: Vec<VoteEvent> = pending.into_values().flatten().collect();
+        for vote in &mut released {
+            state.enrich(&mut vote.latency);
+        }
+        released
+    }
+    
+    /// Number of votes waiting for block metadata
+    pub fn pending_votes(&self) -> usize {
+        self.state.lock().pending.values().map(Vec::len).sum()
+    }
+}
+
+impl IndexState {
+    /// Fill in the landing slot's leader and the slot latency in milliseconds
+    ///
+    /// Slot start times have millisecond precision and are preferred; block
+    /// times only have second precision.
+    fn enrich(&self, latency: &mut VoteLatency) {
+        let landed = self.slots.get(&latency.landed_slot);
+        let voted = self.slots.get(&latency.voted_on_slot());
+        
+        if let Some(leader) = landed.and_then(|entry| entry.meta.as_ref()?.leader) {
+            latency.leader = Some(leader);
+        }
+        
+        let started = |entry: Option<&SlotEntry>| entry?.started_at;
+        let block_time = |entry: Option<&SlotEntry>| entry?.meta.as_ref()?.block_time;
+        let between = |from: Option<DateTime<Utc>>, to: Option<DateTime<Utc>>| {
+            Some((to? - from?).num_milliseconds())
+        };
+        latency.slot_latency_ms = between(started(voted), started(landed))
+            .or_else(|| between(block_time(voted), block_time(landed)))
+            .or(latency.slot_latency_ms);
+    }
+    
+    /// Move the tip forward, pruning old slots and releasing votes that waited too long
+    fn advance(&mut self, slot: u64) -> Vec<VoteEvent> {
+        self.highest_slot = self.highest_slot.max(slot);
+        
+        let horizon = self.highest_slot.saturating_sub(SLOT_HISTORY);
+        if self.slots.first_key_value().is_some_and(|(&oldest, _)| oldest < horizon) {
+            self.slots = self.slots.split_off(&horizon);
+        }
+        
+        let cutoff = self.highest_slot.saturating_sub(MAX_META_WAIT_SLOTS);
+        let mut released = Vec::new();
+        if self.pending.first_key_value().is_some_and(|(&landed_slot, _)| landed_slot < cutoff) {
+            let waiting = self.pending.split_off(&cutoff);
+            let stale = std::mem::replace(&mut self.pending, waiting);
+            for mut vote in stale.into_values().flatten() {
+                self.enrich(&mut vote.latency);
+                released.push(vote);
+            }
+        }
+        released
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yellowstone_grpc_proto::prelude::{Reward, Rewards, UnixTimestamp};
+    
+    fn vote(voted_on_slot: u64, landed_slot: u64) -> VoteEvent {
+        let now = Utc::now();
+        VoteEvent {
+            latency: VoteLatency::new_single_vote(
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                voted_on_slot,
+                now,
+                now,
+                "sig".to_string(),
+                landed_slot,
+            ),
+            created_at: None,
+            received_at: now,
+            tip_slot: landed_slot,
+            endpoint: "test".to_string(),
+        }
+    }
+    
+    fn meta(slot: u64, leader: Pubkey) -> BlockMeta {
+        BlockMeta {
+            slot,
+            parent_slot: slot - 1,
+            blockhash: "hash".to_string(),
+            block_time: None,
+            leader: Some(leader),
+        }
+    }
+    
+    #[test]
+    fn test_meta_from_update() {
+        let leader = Pubkey::new_unique();
+        let update = SubscribeUpdateBlockMeta {
+            slot: 100,
+            parent_slot: 98,
+            blockhash: "hash".to_string(),
+            block_time: Some(UnixTimestamp { timestamp: 1_700_000_000 }),
+            rewards: Some(Rewards {
+                rewards: vec![
+                    Reward {
+                        pubkey: Pubkey::new_unique().to_string(),
+                        reward_type: RewardType::Voting as i32,
+                        ..Default::default()
+                    },
+                    Reward {
+                        pubkey: leader.to_string(),
+                        reward_type: RewardType::Fee as i32,
+                        ..Default::default()
+                    },
+                ],
+                num_partitions: None,
+            }),
+            ..Default::default()
+        };
+        
+        let meta = BlockMeta::from_update(&update);
+        assert_eq!(meta.parent_slot, 98);
+        assert_eq!(meta.leader, Some(leader));
+        assert_eq!(meta.block_time.map(|time| time.timestamp()), Some(1_700_000_000));
+    }
+    
+    #[test]
+    fn test_slot_starts_keep_earliest_and_prune() {
+        let index = BlockMetaIndex::new(false);
+        let start = Utc::now();
+        index.record_slot_start(100, start + chrono::Duration::milliseconds(40));
+        index.record_slot_start(100, start);
+        assert_eq!(index.started_at(100), Some(start));
+        assert_eq!(index.started_at(101), None);
+        
+        index.record_slot_start(100 + SLOT_HISTORY + 1, start);
+        assert_eq!(index.started_at(100), None);
+    }
+    
+    #[test]
+    fn test_votes_wait_for_landing_slot_meta() {
+        let index = BlockMetaIndex::new(true);
+        let start = Utc::now();
+        let leader = Pubkey::new_unique();
+        index.record_slot_start(100, start);
+        index.record_slot_start(102, start + chrono::Duration::milliseconds(820));
+        
+        assert!(index.hold(vote(100, 102)).is_none());
+        assert_eq!(index.pending_votes(), 1);
+        
+        let released = index.insert(meta(102, leader));
+        assert_eq!(released.len(), 1);
+        assert_eq!(released[0].latency.leader, Some(leader));
+        assert_eq!(released[0].latency.slot_latency_ms, Some(820));
+        
+        // Later votes for an indexed slot pass straight through
+        assert_eq!(index.hold(vote(101, 102)).unwrap().latency.leader, Some(leader));
+    }
+    
+    #[test]
+    fn test_votes_released_when_meta_never_arrives() {
+        let index = BlockMetaIndex::new(true);
+        assert!(index.hold(vote(99, 100)).is_none());
+        
+        let released = index.record_slot_start(100 + MAX_META_WAIT_SLOTS + 1, Utc::now());
+        assert_eq!(released.len(), 1);
+        assert_eq!(released[0].latency.leader, None);
+        assert_eq!(index.pending_votes(), 0);
+        
+        // Held votes are handed back on shutdown instead of being lost
+        let start = Utc::now();
+        index.record_slot_start(200, start);
+        index.record_slot_start(201, start + chrono::Duration::milliseconds(400));
+        assert!(index.hold(vote(200, 201)).is_none());
+        let drained = index.drain();
+        assert_eq!(drained.len(), 1);
+        assert_eq!(drained[0].latency.slot_latency_ms, Some(400));
+        assert_eq!(index.pending_votes(), 0);
+        
+        // Without the subscription votes are never held
+        let index = BlockMetaIndex::new(false);
+        assert!(index.hold(vote(99, 100)).is_some());
+    }
+}
@@ -7,6 +7,7 @@
 use crate::error::Result;
 use async_trait::async_trait;
 use dashmap::DashMap;
+use solana_sdk::clock::DEFAULT_SLOTS_PER_EPOCH;
 use solana_sdk::pubkey::Pubkey;
 use std::collections::VecDeque;
 use std::sync::Arc;
@@ -16,7 +17,8 @@ use tokio::select;
 use tracing::{info, trace};
 
 use crate::Config;
-use crate::models::{LatencyMetrics, VoteLatency};
+use crate::models::{vote_credits, LandingStatus, LatencyMetrics, VoteAccountUpdate, VoteCoverage, VoteLatency};
+use crate::modules::missed_votes::MissedVoteDetector;
 use crate::modules::{Shutdown, ShutdownSignal};
 
 /// Trait for latency calculation implementations
@@ -38,6 +40,10 @@ pub struct LatencyCalculator {
     window_size: usize,
     /// Per-validator metrics
     validator_metrics: Arc<DashMap<Pubkey, ValidatorMetricsData>>,
+    /// Per-validator root lags, most recent last
+    root_lags: Arc<DashMap<Pubkey, VecDeque<u64>>>,
+    /// Vote coverage source, when missed votes are tracked
+    missed_votes: Option<Arc<MissedVoteDetector>>,
     /// Global metrics
     global_metrics: Arc<RwLock<GlobalMetricsData>>,
     /// Configuration
@@ -54,14 +60,86 @@ pub struct LatencyCalculator {
 struct ValidatorMetricsData {
     latencies: VecDeque<u64>,
     slot_latencies: VecDeque<Vec<u8>>,  // Store slot-based latencies
+    slot_latencies_ms: VecDeque<u64>,  // Slot latencies converted from slot times
+    credits: EpochCredits,
     total_votes: u64,
     last_update: chrono::DateTime<chrono::Utc>,
 }
 
+/// Vote credits earned within one epoch
+#[derive(Debug, Clone, Copy, Default)]
+struct EpochCredits {
+    epoch: u64,
+    earned: u64,
+    max: u64,
+}
+
+impl EpochCredits {
+    /// Add a vote's credits, starting over when the vote belongs to a newer epoch
+    fn record(&mut self, vote: &VoteLatency) {
+        let epoch = epoch_of(vote.voted_on_slot());
+        if epoch < self.epoch {
+            return;
+        }
+        if epoch > self.epoch {
+            *self = Self { epoch, ..Self::default() };
+        }
+        self.earned += vote.credits();
+        self.max += vote.max_credits();
+    }
+    
+    /// Copy the totals into a metrics snapshot
+    fn apply(&self, metrics: &mut LatencyMetrics) {
+        metrics.epoch = self.epoch;
+        metrics.epoch_credits = self.earned;
+        metrics.epoch_max_credits = self.max;
+    }
+}
+
+/// Epoch of a slot, assuming the fixed-length epochs (no warmup) all public clusters use
+fn epoch_of(slot: u64) -> u64 {
+    slot / DEFAULT_SLOTS_PER_EPOCH
+}
+
+/// Slots the root of a vote account update trails the cluster's finalized slot
+///
+/// A root ahead of our view of the finalized slot counts as no lag.
+fn root_lag(update: &VoteAccountUpdate) -> Option<u64> {
+    let root_slot = update.state.root_slot?;
+    let finalized_slot = update.finalized_slot?;
+    Some(finalized_slot.saturating_sub(root_slot))
+}
+
+/// Copy root lag statistics into a metrics snapshot
+fn apply_root_lags<'a>(lags: impl IntoIterator<Item = &'a u64>, metrics: &mut LatencyMetrics) {
+    let (sum, count, max) = lags
+        .into_iter()
+        .fold((0u64, 0u64, 0u64), |(sum, count, max), &lag| (sum + lag, count + 1, max.max(lag)));
+    if count > 0 {
+        metrics.mean_root_lag_slots = sum as f64 / count as f64;
+        metrics.max_root_lag_slots = max;
+    }
+}
+
+/// Fill in slot latency statistics in milliseconds
+fn apply_slot_latencies_ms(latencies: &[u64], metrics: &mut LatencyMetrics) {
+    let stats = LatencyCalculator::calculate_stats(latencies);
+    metrics.mean_slot_latency_ms = stats.mean_ms;
+    metrics.median_slot_latency_ms = stats.median_ms;
+    metrics.p95_slot_latency_ms = stats.p95_ms;
+}
+
+/// Fill in the missed votes and coverage share of a vote coverage
+fn apply_coverage(coverage: &VoteCoverage, metrics: &mut LatencyMetrics) {
+    metrics.missed_votes = coverage.missed_votes();
+    metrics.vote_coverage_pct = coverage.coverage_pct();
+}
+
 /// Data structure for tracking global metrics
 struct GlobalMetricsData {
     all_latencies: VecDeque<u64>,
     all_slot_latencies: VecDeque<Vec<u8>>,  // Store all slot latencies
+    all_slot_latencies_ms: VecDeque<u64>,
     total_votes: u64,
     validator_count: usize,
     current_metrics: Option<LatencyMetrics>,
@@ -72,6 +150,7 @@ impl Default for GlobalMetricsData {
         Self {
             all_latencies: VecDeque::new(),
             all_slot_latencies: VecDeque::new(),
+            all_slot_latencies_ms: VecDeque::new(),
             total_votes: 0,
             validator_count: 0,
             current_metrics: None,
@@ -90,9 +169,12 @@ impl LatencyCalculator {
         Ok(Self {
             window_size,
             validator_metrics: Arc::new(DashMap::new()),
+            root_lags: Arc::new(DashMap::new()),
+            missed_votes: None,
             global_metrics: Arc::new(RwLock::new(GlobalMetricsData {
                 all_latencies: VecDeque::with_capacity(window_size),
                 all_slot_latencies: VecDeque::with_capacity(window_size),
+                all_slot_latencies_ms: VecDeque::with_capacity(window_size),
                 total_votes: 0,
                 validator_count: 0,
                 current_metrics: None,
@@ -106,20 +188,34 @@ impl LatencyCalculator {
 
     /// Update metrics with a new vote latency
     async fn update_metrics(&self, vote: &VoteLatency) -> Result<()> {
+        // Votes that landed on or voted for an abandoned fork never counted on chain
+        if vote.landing_status == LandingStatus::Skipped || vote.is_fork_vote() {
+            trace!("Excluding forked-out vote {} from statistics", vote.signature);
+            return Ok(());
+        }
+        
         trace!("Updating metrics for validator: {}", vote.validator_pubkey);
         
+        // Slot times can be out of order across streams; a negative span is no measurement
+        let slot_latency_ms = vote.slot_latency_ms.and_then(|ms| u64::try_from(ms).ok());
+        
         // Update per-validator metrics
         self.validator_metrics
             .entry(vote.validator_pubkey.clone())
             .and_modify(|data| {
                 data.latencies.push_back(vote.latency_ms);
                 data.slot_latencies.push_back(vote.latency_slots.clone());
+                data.credits.record(vote);
+                data.slot_latencies_ms.extend(slot_latency_ms);
                 if data.latencies.len() > self.window_size {
                     data.latencies.pop_front();
                 }
                 if data.slot_latencies.len() > self.window_size {
                     data.slot_latencies.pop_front();
                 }
+                if data.slot_latencies_ms.len() > self.window_size {
+                    data.slot_latencies_ms.pop_front();
+                }
                 data.total_votes += 1;
                 data.last_update = chrono::Utc::now();
             })
@@ -128,9 +224,13 @@ impl LatencyCalculator {
                 let mut slot_latencies = VecDeque::with_capacity(self.window_size);
                 latencies.push_back(vote.latency_ms);
                 slot_latencies.push_back(vote.latency_slots.clone());
+                let mut credits = EpochCredits::default();
+                credits.record(vote);
                 ValidatorMetricsData {
                     latencies,
                     slot_latencies,
+                    slot_latencies_ms: slot_latency_ms.into_iter().collect(),
+                    credits,
                     total_votes: 1,
                     last_update: chrono::Utc::now(),
                 }
@@ -146,11 +246,53 @@ impl LatencyCalculator {
         if global.all_slot_latencies.len() > self.window_size * 10 {
             global.all_slot_latencies.pop_front();
         }
+        global.all_slot_latencies_ms.extend(slot_latency_ms);
+        if global.all_slot_latencies_ms.len() > self.window_size * 10 {
+            global.all_slot_latencies_ms.pop_front();
+        }
         global.total_votes += 1;
         global.validator_count = self.validator_metrics.len();
 
         Ok(())
     }
+    
+    /// Report missed votes and vote coverage from `detector`; call before [`start`](Self::start)
+    pub fn set_missed_vote_detector(&mut self, detector: Arc<MissedVoteDetector>) {
+        self.missed_votes = Some(detector);
+    }
+    
+    /// Record the root lag of a vote account update and fill it in on the update
+    ///
+    /// Returns `None` when the root or the cluster's finalized slot is unknown.
+    pub fn record_root_lag(&self, update: &mut VoteAccountUpdate) -> Option<u64> {
+        let lag = root_lag(update)?;
+        update.root_lag_slots = Some(lag);
+        self.add_root_lag(update.state.validator_pubkey, lag);
+        crate::metrics::METRICS.record_root_lag(&update.state.validator_pubkey.to_string(), lag);
+        Some(lag)
+    }
+    
+    /// Add a root lag to a validator's window
+    fn add_root_lag(&self, validator_pubkey: Pubkey, lag: u64) {
+        let mut lags = self.root_lags
+            .entry(validator_pubkey)
+            .or_insert_with(|| VecDeque::with_capacity(self.window_size));
+        lags.push_back(lag);
+        if lags.len() > self.window_size {
+            lags.pop_front();
+        }
+    }
+    
+    /// Forget the root lags of a validator that is no longer monitored
+    pub fn remove_root_lag(&self, validator_pubkey: &Pubkey) {
+        self.root_lags.remove(validator_pubkey);
+        crate::metrics::METRICS.remove_root_lag(&validator_pubkey.to_string());
+    }
+    
+    /// Latest root lag of every validator
+    fn latest_root_lags(root_lags: &DashMap<Pubkey, VecDeque<u64>>) -> Vec<u64> {
+        root_lags.iter().filter_map(|lags| lags.back().copied()).collect()
+    }
 
     /// Calculate statistics from a collection of latencies
     fn calculate_stats(latencies: &[u64]) -> LatencyMetrics {
@@ -193,6 +335,21 @@ impl LatencyCalculator {
             votes_1_slot: 0,
             votes_2_slots: 0,
             votes_3plus_slots: 0,
+            // Filled in from slot times where the block meta index knows them
+            mean_slot_latency_ms: 0.0,
+            median_slot_latency_ms: 0.0,
+            p95_slot_latency_ms: 0.0,
+            // Credit metrics will be filled by calculate_combined_stats
+            mean_credits: 0.0,
+            epoch: 0,
+            epoch_credits: 0,
+            epoch_max_credits: 0,
+            // Root lags are filled in from vote account updates
+            mean_root_lag_slots: 0.0,
+            max_root_lag_slots: 0,
+            // Coverage is filled in from the missed vote detector
+            missed_votes: 0,
+            vote_coverage_pct: 0.0,
         }
     }
     
@@ -265,9 +422,63 @@ impl LatencyCalculator {
         metrics.votes_1_slot = votes_1_slot;
         metrics.votes_2_slots = votes_2_slots;
         metrics.votes_3plus_slots = votes_3plus_slots;
+        metrics.mean_credits = Self::calculate_mean_credits(slot_latencies);
         
         metrics
     }
+    
+    /// Mean credits earned per voted slot
+    fn calculate_mean_credits(slot_latencies: &[Vec<u8>]) -> f32 {
+        let (credits, voted_slots) = slot_latencies
+            .iter()
+            .flatten()
+            .fold((0u64, 0u64), |(credits, voted_slots), &latency| {
+                (credits + vote_credits(latency), voted_slots + 1)
+            });
+        
+        if voted_slots == 0 {
+            return 0.0;
+        }
+        credits as f32 / voted_slots as f32
+    }
+    
+    /// Current metrics of one validator, if it has votes in the window
+    fn validator_metrics_of(
+        validator_metrics: &DashMap<Pubkey, ValidatorMetricsData>,
+        root_lags: &DashMap<Pubkey, VecDeque<u64>>,
+        missed_votes: Option<&MissedVoteDetector>,
+        pubkey: &Pubkey,
+    ) -> Option<LatencyMetrics> {
+        let data = validator_metrics.get(pubkey)?;
+        let latencies: Vec<u64> = data.latencies.iter().copied().collect();
+        let slot_latencies: Vec<Vec<u8>> = data.slot_latencies.iter().cloned().collect();
+        let slot_latencies_ms: Vec<u64> = data.slot_latencies_ms.iter().copied().collect();
+        let mut metrics = Self::calculate_combined_stats(&latencies, &slot_latencies);
+        apply_slot_latencies_ms(&slot_latencies_ms, &mut metrics);
+        data.credits.apply(&mut metrics);
+        drop(data);
+        
+        if let Some(lags) = root_lags.get(pubkey) {
+            apply_root_lags(lags.iter(), &mut metrics);
+        }
+        if let Some(coverage) = missed_votes.and_then(|detector| detector.coverage(pubkey)) {
+            apply_coverage(&coverage, &mut metrics);
+        }
+        Some(metrics)
+    }
+    
+    /// Credits of all validators in the most recent epoch seen
+    fn global_epoch_credits(validator_metrics: &DashMap<Pubkey, ValidatorMetricsData>) -> EpochCredits {
+        let epoch = validator_metrics.iter().map(|data| data.credits.epoch).max().unwrap_or(0);
+        validator_metrics
+            .iter()
+            .filter(|data| data.credits.epoch == epoch)
+            .fold(EpochCredits { epoch, ..EpochCredits::default() }, |mut total, data| {
+                total.earned += data.credits.earned;
+                total.max += data.credits.max;
+                total
+            })
+    }
 
     /// Start background metrics aggregation task
     pub async fn start(&mut self) -> Result<()> {
@@ -275,6 +486,8 @@ impl LatencyCalculator {
         
         // Start periodic metrics logging
         let validator_metrics = Arc::clone(&self.validator_metrics);
+        let root_lags = Arc::clone(&self.root_lags);
+        let missed_votes = self.missed_votes.clone();
         let global_metrics = Arc::clone(&self.global_metrics);
         let mut shutdown_rx = self.shutdown_rx.as_ref()
             .ok_or_else(|| anyhow::anyhow!("Shutdown receiver not initialized"))?
@@ -288,15 +501,22 @@ impl LatencyCalculator {
                 select! {
                     _ = interval.tick() => {
                         // Quickly grab a snapshot of the data to minimize lock time
-                        let (latencies, slot_latencies, validator_count) = {
+                        let (latencies, slot_latencies, slot_latencies_ms, validator_count) = {
                             let global = global_metrics.read().await;
                             let latencies: Vec<u64> = global.all_latencies.iter().copied().collect();
                             let slot_latencies: Vec<Vec<u8>> = global.all_slot_latencies.iter().cloned().collect();
-                            (latencies, slot_latencies, validator_metrics.len())
+                            let slot_latencies_ms: Vec<u64> = global.all_slot_latencies_ms.iter().copied().collect();
+                            (latencies, slot_latencies, slot_latencies_ms, validator_metrics.len())
                         };
                         
                         if !latencies.is_empty() {
-                            let metrics = LatencyCalculator::calculate_combined_stats(&latencies, &slot_latencies);
+                            let mut metrics = LatencyCalculator::calculate_combined_stats(&latencies, &slot_latencies);
+                            apply_slot_latencies_ms(&slot_latencies_ms, &mut metrics);
+                            LatencyCalculator::global_epoch_credits(&validator_metrics).apply(&mut metrics);
+                            apply_root_lags(&LatencyCalculator::latest_root_lags(&root_lags), &mut metrics);
+                            if let Some(detector) = &missed_votes {
+                                apply_coverage(&detector.total_coverage(), &mut metrics);
+                            }
                             info!(
                                 "Global metrics - Mean: {:.2}ms ({:.2} slots), Median: {:.2}ms ({:.2} slots), P95: {:.2}ms ({:.2} slots), Validators: {}",
                                 metrics.mean_ms, metrics.mean_slots,
@@ -304,19 +524,58 @@ impl LatencyCalculator {
                                 metrics.p95_ms, metrics.p95_slots,
                                 validator_count
                             );
+                            if !slot_latencies_ms.is_empty() {
+                                info!(
+                                    "Slot latency - Mean: {:.0}ms, Median: {:.0}ms, P95: {:.0}ms",
+                                    metrics.mean_slot_latency_ms, metrics.median_slot_latency_ms, metrics.p95_slot_latency_ms
+                                );
+                            }
                             info!(
                                 "Vote distribution - 1 slot: {}, 2 slots: {}, 3+ slots: {}",
                                 metrics.votes_1_slot, metrics.votes_2_slots, metrics.votes_3plus_slots
                             );
+                            info!(
+                                "Vote credits - Mean: {:.2} per slot, Epoch {}: {}/{}",
+                                metrics.mean_credits, metrics.epoch,
+                                metrics.epoch_credits, metrics.epoch_max_credits
+                            );
+                            if missed_votes.is_some() {
+                                info!(
+                                    "Vote coverage - {:.2}% of confirmed slots, {} missed",
+                                    metrics.vote_coverage_pct, metrics.missed_votes
+                                );
+                            }
+                            if metrics.max_root_lag_slots > 0 {
+                                info!(
+                                    "Root lag - Mean: {:.1} slots, Max: {} slots",
+                                    metrics.mean_root_lag_slots, metrics.max_root_lag_slots
+                                );
+                            }
                             
                             // Store metrics in a separate non-blocking task to avoid holding locks
                             if let Some(storage) = &storage {
                                 let storage_clone = storage.clone();
                                 let metrics_clone = metrics.clone();
+                                
+                                // Per-validator metrics carry each validator's epoch credits
+                                let pubkeys: Vec<Pubkey> = validator_metrics.iter().map(|entry| *entry.key()).collect();
+                                let per_validator: Vec<(Pubkey, LatencyMetrics)> = pubkeys
+                                    .into_iter()
+                                    .filter_map(|pubkey| {
+                                        LatencyCalculator::validator_metrics_of(&validator_metrics, &root_lags, missed_votes.as_deref(), &pubkey)
+                                            .map(|metrics| (pubkey, metrics))
+                                    })
+                                    .collect();
+                                
                                 tokio::spawn(async move {
                                     if let Err(e) = storage_clone.store_metrics(&metrics_clone, None).await {
                                         tracing::error!("Failed to store global metrics: {}", e);
                                     }
+                                    for (pubkey, metrics) in &per_validator {
+                                        if let Err(e) = storage_clone.store_metrics(metrics, Some(pubkey)).await {
+                                            tracing::error!("Failed to store metrics of validator {}: {}", pubkey, e);
+                                        }
+                                    }
                                 });
                             }
                             
@@ -375,28 +634,28 @@ impl LatencyCalculatorTrait for LatencyCalculator {
         self.update_metrics(vote).await?;
         
         // Get validator's current metrics
-        if let Some(data) = self.validator_metrics.get(&vote.validator_pubkey) {
-            let latencies: Vec<u64> = data.latencies.iter().copied().collect();
-            let slot_latencies: Vec<Vec<u8>> = data.slot_latencies.iter().cloned().collect();
-            Ok(Self::calculate_combined_stats(&latencies, &slot_latencies))
-        } else {
-            Ok(LatencyMetrics::default())
-        }
+        Ok(self.get_validator_metrics(&vote.validator_pubkey).await.unwrap_or_default())
     }
 
     async fn get_validator_metrics(&self, pubkey: &Pubkey) -> Option<LatencyMetrics> {
-        self.validator_metrics.get(pubkey).map(|data| {
-            let latencies: Vec<u64> = data.latencies.iter().copied().collect();
-            let slot_latencies: Vec<Vec<u8>> = data.slot_latencies.iter().cloned().collect();
-            Self::calculate_combined_stats(&latencies, &slot_latencies)
-        })
+        Self::validator_metrics_of(&self.validator_metrics, &self.root_lags, self.missed_votes.as_deref(), pubkey)
     }
 
     async fn get_global_metrics(&self) -> LatencyMetrics {
         let global = self.global_metrics.read().await;
         let latencies: Vec<u64> = global.all_latencies.iter().copied().collect();
         let slot_latencies: Vec<Vec<u8>> = global.all_slot_latencies.iter().cloned().collect();
-        Self::calculate_combined_stats(&latencies, &slot_latencies)
+        let slot_latencies_ms: Vec<u64> = global.all_slot_latencies_ms.iter().copied().collect();
+        drop(global);
+        
+        let mut metrics = Self::calculate_combined_stats(&latencies, &slot_latencies);
+        apply_slot_latencies_ms(&slot_latencies_ms, &mut metrics);
+        Self::global_epoch_credits(&self.validator_metrics).apply(&mut metrics);
+        apply_root_lags(&Self::latest_root_lags(&self.root_lags), &mut metrics);
+        if let Some(detector) = &self.missed_votes {
+            apply_coverage(&detector.total_coverage(), &mut metrics);
+        }
+        metrics
     }
 }
 
@@ -458,11 +717,161 @@ mod tests {
             voted_on_slots: vec![12345],
             landed_slot: 12347,
             latency_slots: vec![2],
+            landing_status: LandingStatus::Processed,
+            breakdown: Default::default(),
+            slot_latency_ms: None,
+            leader: None,
+            vote_hash: None,
+            fork_slots: Vec::new(),
         };
         
         let metrics = calculator.calculate(&vote).await.unwrap();
         assert_eq!(metrics.mean_ms, 50.0);
         assert_eq!(metrics.mean_slots, 2.0);
         assert_eq!(metrics.sample_count, 1);
+        assert_eq!(metrics.mean_credits, 16.0);
+        assert_eq!(metrics.epoch_credits, 16);
+        assert_eq!(metrics.epoch_max_credits, 16);
+        
+        // Forked-out landings are excluded from statistics
+        let forked = VoteLatency {
+            latency_ms: 500,
+            signature: "forked".to_string(),
+            landing_status: LandingStatus::Skipped,
+            ..vote
+        };
+        let metrics = calculator.calculate(&forked).await.unwrap();
+        assert_eq!(metrics.mean_ms, 50.0);
+        assert_eq!(metrics.sample_count, 1);
+        
+        // So are votes for abandoned slots
+        let fork_vote = VoteLatency {
+            signature: "fork_vote".to_string(),
+            landing_status: LandingStatus::Confirmed,
+            fork_slots: vec![12345],
+            ..forked
+        };
+        let metrics = calculator.calculate(&fork_vote).await.unwrap();
+        assert_eq!(metrics.sample_count, 1);
+    }
+    
+    #[tokio::test]
+    async fn test_epoch_credits() {
+        let config = Arc::new(Config::default());
+        let (_shutdown_tx, shutdown_rx) = broadcast::channel(1);
+        let calculator = LatencyCalculator::new(config, None, shutdown_rx).await.unwrap();
+        let validator = Pubkey::new_unique();
+        let vote = |voted_on_slot: u64, landed_slot: u64| VoteLatency::new_single_vote(
+            validator,
+            Pubkey::new_unique(),
+            voted_on_slot,
+            chrono::Utc::now(),
+            chrono::Utc::now(),
+            "test".to_string(),
+            landed_slot,
+        );
+        
+        let epoch_start = 10 * DEFAULT_SLOTS_PER_EPOCH;
+        calculator.calculate(&vote(epoch_start - 10, epoch_start - 9)).await.unwrap();
+        calculator.calculate(&vote(epoch_start, epoch_start + 1)).await.unwrap();
+        let metrics = calculator.calculate(&vote(epoch_start + 1, epoch_start + 6)).await.unwrap();
+        
+        // The previous epoch's vote no longer counts; 5 slots late earns 13
+        assert_eq!(metrics.epoch, 10);
+        assert_eq!(metrics.epoch_credits, 16 + 13);
+        assert_eq!(metrics.epoch_max_credits, 32);
+        assert!((metrics.mean_credits - 15.0).abs() < 0.01);
+        
+        let global = calculator.get_global_metrics().await;
+        assert_eq!(global.epoch_credits, 29);
     }
-}
\ No newline at end of file
+    
+    #[tokio::test]
+    async fn test_root_lag() {
+        let config = Arc::new(Config::default());
+        let (_shutdown_tx, shutdown_rx) = broadcast::channel(1);
+        let calculator = LatencyCalculator::new(config, None, shutdown_rx).await.unwrap();
+        let validator = Pubkey::new_unique();
+        
+        let update = |root_slot: Option<u64>, finalized_slot: Option<u64>| VoteAccountUpdate {
+            state: crate::models::VoteAccountState {
+                validator_pubkey: validator,
+                vote_pubkey: Pubkey::new_unique(),
+                slot: 1_000,
+                node_pubkey: validator,
+                commission: 0,
+                root_slot,
+                last_voted_slot: Some(999),
+                lockout_depth: 31,
+                epoch: None,
+                epoch_credits: 0,
+                total_credits: 0,
+                last_timestamp_slot: 999,
+                last_timestamp: 0,
+                observed_at: chrono::Utc::now(),
+            },
+            change: None,
+            finalized_slot,
+            root_lag_slots: None,
+        };
+        
+        assert_eq!(root_lag(&update(Some(960), Some(968))), Some(8));
+        // A root ahead of the finalized slot we have seen is not lagging
+        assert_eq!(root_lag(&update(Some(970), Some(968))), Some(0));
+        assert_eq!(root_lag(&update(None, Some(968))), None);
+        assert_eq!(root_lag(&update(Some(960), None)), None);
+        
+        calculator.add_root_lag(validator, 8);
+        calculator.add_root_lag(validator, 2);
+        calculator.add_root_lag(Pubkey::new_unique(), 30);
+        calculator.calculate(&VoteLatency::new_single_vote(
+            validator,
+            Pubkey::new_unique(),
+            1_000,
+            chrono::Utc::now(),
+            chrono::Utc::now(),
+            "test".to_string(),
+            1_001,
+        )).await.unwrap();
+        
+        let metrics = calculator.get_validator_metrics(&validator).await.unwrap();
+        assert_eq!(metrics.mean_root_lag_slots, 5.0);
+        assert_eq!(metrics.max_root_lag_slots, 8);
+        
+        // Globally, the latest lag of each validator
+        let global = calculator.get_global_metrics().await;
+        assert_eq!(global.mean_root_lag_slots, 16.0);
+        assert_eq!(global.max_root_lag_slots, 30);
+    }
+    
+    #[tokio::test]
+    async fn test_vote_coverage() {
+        let config = Arc::new(Config::default());
+        let (_shutdown_tx, shutdown_rx) = broadcast::channel(1);
+        let mut calculator = LatencyCalculator::new(config, None, shutdown_rx).await.unwrap();
+        let detector = Arc::new(MissedVoteDetector::new(0));
+        calculator.set_missed_vote_detector(Arc::clone(&detector));
+        
+        let validator = Pubkey::new_unique();
+        let vote = VoteLatency::new_single_vote(
+            validator,
+            Pubkey::new_unique(),
+            100,
+            chrono::Utc::now(),
+            chrono::Utc::now(),
+            "test".to_string(),
+            101,
+        );
+        detector.record_vote(&vote);
+        for slot in 100..=103 {
+            detector.on_confirmed(slot);
+        }
+        
+        let metrics = calculator.calculate(&vote).await.unwrap();
+        assert_eq!(metrics.missed_votes, 3);
+        assert_eq!(metrics.vote_coverage_pct, 25.0);
+        
+        let global = calculator.get_global_metrics().await;
+        assert_eq!(global.missed_votes, 3);
+    }
+}
@@ -59,6 +59,11 @@ impl ValidatorDiscovery {
             task_handle: None,
         })
     }
+    
+    /// RPC client shared with other modules that query the cluster
+    pub fn rpc_client(&self) -> Arc<RpcClient> {
+        Arc::clone(&self.rpc_client)
+    }
 
     /// Start the discovery service
     pub async fn start(&mut self) -> Result<()> {
@@ -167,7 +172,8 @@ impl ValidatorDiscovery {
                 continue;
             }
             
-            let info = ValidatorInfo::new(validator_pubkey, vote_pubkey);
+            let mut info = ValidatorInfo::new(validator_pubkey, vote_pubkey);
+            info.activated_stake = stake_lamports;
             validators.insert(validator_pubkey, info);
         }
         
@@ -196,7 +202,9 @@ impl ValidatorDiscovery {
                     continue;
                 }
                 
-                let info = ValidatorInfo::new(validator_pubkey, vote_pubkey);
+                let mut info = ValidatorInfo::new(validator_pubkey, vote_pubkey);
+                info.activated_stake = vote_account.activated_stake;
+                info.delinquent = true;
                 validators.insert(validator_pubkey, info);
             }
         }
@@ -231,7 +239,8 @@ impl ValidatorDiscovery {
             let vote_pubkey = vote_account.vote_pubkey.parse::<Pubkey>()?;
             let stake = vote_account.activated_stake;
             
-            let info = ValidatorInfo::new(validator_pubkey, vote_pubkey);
+            let mut info = ValidatorInfo::new(validator_pubkey, vote_pubkey);
+            info.activated_stake = stake;
             validators.push((info, stake));
         }
         
@@ -284,8 +293,8 @@ impl ValidatorDiscoveryTrait for ValidatorDiscovery {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{AppConfig, SolanaConfig, GrpcConfig, InfluxConfig, MetricsConfig, LatencyConfig, DiscoveryConfig};
-
+    use crate::config::{AppConfig, SolanaConfig, GrpcConfig, InfluxConfig, MetricsConfig, LatencyConfig, DiscoveryConfig, SubscriptionMode, EndpointMode, GrpcCommitment, OverflowPolicy, AdmissionPriority};
+    
     fn create_test_config() -> Config {
         Config {
             app: AppConfig {
@@ -304,10 +313,34 @@ mod tests {
                 endpoint: None,
                 access_token: None,
                 max_subscriptions: 50,
+                admission_priority: AdmissionPriority::Stake,
+                priority_validators: vec![],
+                anomaly_window_secs: 600,
                 connection_timeout_secs: 30,
                 reconnect_interval_secs: 5,
+                reconnect_max_delay_secs: 60,
                 buffer_size: 10000,
+                overflow_policy: OverflowPolicy::Block,
+                spill_dir: "./data/vote_spill".to_string(),
+                spill_max_mb: 512,
                 enable_tls: false,
+                tls_ca_cert_path: None,
+                tls_client_cert_path: None,
+                tls_client_key_path: None,
+                tls_domain_name: None,
+                subscription_mode: SubscriptionMode::PerValidator,
+                stream_pool_size: 1,
+                replay_on_reconnect: true,
+                stall_timeout_secs: 30,
+                max_slot_lag: 150,
+                endpoints: Vec::new(),
+                endpoint_mode: EndpointMode::Failover,
+                commitment: GrpcCommitment::Processed,
+                track_finality: false,
+                subscribe_block_meta: false,
+                track_missed_votes: false,
+                missed_vote_tolerance_slots: 32,
+                track_root_lag: false,
             },
             influxdb: InfluxConfig {
                 url: "http://localhost:8086".to_string(),
@@ -318,6 +351,12 @@ mod tests {
                 flush_interval_ms: 100,
                 num_workers: 2,
                 enable_compression: false,
+                short_validator_tag: false,
+                instance: None,
+                spool_dir: "./data/influx_spool".to_string(),
+                spool_max_mb: 0,
+                spool_max_batches: 100_000,
+                spool_replay_interval_secs: 5,
             },
             metrics: MetricsConfig {
                 enabled: false,
@@ -0,0 +1,319 @@
+//! Finality Tracking Module
+//!
+//! Holds vote transactions until the slot they landed in is finalized or
+//! skipped. Slot status updates from the gRPC streams drive the tracker: a
+//! finalized slot and its parent are on the finalized chain, and the slots
+//! strictly between them were skipped by it. Dead slots are skipped too. A
+//! slot without such evidence, e.g. because a finalized update was missed
+//! during a stream gap, stays pending; votes whose slot is never resolved are
+//! released with their last known status after `STALE_AFTER_SLOTS`.
+//!
+//! Released votes also have their voted-on slots checked against the same
+//! view of the chain: only slots the finalized chain is known to have
+//! skipped, or that were reported dead, are recorded as the vote's fork slots.
+
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, BTreeSet};
+use tracing::debug;
+
+use crate::models::{LandingStatus, VoteEvent};
+
+/// Slots after which an unresolved vote is released with its last known status
+pub const STALE_AFTER_SLOTS: u64 = 512;
+
+/// Finalized slots remembered for votes that arrive after their slot was rooted
+const FINALIZED_HISTORY: u64 = 4096;
+
+/// Slot status as reported by the gRPC slot updates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotUpdate {
+    /// Slot was processed by the node
+    Processed,
+    /// Slot was optimistically confirmed by the cluster
+    Confirmed,
+    /// Slot was rooted
+    Finalized,
+    /// Slot was abandoned by the node
+    Dead,
+}
+
+/// Votes waiting for their landing slot to resolve
+#[derive(Default)]
+struct PendingSlot {
+    status: LandingStatus,
+    votes: Vec<VoteEvent>,
+}
+
+#[derive(Default)]
+struct TrackerState {
+    /// Pending votes keyed by landing slot
+    pending: BTreeMap<u64, PendingSlot>,
+    /// Recently finalized slots, including the parents of finalized slots
+    finalized: BTreeSet<u64>,
+    /// Recent slots a finalized slot's parent link skipped over
+    skipped: BTreeSet<u64>,
+    /// Recently dead slots
+    dead: BTreeSet<u64>,
+    /// Highest slot seen with any status
+    highest_slot: u64,
+}
+
+/// Tracks vote landing slots through processed, confirmed and finalized
+#[derive(Default)]
+pub struct FinalityTracker {
+    state: Mutex<TrackerState>,
+}
+
+impl FinalityTracker {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Hold a vote until its landing slot resolves
+    ///
+    /// Returns the vote straight away if its slot is already known to be
+    /// finalized or skipped.
+    pub fn hold(&self, vote: VoteEvent) -> Option<VoteEvent> {
+        let landed_slot = vote.latency.landed_slot;
+        
+        let mut state = self.state.lock();
+        if let Some(status) = state.resolved_status(landed_slot) {
+            let mut resolved = Vec::with_capacity(1);
+            state.release(vec![vote], status, &mut resolved);
+            return resolved.pop();
+        }
+        
+        state.pending.entry(landed_slot).or_default().votes.push(vote);
+        None
+    }
+    
+    /// Apply a slot status update, returning the votes it resolved
+    pub fn on_slot(&self, slot: u64, parent: Option<u64>, update: SlotUpdate) -> Vec<VoteEvent> {
+        let mut state = self.state.lock();
+        let mut resolved = Vec::new();
+        state.highest_slot = state.highest_slot.max(slot);
+        
+        match update {
+            SlotUpdate::Processed => {}
+            SlotUpdate::Confirmed => {
+                if let Some(pending) = state.pending.get_mut(&slot) {
+                    pending.status = LandingStatus::Confirmed;
+                }
+            }
+            SlotUpdate::Dead => {
+                state.dead.insert(slot);
+                if let Some(pending) = state.pending.remove(&slot) {
+                    debug!("Slot {} is dead, {} votes skipped", slot, pending.votes.len());
+                    state.release(pending.votes, LandingStatus::Skipped, &mut resolved);
+                }
+            }
+            SlotUpdate::Finalized => {
+                state.finalized.insert(slot);
+                
+                // The parent is on the finalized chain and the gap back to it
+                // was skipped; without a parent nothing is known about the gap
+                if let Some(parent) = parent {
+                    state.finalized.insert(parent);
+                    if parent + 1 < slot {
+                        debug!("Slots {}..{} skipped before finalized slot {}", parent + 1, slot, slot);
+                        state.skipped.extend(parent + 1..slot);
+                    }
+                }
+                
+                let decided: Vec<u64> = state.pending
+                    .range(..=slot)
+                    .map(|(landed_slot, _)| *landed_slot)
+                    .filter(|landed_slot| state.resolved_status(*landed_slot).is_some())
+                    .collect();
+                for landed_slot in decided {
+                    if let (Some(pending), Some(status)) = (state.pending.remove(&landed_slot), state.resolved_status(landed_slot)) {
+                        state.release(pending.votes, status, &mut resolved);
+                    }
+                }
+                
+                let horizon = slot.saturating_sub(FINALIZED_HISTORY);
+                state.finalized = state.finalized.split_off(&horizon);
+                state.skipped = state.skipped.split_off(&horizon);
+                state.dead = state.dead.split_off(&horizon);
+            }
+        }
+        
+        // Release votes whose slot never resolved
+        let cutoff = state.highest_slot.saturating_sub(STALE_AFTER_SLOTS);
+        if state.pending.first_key_value().is_some_and(|(landed_slot, _)| *landed_slot < cutoff) {
+            let fresh = state.pending.split_off(&cutoff);
+            let stale = std::mem::replace(&mut state.pending, fresh);
+            for (_, pending) in stale {
+                state.release(pending.votes, pending.status, &mut resolved);
+            }
+        }
+        
+        resolved
+    }
+    
+    /// Number of votes waiting for their slot to resolve
+    pub fn pending_votes(&self) -> usize {
+        self.state.lock().pending.values().map(|pending| pending.votes.len()).sum()
+    }
+}
+
+impl TrackerState {
+    /// Status of a slot the finalized chain is known to include or skip
+    fn resolved_status(&self, slot: u64) -> Option<LandingStatus> {
+        if self.finalized.contains(&slot) {
+            Some(LandingStatus::Finalized)
+        } else if self.is_abandoned(slot) {
+            Some(LandingStatus::Skipped)
+        } else {
+            None
+        }
+    }
+    
+    /// Whether a slot is known not to be on the finalized chain
+    fn is_abandoned(&self, slot: u64) -> bool {
+        self.dead.contains(&slot) || self.skipped.contains(&slot)
+    }
+    
+    /// Stamp votes with their landing status and fork slots and move them to the output
+    fn release(&self, votes: Vec<VoteEvent>, status: LandingStatus, resolved: &mut Vec<VoteEvent>) {
+        resolved.extend(votes.into_iter().map(|mut vote| {
+            vote.latency.landing_status = status;
+            vote.latency.fork_slots = vote.latency.voted_on_slots
+                .iter()
+                .copied()
+                .filter(|&slot| self.is_abandoned(slot))
+                .collect();
+            vote
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::VoteLatency;
+    use solana_sdk::pubkey::Pubkey;
+    
+    fn vote(signature: &str, landed_slot: u64) -> VoteEvent {
+        let now = chrono::Utc::now();
+        VoteEvent {
+            latency: VoteLatency::new_single_vote(
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                landed_slot - 1,
+                now,
+                now,
+                signature.to_string(),
+                landed_slot,
+            ),
+            created_at: None,
+            received_at: now,
+            tip_slot: landed_slot,
+            endpoint: "test".to_string(),
+        }
+    }
+    
+    #[test]
+    fn test_finalized_and_skipped_landings() {
+        let tracker = FinalityTracker::new();
+        assert!(tracker.hold(vote("on_chain", 100)).is_none());
+        assert!(tracker.hold(vote("forked", 101)).is_none());
+        assert!(tracker.hold(vote("later", 103)).is_none());
+        
+        assert!(tracker.on_slot(100, Some(99), SlotUpdate::Confirmed).is_empty());
+        
+        let resolved = tracker.on_slot(100, Some(99), SlotUpdate::Finalized);
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].latency.landing_status, LandingStatus::Finalized);
+        
+        // Slot 101 was skipped by the finalized chain
+        let resolved = tracker.on_slot(102, Some(100), SlotUpdate::Finalized);
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].latency.signature, "forked");
+        assert_eq!(resolved[0].latency.landing_status, LandingStatus::Skipped);
+        
+        assert_eq!(tracker.pending_votes(), 1);
+    }
+    
+    #[test]
+    fn test_dead_slot_resolves_immediately() {
+        let tracker = FinalityTracker::new();
+        tracker.hold(vote("dead", 50));
+        
+        let resolved = tracker.on_slot(50, None, SlotUpdate::Dead);
+        assert_eq!(resolved[0].latency.landing_status, LandingStatus::Skipped);
+        assert_eq!(tracker.pending_votes(), 0);
+    }
+    
+    #[test]
+    fn test_vote_after_finalization_resolves_on_hold() {
+        let tracker = FinalityTracker::new();
+        tracker.on_slot(200, Some(198), SlotUpdate::Finalized);
+        
+        assert_eq!(tracker.hold(vote("rooted", 200)).unwrap().latency.landing_status, LandingStatus::Finalized);
+        assert_eq!(tracker.hold(vote("skipped", 199)).unwrap().latency.landing_status, LandingStatus::Skipped);
+        assert!(tracker.hold(vote("pending", 201)).is_none());
+    }
+    
+    #[test]
+    fn test_stale_votes_released_with_last_status() {
+        let tracker = FinalityTracker::new();
+        tracker.hold(vote("stuck", 10));
+        tracker.on_slot(10, None, SlotUpdate::Confirmed);
+        
+        let resolved = tracker.on_slot(10 + STALE_AFTER_SLOTS + 1, None, SlotUpdate::Processed);
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].latency.landing_status, LandingStatus::Confirmed);
+    }
+    
+    #[test]
+    fn test_votes_for_abandoned_slots_flagged() {
+        let tracker = FinalityTracker::new();
+        tracker.hold(vote("on_fork", 102));
+        let mut on_chain = vote("on_chain", 103);
+        on_chain.latency.voted_on_slots = vec![100];
+        tracker.hold(on_chain);
+        
+        tracker.on_slot(100, Some(99), SlotUpdate::Finalized);
+        // Slots 101 and 102 were abandoned
+        let resolved = tracker.on_slot(103, Some(100), SlotUpdate::Finalized);
+        assert_eq!(resolved.len(), 2);
+        assert_eq!(resolved[0].latency.signature, "on_fork");
+        assert_eq!(resolved[0].latency.fork_slots, vec![101]);
+        assert!(!resolved[1].latency.is_fork_vote());
+        
+        // A dead slot is abandoned before the finalized chain gets past it
+        tracker.on_slot(110, None, SlotUpdate::Dead);
+        tracker.hold(vote("dead_fork", 111));
+        let resolved = tracker.on_slot(111, None, SlotUpdate::Dead);
+        assert_eq!(resolved[0].latency.fork_slots, vec![110]);
+        
+        // Votes resolved on arrival are checked too
+        assert_eq!(tracker.hold(vote("late", 102)).unwrap().latency.fork_slots, vec![101]);
+    }
+    
+    #[test]
+    fn test_missed_finalized_update_is_not_a_skip() {
+        let tracker = FinalityTracker::new();
+        tracker.hold(vote("unknown", 101));
+        tracker.hold(vote("parent", 102));
+        let mut voter = vote("voter", 104);
+        voter.latency.voted_on_slots = vec![101, 102];
+        tracker.hold(voter);
+        
+        // The finalized updates for 101 and 102 never arrived
+        tracker.on_slot(100, Some(99), SlotUpdate::Finalized);
+        let resolved = tracker.on_slot(103, Some(102), SlotUpdate::Finalized);
+        assert_eq!(resolved.len(), 1);
+        assert_eq!(resolved[0].latency.signature, "parent");
+        assert_eq!(resolved[0].latency.landing_status, LandingStatus::Finalized);
+        assert_eq!(tracker.pending_votes(), 2);
+        
+        // Neither slot is taken for a fork without evidence
+        let resolved = tracker.on_slot(104, Some(103), SlotUpdate::Finalized);
+        assert_eq!(resolved[0].latency.signature, "voter");
+        assert!(!resolved[0].latency.is_fork_vote());
+        assert!(tracker.hold(vote("still_unknown", 101)).is_none());
+    }
+}
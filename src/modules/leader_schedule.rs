@@ -0,0 +1,257 @@
+//! Leader Schedule Module
+//!
+//! Fetches the leader schedule for the current and next epoch with
+//! `getLeaderSchedule` over the discovery RPC client, so the slot a vote
+//! landed in can be attributed to the leader that included it. A background
+//! task checks the current slot periodically and loads each epoch once.
+
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::epoch_schedule::EpochSchedule;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::select;
+use tokio::sync::broadcast;
+use tracing::{debug, error, info, warn};
+
+use crate::error::{Error, Result};
+use crate::modules::{Shutdown, ShutdownSignal};
+
+/// How often the current slot is checked for an epoch change
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Epochs kept behind the current one, for votes that land late
+const EPOCHS_KEPT_BEHIND: u64 = 1;
+
+/// Slot leaders of the loaded epochs
+#[derive(Default)]
+pub struct LeaderSchedule {
+    /// Leaders by slot offset, keyed by the first slot of their epoch
+    epochs: RwLock<BTreeMap<u64, Vec<Option<Pubkey>>>>,
+}
+
+impl LeaderSchedule {
+    /// Create an empty schedule
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Leader of a slot, if its epoch is loaded
+    pub fn leader(&self, slot: u64) -> Option<Pubkey> {
+        let epochs = self.epochs.read();
+        let (first_slot, leaders) = epochs.range(..=slot).next_back()?;
+        leaders.get((slot - first_slot) as usize).copied().flatten()
+    }
+    
+    /// Whether the epoch starting at `first_slot` is loaded
+    pub fn has_epoch(&self, first_slot: u64) -> bool {
+        self.epochs.read().contains_key(&first_slot)
+    }
+    
+    /// Load an epoch from a `getLeaderSchedule` response
+    ///
+    /// The response maps each leader to the slot offsets it leads within the
+    /// epoch; slots no leader claims stay unattributed.
+    pub fn insert_epoch(
+        &self,
+        first_slot: u64,
+        slots_in_epoch: u64,
+        schedule: &HashMap<String, Vec<usize>>,
+    ) -> Result<()> {
+        let mut leaders = vec![None; slots_in_epoch as usize];
+        for (leader, offsets) in schedule {
+            let leader: Pubkey = leader.parse()?;
+            for &offset in offsets {
+                if let Some(slot) = leaders.get_mut(offset) {
+                    *slot = Some(leader);
+                }
+            }
+        }
+        
+        self.epochs.write().insert(first_slot, leaders);
+        Ok(())
+    }
+    
+    /// Drop epochs that start before `first_slot`
+    pub fn prune_before(&self, first_slot: u64) {
+        let mut epochs = self.epochs.write();
+        *epochs = epochs.split_off(&first_slot);
+    }
+}
+
+/// Keeps a [`LeaderSchedule`] loaded for the current and next epoch
+pub struct LeaderScheduleTracker {
+    rpc_client: Arc<RpcClient>,
+    schedule: Arc<LeaderSchedule>,
+    shutdown_rx: broadcast::Receiver<ShutdownSignal>,
+    task_handle: Option<tokio::task::JoinHandle<()>>,
+}
+
+impl LeaderScheduleTracker {
+    /// Create a tracker over an existing RPC client
+    pub fn new(rpc_client: Arc<RpcClient>, shutdown_rx: broadcast::Receiver<ShutdownSignal>) -> Self {
+        Self {
+            rpc_client,
+            schedule: Arc::new(LeaderSchedule::new()),
+            shutdown_rx,
+            task_handle: None,
+        }
+    }
+    
+    /// Shared handle to the loaded schedule
+    pub fn schedule(&self) -> Arc<LeaderSchedule> {
+        Arc::clone(&self.schedule)
+    }
+    
+    /// Load the current epoch and keep the schedule up to date in the background
+    ///
+    /// A failed initial load is not fatal; votes go unattributed until the
+    /// next check succeeds. The epoch schedule is fetched on the first check
+    /// that reaches the RPC node.
+    pub async fn start(&mut self) -> Result<()> {
+        info!("Starting leader schedule tracker");
+        
+        let mut epoch_schedule = None;
+        if let Err(e) = Self::refresh(&self.rpc_client, &self.schedule, &mut epoch_schedule).await {
+            warn!("Failed to load leader schedule: {}", e);
+        }
+        
+        let rpc_client = Arc::clone(&self.rpc_client);
+        let schedule = Arc::clone(&self.schedule);
+        let mut shutdown_rx = self.shutdown_rx.resubscribe();
+        
+        let handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(CHECK_INTERVAL);
+            interval.tick().await;
+            
+            loop {
+                select! {
+                    _ = interval.tick() => {
+                        if let Err(e) = Self::refresh(&rpc_client, &schedule, &mut epoch_schedule).await {
+                            error!("Failed to refresh leader schedule: {}", e);
+                        }
+                    }
+                    _ = shutdown_rx.recv() => {
+                        info!("Leader schedule tracker received shutdown signal");
+                        break;
+                    }
+                }
+            }
+        });
+        
+        self.task_handle = Some(handle);
+        Ok(())
+    }
+    
+    /// Load the current and next epoch if missing and drop old epochs
+    async fn refresh(
+        rpc_client: &RpcClient,
+        schedule: &LeaderSchedule,
+        epoch_schedule: &mut Option<EpochSchedule>,
+    ) -> Result<()> {
+        let epoch_schedule = match epoch_schedule {
+            Some(epoch_schedule) => epoch_schedule,
+            None => {
+                let fetched = rpc_client.get_epoch_schedule().await
+                    .map_err(|e| Error::rpc(format!("Failed to get epoch schedule: {}", e)))?;
+                epoch_schedule.insert(fetched)
+            }
+        };
+        
+        let slot = rpc_client.get_slot().await
+            .map_err(|e| Error::rpc(format!("Failed to get slot: {}", e)))?;
+        let epoch = epoch_schedule.get_epoch(slot);
+        
+        for epoch in [epoch, epoch + 1] {
+            let first_slot = epoch_schedule.get_first_slot_in_epoch(epoch);
+            if schedule.has_epoch(first_slot) {
+                continue;
+            }
+            
+            let leaders = rpc_client.get_leader_schedule(Some(first_slot)).await
+                .map_err(|e| Error::rpc(format!("Failed to get leader schedule: {}", e)))?;
+            match leaders {
+                Some(leaders) => {
+                    schedule.insert_epoch(first_slot, epoch_schedule.get_slots_in_epoch(epoch), &leaders)?;
+                    info!("Loaded leader schedule for epoch {}", epoch);
+                }
+                None => debug!("Leader schedule for epoch {} is not available yet", epoch),
+            }
+        }
+        
+        let oldest_kept = epoch.saturating_sub(EPOCHS_KEPT_BEHIND);
+        schedule.prune_before(epoch_schedule.get_first_slot_in_epoch(oldest_kept));
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Shutdown for LeaderScheduleTracker {
+    async fn shutdown(&mut self) -> Result<()> {
+        info!("Shutting down leader schedule tracker");
+        
+        if let Some(handle) = self.task_handle.take() {
+            handle.abort();
+            let _ = tokio::time::timeout(Duration::from_secs(5), handle).await;
+        }
+        
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_leaders_by_slot_across_epochs() {
+        let schedule = LeaderSchedule::new();
+        let first = Pubkey::new_unique();
+        let second = Pubkey::new_unique();
+        
+        let epoch: HashMap<String, Vec<usize>> = [
+            (first.to_string(), vec![0, 1, 2, 3]),
+            (second.to_string(), vec![4, 5, 6, 7]),
+        ].into_iter().collect();
+        schedule.insert_epoch(1_000, 8, &epoch).unwrap();
+        
+        let next: HashMap<String, Vec<usize>> = [(second.to_string(), vec![0, 1, 2, 3])].into_iter().collect();
+        schedule.insert_epoch(1_008, 8, &next).unwrap();
+        
+        assert_eq!(schedule.leader(999), None);
+        assert_eq!(schedule.leader(1_000), Some(first));
+        assert_eq!(schedule.leader(1_007), Some(second));
+        assert_eq!(schedule.leader(1_008), Some(second));
+        // Unclaimed slots are not attributed
+        assert_eq!(schedule.leader(1_012), None);
+        
+        schedule.prune_before(1_008);
+        assert!(!schedule.has_epoch(1_000));
+        assert_eq!(schedule.leader(1_000), None);
+        assert_eq!(schedule.leader(1_009), Some(second));
+    }
+    
+    #[test]
+    fn test_unclaimed_slots_unattributed() {
+        let schedule = LeaderSchedule::new();
+        let leader = Pubkey::new_unique();
+        let epoch: HashMap<String, Vec<usize>> = [(leader.to_string(), vec![0, 2])].into_iter().collect();
+        schedule.insert_epoch(0, 4, &epoch).unwrap();
+        
+        assert_eq!(schedule.leader(0), Some(leader));
+        assert_eq!(schedule.leader(1), None);
+        assert_eq!(schedule.leader(2), Some(leader));
+        assert_eq!(schedule.leader(3), None);
+    }
+    
+    #[test]
+    fn test_invalid_leader_rejected() {
+        let schedule = LeaderSchedule::new();
+        let epoch: HashMap<String, Vec<usize>> = [("not a pubkey".to_string(), vec![0])].into_iter().collect();
+        assert!(schedule.insert_epoch(0, 4, &epoch).is_err());
+        assert!(!schedule.has_epoch(0));
+    }
+}
@@ -0,0 +1,295 @@
+//! Missed Vote Module
+//!
+//! Finds confirmed slots that monitored validators never voted on. Confirmed
+//! slots come from the gRPC slot stream and voted slots from each validator's
+//! parsed votes (after tower diffing, so every voted slot is seen once). A
+//! confirmed slot is checked once the tip is `tolerance_slots` past it: every
+//! validator seen voting before that slot either voted on it or missed it.
+//!
+//! Validators are only checked from their first observed vote onwards, so
+//! newly monitored validators are not charged for slots before they were
+//! watched. Votes are recorded before they are queued for the pipeline, so
+//! votes later dropped by the vote queue overflow policy still count as voted.
+//!
+//! While no stream carrying a validator is up, its votes can't be seen. Slots
+//! from the outage that were not voted on (as far as we know) are counted as
+//! unobserved instead of missed, whether they were checked during the outage
+//! or fell in the part of the reconnect gap that could not be replayed.
+
+use chrono::Utc;
+use parking_lot::Mutex;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeSet, HashMap};
+
+use crate::models::{MissedVote, VoteCoverage, VoteLatency};
+
+/// Vote history of one validator
+struct ValidatorVotes {
+    vote_pubkey: Pubkey,
+    /// First slot the validator was seen voting on; earlier slots are not checked
+    first_voted_slot: u64,
+    /// Voted slots not yet checked against the confirmed slots
+    voted: BTreeSet<u64>,
+    /// Slot ranges `[start, end)` no stream observed; an open range is an ongoing outage
+    unobserved: Vec<(u64, Option<u64>)>,
+    coverage: VoteCoverage,
+}
+
+impl ValidatorVotes {
+    /// Whether the validator's votes for `slot` could not be seen
+    fn is_unobserved(&self, slot: u64) -> bool {
+        self.unobserved
+            .iter()
+            .any(|&(start, end)| slot >= start && end.is_none_or(|end| slot < end))
+    }
+}
+
+#[derive(Default)]
+struct DetectorState {
+    /// Confirmed slots waiting for the tolerance window to pass
+    confirmed: BTreeSet<u64>,
+    /// Highest slot checked so far
+    checked_through: Option<u64>,
+    validators: HashMap<Pubkey, ValidatorVotes>,
+}
+
+/// Tracks confirmed slots against the slots each validator voted on
+pub struct MissedVoteDetector {
+    tolerance_slots: u64,
+    state: Mutex<DetectorState>,
+}
+
+impl MissedVoteDetector {
+    /// Create a detector that waits `tolerance_slots` past a confirmed slot for votes
+    pub fn new(tolerance_slots: u64) -> Self {
+        Self {
+            tolerance_slots,
+            state: Mutex::new(DetectorState::default()),
+        }
+    }
+    
+    /// Record the slots a vote voted on
+    pub fn record_vote(&self, vote: &VoteLatency) {
+        let Some(first) = vote.voted_on_slots.iter().min().copied() else {
+            return;
+        };
+        
+        let mut state = self.state.lock();
+        let checked_through = state.checked_through;
+        let validator = state.validators.entry(vote.validator_pubkey).or_insert_with(|| ValidatorVotes {
+            vote_pubkey: vote.vote_pubkey,
+            first_voted_slot: first,
+            voted: BTreeSet::new(),
+            unobserved: Vec::new(),
+            coverage: VoteCoverage::default(),
+        });
+        
+        // Slots already checked were counted as missed; a vote this late doesn't change that
+        validator.voted.extend(
+            vote.voted_on_slots
+                .iter()
+                .filter(|&&slot| checked_through.is_none_or(|checked| slot > checked)),
+        );
+    }
+    
+    /// Record a confirmed slot, returning the misses of slots that left the tolerance window
+    pub fn on_confirmed(&self, slot: u64) -> Vec<MissedVote> {
+        let mut state = self.state.lock();
+        if state.checked_through.is_some_and(|checked| slot <= checked) {
+            return Vec::new();
+        }
+        state.confirmed.insert(slot);
+        
+        let Some(&tip) = state.confirmed.last() else {
+            return Vec::new();
+        };
+        let Some(cutoff) = tip.checked_sub(self.tolerance_slots) else {
+            return Vec::new();
+        };
+        
+        // Everything up to the cutoff is checked now
+        let waiting = state.confirmed.split_off(&(cutoff + 1));
+        let due = std::mem::replace(&mut state.confirmed, waiting);
+        state.checked_through = Some(state.checked_through.map_or(cutoff, |checked| checked.max(cutoff)));
+        
+        let detected_at = Utc::now();
+        let mut missed = Vec::new();
+        for (validator_pubkey, validator) in &mut state.validators {
+            let mut misses = 0;
+            for &slot in &due {
+                if slot < validator.first_voted_slot {
+                    continue;
+                }
+                
+                if validator.voted.contains(&slot) {
+                    validator.coverage.expected_slots += 1;
+                    validator.coverage.voted_slots += 1;
+                } else if validator.is_unobserved(slot) {
+                    validator.coverage.unobserved_slots += 1;
+                } else {
+                    validator.coverage.expected_slots += 1;
+                    // A nanosecond apart, so each miss is stored as its own point
+                    missed.push(MissedVote {
+                        validator_pubkey: *validator_pubkey,
+                        vote_pubkey: validator.vote_pubkey,
+                        slot,
+                        coverage: validator.coverage,
+                        detected_at: detected_at + chrono::Duration::nanoseconds(misses),
+                    });
+                    misses += 1;
+                }
+            }
+            validator.voted = validator.voted.split_off(&(cutoff + 1));
+            validator.unobserved.retain(|&(_, end)| end.is_none_or(|end| end > cutoff + 1));
+        }
+        
+        missed
+    }
+    
+    /// Vote coverage of a validator, if it was seen voting
+    pub fn coverage(&self, validator_pubkey: &Pubkey) -> Option<VoteCoverage> {
+        self.state.lock().validators.get(validator_pubkey).map(|validator| validator.coverage)
+    }
+    
+    /// Vote coverage summed over every validator
+    pub fn total_coverage(&self) -> VoteCoverage {
+        self.state.lock().validators.values().fold(VoteCoverage::default(), |mut total, validator| {
+            total.expected_slots += validator.coverage.expected_slots;
+            total.voted_slots += validator.coverage.voted_slots;
+            total.unobserved_slots += validator.coverage.unobserved_slots;
+            total
+        })
+    }
+    
+    /// Stop observing the validators of `vote_accounts`, e.g. when their stream drops
+    ///
+    /// Every slot not checked yet is unobserved until [`resume`](Self::resume).
+    pub fn pause(&self, vote_accounts: &[Pubkey]) {
+        let mut state = self.state.lock();
+        let start = state.checked_through.map_or(0, |checked| checked + 1);
+        for validator in state.validators.values_mut() {
+            if !vote_accounts.contains(&validator.vote_pubkey) {
+                continue;
+            }
+            if !validator.unobserved.iter().any(|(_, end)| end.is_none()) {
+                validator.unobserved.push((start, None));
+            }
+        }
+    }
+    
+    /// Observe the validators of `vote_accounts` again from `from_slot` onwards
+    pub fn resume(&self, vote_accounts: &[Pubkey], from_slot: u64) {
+        let mut state = self.state.lock();
+        for validator in state.validators.values_mut() {
+            if !vote_accounts.contains(&validator.vote_pubkey) {
+                continue;
+            }
+            validator.unobserved.retain_mut(|(start, end)| {
+                if end.is_none() {
+                    *end = Some(from_slot);
+                }
+                *start < from_slot
+            });
+        }
+    }
+    
+    /// Stop checking a validator, e.g. once it is no longer monitored
+    pub fn remove(&self, validator_pubkey: &Pubkey) {
+        self.state.lock().validators.remove(validator_pubkey);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn vote(validator: Pubkey, voted_on_slots: Vec<u64>) -> VoteLatency {
+        let landed_slot = voted_on_slots.iter().max().unwrap() + 1;
+        VoteLatency::new_with_slots(
+            validator,
+            Pubkey::new_unique(),
+            landed_slot - 1,
+            Utc::now(),
+            Utc::now(),
+            "sig".to_string(),
+            voted_on_slots,
+            landed_slot,
+        )
+    }
+    
+    #[test]
+    fn test_missed_slots_reported_after_tolerance() {
+        let detector = MissedVoteDetector::new(4);
+        let steady = Pubkey::new_unique();
+        let skipping = Pubkey::new_unique();
+        
+        detector.record_vote(&vote(steady, vec![100, 101, 102]));
+        detector.record_vote(&vote(skipping, vec![100]));
+        detector.record_vote(&vote(skipping, vec![102]));
+        
+        for slot in 100..=104 {
+            assert!(detector.on_confirmed(slot).is_empty());
+        }
+        // Slot 101 leaves the tolerance window
+        let missed = detector.on_confirmed(105);
+        assert_eq!(missed.len(), 1);
+        assert_eq!(missed[0].validator_pubkey, skipping);
+        assert_eq!(missed[0].slot, 101);
+        assert_eq!(missed[0].coverage, VoteCoverage { expected_slots: 2, voted_slots: 1, unobserved_slots: 0 });
+        
+        assert!(detector.on_confirmed(106).is_empty());
+        assert_eq!(detector.coverage(&steady), Some(VoteCoverage { expected_slots: 3, voted_slots: 3, unobserved_slots: 0 }));
+        assert_eq!(detector.coverage(&skipping).unwrap().missed_votes(), 1);
+    }
+    
+    #[test]
+    fn test_slots_before_first_vote_not_charged() {
+        let detector = MissedVoteDetector::new(2);
+        let validator = Pubkey::new_unique();
+        
+        detector.on_confirmed(10);
+        detector.record_vote(&vote(validator, vec![11]));
+        detector.on_confirmed(11);
+        detector.on_confirmed(12);
+        
+        // Slot 10 was checked but predates the validator's first vote
+        assert!(detector.on_confirmed(13).is_empty());
+        assert_eq!(detector.coverage(&validator), Some(VoteCoverage { expected_slots: 1, voted_slots: 1, unobserved_slots: 0 }));
+        
+        // Repeated confirmations from other streams are ignored
+        assert!(detector.on_confirmed(11).is_empty());
+        
+        detector.remove(&validator);
+        assert_eq!(detector.coverage(&validator), None);
+    }
+    
+    #[test]
+    fn test_outage_slots_unobserved_not_missed() {
+        let detector = MissedVoteDetector::new(2);
+        let validator = Pubkey::new_unique();
+        let vote_account = Pubkey::new_unique();
+        let mut first = vote(validator, vec![100]);
+        first.vote_pubkey = vote_account;
+        detector.record_vote(&first);
+        
+        // The stream drops after slot 100 and other streams keep confirming slots
+        detector.on_confirmed(100);
+        detector.pause(&[vote_account]);
+        for slot in 101..=105 {
+            assert!(detector.on_confirmed(slot).is_empty());
+        }
+        
+        // Reconnected without replay from slot 106; later slots are checked again
+        detector.resume(&[vote_account], 106);
+        detector.on_confirmed(106);
+        detector.on_confirmed(107);
+        let missed = detector.on_confirmed(108);
+        assert_eq!(missed.len(), 1);
+        assert_eq!(missed[0].slot, 106);
+        assert_eq!(
+            detector.coverage(&validator),
+            Some(VoteCoverage { expected_slots: 2, voted_slots: 1, unobserved_slots: 5 })
+        );
+        assert_eq!(detector.total_coverage().coverage_pct(), 50.0);
+    }
+}
@@ -2,16 +2,36 @@
 //!
 //! This module contains the main components of the monitoring system:
 //! - Validator discovery
+//! - Subscription admission
 //! - gRPC subscription management
+//! - Slot start times and block metadata
+//! - Leader schedule tracking
+//! - Vote landing finality tracking
+//! - Missed vote detection
+//! - Stream health tracking
 //! - Vote transaction parsing
+//! - Vote tower diffing
+//! - Vote account state tracking
+//! - Vote hash comparison
 //! - Latency calculation
 //! - Storage management
+//! - Vote queue with overflow policies
 
+pub mod admission;
+pub mod block_meta;
 pub mod calculator;
 pub mod discovery;
+pub mod finality;
+pub mod leader_schedule;
+pub mod missed_votes;
 pub mod parser;
 pub mod storage;
+pub mod stream_health;
 pub mod subscription;
+pub mod tower;
+pub mod vote_hash;
+pub mod vote_state;
+pub mod vote_queue;
 
 pub use calculator::LatencyCalculator;
 pub use discovery::ValidatorDiscovery;
@@ -75,7 +95,10 @@ impl ModuleManager {
         
         info!("Initializing InfluxDB storage...");
         let influxdb_storage = Arc::new(
-            crate::storage::InfluxDBStorage::new(self.config.influxdb.clone()).await?
+            crate::storage::InfluxDBStorage::new(
+                self.config.influxdb.clone(),
+                self.config.solana.network.clone(),
+            ).await?
         );
         
         self.storage = Some(influxdb_storage as Arc<dyn crate::modules::storage::StorageManagerTrait>);
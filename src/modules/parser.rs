@@ -5,6 +5,7 @@
 
 use anyhow::Result;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use solana_sdk::{
     instruction::CompiledInstruction,
     pubkey::Pubkey,
@@ -14,15 +15,60 @@ use solana_sdk::{
 use tracing::{debug, error, trace, warn};
 use yellowstone_grpc_proto::prelude::SubscribeUpdateTransactionInfo;
 
-use crate::models::{VoteLatency, VoteTransaction};
+use crate::models::{LatencyBreakdown, VoteAccountState, VoteLatency, VoteTransaction};
+
+/// Stream-side timestamps of a vote update
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamTiming {
+    /// When the landing slot started, as seen by the provider
+    pub landed_at: Option<DateTime<Utc>>,
+    
+    /// When the provider emitted the update (`SubscribeUpdate.created_at`)
+    pub created_at: Option<DateTime<Utc>>,
+    
+    /// When the update was received here
+    pub received_at: DateTime<Utc>,
+}
+
+impl StreamTiming {
+    /// Timing for an update received now with no provider timestamps
+    pub fn received_now() -> Self {
+        Self {
+            landed_at: None,
+            created_at: None,
+            received_at: Utc::now(),
+        }
+    }
+}
 
 /// Parse vote transaction from Yellowstone protobuf format
 /// This is a more direct approach that works with the pre-filtered vote transactions
+///
+/// Tower votes (`UpdateVoteState`, `TowerSync`) report every slot in the
+/// tower; use `VoteTowers` to keep only the newly voted ones.
 pub fn parse_yellowstone_vote_transaction(
     tx_info: &SubscribeUpdateTransactionInfo,
     validator_pubkey: Pubkey,
     vote_pubkey: Pubkey,
     slot: u64,
+) -> Result<VoteLatency> {
+    parse_yellowstone_vote_transaction_with_timing(
+        tx_info,
+        validator_pubkey,
+        vote_pubkey,
+        slot,
+        &StreamTiming::received_now(),
+    )
+}
+
+/// Parse vote transaction from Yellowstone protobuf format, measuring
+/// wall-clock latency from the vote's own timestamp to `timing.received_at`
+pub fn parse_yellowstone_vote_transaction_with_timing(
+    tx_info: &SubscribeUpdateTransactionInfo,
+    validator_pubkey: Pubkey,
+    vote_pubkey: Pubkey,
+    slot: u64,
+    timing: &StreamTiming,
 ) -> Result<VoteLatency> {
     debug!("Parsing Yellowstone vote transaction");
     
@@ -32,8 +78,10 @@ pub fn parse_yellowstone_vote_transaction(
     // The slot parameter is the landed slot
     let landed_slot = slot;
     
-    // Extract voted slots from the transaction data
+    // Extract voted slots and the vote's own timestamp from the transaction data
     let mut voted_on_slots = Vec::new();
+    let mut vote_unix_timestamp = None;
+    let mut vote_hash = None;
     
     // Check if we have transaction data
     if let Some(tx) = &tx_info.transaction {
@@ -66,10 +114,14 @@ pub fn parse_yellowstone_vote_transaction(
                                     VoteInstruction::Vote(vote) => {
                                         debug!("Decoded Vote instruction with {} slots", vote.slots.len());
                                         voted_on_slots.extend(&vote.slots);
+                                        vote_unix_timestamp = vote_unix_timestamp.or(vote.timestamp);
+                                        vote_hash = vote_hash.or(Some(vote.hash));
                                     }
                                     VoteInstruction::VoteSwitch(vote, _) => {
                                         debug!("Decoded VoteSwitch instruction with {} slots", vote.slots.len());
                                         voted_on_slots.extend(&vote.slots);
+                                        vote_unix_timestamp = vote_unix_timestamp.or(vote.timestamp);
+                                        vote_hash = vote_hash.or(Some(vote.hash));
                                     }
                                     VoteInstruction::UpdateVoteState(update) => {
                                         let slots: Vec<u64> = update.lockouts.iter()
@@ -77,6 +129,8 @@ pub fn parse_yellowstone_vote_transaction(
                                             .collect();
                                         debug!("Decoded UpdateVoteState instruction with {} slots", slots.len());
                                         voted_on_slots.extend(&slots);
+                                        vote_unix_timestamp = vote_unix_timestamp.or(update.timestamp);
+                                        vote_hash = vote_hash.or(Some(update.hash));
                                     }
                                     VoteInstruction::UpdateVoteStateSwitch(update, _) => {
                                         let slots: Vec<u64> = update.lockouts.iter()
@@ -84,26 +138,30 @@ pub fn parse_yellowstone_vote_transaction(
                                             .collect();
                                         debug!("Decoded UpdateVoteStateSwitch instruction with {} slots", slots.len());
                                         voted_on_slots.extend(&slots);
+                                        vote_unix_timestamp = vote_unix_timestamp.or(update.timestamp);
+                                        vote_hash = vote_hash.or(Some(update.hash));
                                     }
                                     VoteInstruction::TowerSync(tower_sync) => {
-                                        // Only take the most recent vote (last lockout)
-                                        if let Some(latest_lockout) = tower_sync.lockouts.back() {
-                                            let latest_slot = latest_lockout.slot();
-                                            debug!("Decoded TowerSync instruction, taking most recent vote: slot {}", latest_slot);
-                                            voted_on_slots.push(latest_slot);
-                                        } else {
-                                            debug!("Decoded TowerSync instruction with no lockouts");
-                                        }
+                                        // The whole tower is reported; `VoteTowers` diffs it
+                                        // against the validator's previous vote
+                                        let slots: Vec<u64> = tower_sync.lockouts.iter()
+                                            .map(|l| l.slot())
+                                            .collect();
+                                        debug!("Decoded TowerSync instruction with {} slots", slots.len());
+                                        voted_on_slots.extend(&slots);
+                                        vote_unix_timestamp = vote_unix_timestamp.or(tower_sync.timestamp);
+                                        vote_hash = vote_hash.or(Some(tower_sync.hash));
                                     }
                                     VoteInstruction::TowerSyncSwitch(tower_sync, _) => {
-                                        // Only take the most recent vote (last lockout)
-                                        if let Some(latest_lockout) = tower_sync.lockouts.back() {
-                                            let latest_slot = latest_lockout.slot();
-                                            debug!("Decoded TowerSyncSwitch instruction, taking most recent vote: slot {}", latest_slot);
-                                            voted_on_slots.push(latest_slot);
-                                        } else {
-                                            debug!("Decoded TowerSyncSwitch instruction with no lockouts");
-                                        }
+                                        // The whole tower is reported; `VoteTowers` diffs it
+                                        // against the validator's previous vote
+                                        let slots: Vec<u64> = tower_sync.lockouts.iter()
+                                            .map(|l| l.slot())
+                                            .collect();
+                                        debug!("Decoded TowerSyncSwitch instruction with {} slots", slots.len());
+                                        voted_on_slots.extend(&slots);
+                                        vote_unix_timestamp = vote_unix_timestamp.or(tower_sync.timestamp);
+                                        vote_hash = vote_hash.or(Some(tower_sync.hash));
                                     }
                                     _ => {
                                         trace!("Vote instruction type does not contain vote data");
@@ -132,17 +190,25 @@ pub fn parse_yellowstone_vote_transaction(
         debug!("Extracted {} unique voted slots: {:?}", voted_on_slots.len(), voted_on_slots);
     }
     
-    // Use current time as timestamps (approximation for real-time processing)
-    let vote_timestamp = chrono::Utc::now();
-    let received_timestamp = chrono::Utc::now();
+    // `latency_ms` runs from the start of the landing slot to receipt. The
+    // vote's own timestamp is only set when the unix second changes and is
+    // truncated to it, so it only feeds the creation-to-landing stage
+    let vote_created_at = vote_unix_timestamp.and_then(|ts| DateTime::from_timestamp(ts, 0));
+    let vote_timestamp = timing.landed_at.unwrap_or(timing.received_at);
+    let received_timestamp = timing.received_at;
+    let breakdown = LatencyBreakdown::from_timestamps(
+        vote_created_at,
+        timing.landed_at,
+        timing.created_at,
+        timing.received_at,
+    );
     
     // Find the highest voted slot for backward compatibility
     let highest_voted_slot = voted_on_slots.iter().max().copied().unwrap_or(slot);
     
-    // For TowerSync, we only track the most recent vote, so use single-value constructor
-    // if we have exactly one voted slot (which is typical for TowerSync)
-    if voted_on_slots.len() == 1 {
-        Ok(VoteLatency::new_single_vote(
+    // Use the single-value constructor when the transaction votes on one slot
+    let mut vote_latency = if voted_on_slots.len() == 1 {
+        VoteLatency::new_single_vote(
             validator_pubkey,
             vote_pubkey,
             voted_on_slots[0],
@@ -150,10 +216,10 @@ pub fn parse_yellowstone_vote_transaction(
             received_timestamp,
             signature,
             landed_slot,
-        ))
+        )
     } else {
-        // Fall back to multi-slot constructor for other vote types
-        Ok(VoteLatency::new_with_slots(
+        // Tower votes report every lockout slot
+        VoteLatency::new_with_slots(
             validator_pubkey,
             vote_pubkey,
             highest_voted_slot,
@@ -162,8 +228,12 @@ pub fn parse_yellowstone_vote_transaction(
             signature,
             voted_on_slots,
             landed_slot,
-        ))
-    }
+        )
+    };
+    vote_latency.breakdown = breakdown;
+    vote_latency.vote_hash = vote_hash.map(|hash| hash.to_string());
+    
+    Ok(vote_latency)
 }
 
 /// Vote program ID on Solana
@@ -322,9 +392,9 @@ impl VoteParser {
             .collect()
     }
     
-    /// Extract voted slots from raw transaction data
+    /// Extract voted slots and the vote hash from raw transaction data
     /// This deserializes the transaction and extracts vote instructions
-    fn extract_voted_slots_from_raw_data(&self, raw_data: &[u8]) -> Result<Vec<u64>> {
+    fn extract_voted_slots_from_raw_data(&self, raw_data: &[u8]) -> Result<(Vec<u64>, Option<solana_sdk::hash::Hash>)> {
         debug!("Extracting voted slots from {} bytes of raw data", raw_data.len());
         
         // Deserialize the transaction from raw bytes
@@ -341,11 +411,12 @@ impl VoteParser {
         
         if vote_instructions.is_empty() {
             warn!("No vote instructions found in transaction");
-            return Ok(vec![]);
+            return Ok((vec![], None));
         }
         
         // Collect all voted slots from all vote instructions
         let mut all_slots = Vec::new();
+        let mut vote_hash = None;
         
         for instruction in vote_instructions {
             trace!("Processing vote instruction with {} bytes of data", instruction.data.len());
@@ -355,6 +426,7 @@ impl VoteParser {
                 Ok(vote_info) => {
                     debug!("Found {} slots in vote instruction", vote_info.slots.len());
                     all_slots.extend(vote_info.slots);
+                    vote_hash = vote_hash.or(Some(vote_info.hash));
                 }
                 Err(e) => {
                     warn!("Failed to parse vote instruction: {}", e);
@@ -368,7 +440,7 @@ impl VoteParser {
         all_slots.dedup();
         
         debug!("Extracted {} unique voted slots", all_slots.len());
-        Ok(all_slots)
+        Ok((all_slots, vote_hash))
     }
 }
 
@@ -386,11 +458,13 @@ impl VoteParserTrait for VoteParser {
         // The landed slot is provided in the VoteTransaction
         let landed_slot = vote_tx.landed_slot.unwrap_or(vote_tx.slot);
         
-        // Extract voted_on_slots from the transaction data
+        // Extract voted_on_slots and the vote hash from the transaction data
+        let mut vote_hash = None;
         let voted_on_slots = if !vote_tx.raw_data.is_empty() {
             match self.extract_voted_slots_from_raw_data(&vote_tx.raw_data) {
-                Ok(slots) => {
+                Ok((slots, hash)) => {
                     debug!("Extracted {} voted slots from transaction", slots.len());
+                    vote_hash = hash;
                     slots
                 }
                 Err(e) => {
@@ -455,8 +529,8 @@ impl VoteParserTrait for VoteParser {
         let highest_voted_slot = voted_on_slots.iter().max().copied().unwrap_or(vote_tx.slot);
         
         // Use single-value constructor when we have exactly one voted slot
-        if voted_on_slots.len() == 1 {
-            Ok(VoteLatency::new_single_vote(
+        let mut vote_latency = if voted_on_slots.len() == 1 {
+            VoteLatency::new_single_vote(
                 vote_tx.validator_pubkey.clone(),
                 vote_tx.vote_pubkey.clone(),
                 voted_on_slots[0],
@@ -464,9 +538,9 @@ impl VoteParserTrait for VoteParser {
                 received_timestamp,
                 vote_tx.signature.clone(),
                 landed_slot,
-            ))
+            )
         } else {
-            Ok(VoteLatency::new_with_slots(
+            VoteLatency::new_with_slots(
                 vote_tx.validator_pubkey.clone(),
                 vote_tx.vote_pubkey.clone(),
                 highest_voted_slot,
@@ -475,8 +549,12 @@ impl VoteParserTrait for VoteParser {
                 vote_tx.signature.clone(),
                 voted_on_slots,
                 landed_slot,
-            ))
-        }
+            )
+        };
+        vote_latency.landing_status = vote_tx.landing_status;
+        vote_latency.vote_hash = vote_hash.map(|hash| hash.to_string());
+        
+        Ok(vote_latency)
     }
 
     async fn is_vote_transaction(&self, transaction: &Transaction) -> bool {
@@ -491,76 +569,52 @@ impl VoteParserTrait for VoteParser {
 /// Parse vote account data to extract vote state
 /// This function is called when we receive account updates for vote accounts
 /// NOTE: Account updates do not provide accurate landed slot information,
-/// so this is primarily for tracking vote state, not for latency calculation
+/// so this is for tracking vote state, not for latency calculation
 pub fn parse_vote_account_data(
     account_data: &[u8],
     validator_pubkey: Pubkey,
-    _vote_pubkey: Pubkey,
-    _account_slot: u64,  // The slot when account was updated - not accurate for latency
-) -> Result<Vec<VoteLatency>> {
+    vote_pubkey: Pubkey,
+    account_slot: u64,  // The slot when account was updated - not accurate for latency
+) -> Result<VoteAccountState> {
     use solana_sdk::vote::state::{VoteState, VoteStateVersions};
     
     debug!("Parsing vote account data for validator {} (data length: {} bytes)", 
         validator_pubkey, account_data.len());
     
-    // Check minimum data length (4 bytes for version + vote state data)
+    // Check minimum data length (4 bytes for the version tag + vote state data)
     if account_data.len() < 4 {
         return Err(anyhow::anyhow!("Vote account data too short: {} bytes", account_data.len()));
     }
     
-    // The vote account data format has a 4-byte version prefix
-    let version_bytes = &account_data[0..4];
-    let version = u32::from_le_bytes([
-        version_bytes[0], 
-        version_bytes[1], 
-        version_bytes[2], 
-        version_bytes[3]
-    ]);
+    // Vote accounts hold a bincode serialized VoteStateVersions; its 4-byte
+    // enum tag is the version, and the account is zero padded past the state
+    let versions = bincode::deserialize::<VoteStateVersions>(account_data)
+        .map_err(|e| anyhow::anyhow!("Failed to deserialize vote state: {}", e))?;
     
-    debug!("Vote account version: {}", version);
-    
-    // Get the actual vote state data after the version prefix
-    let vote_state_data = &account_data[4..];
-    
-    // Deserialize the vote state using bincode (Solana uses bincode for vote state)
-    // Try to deserialize as VoteStateVersions which handles different versions
-    let vote_state = match bincode::deserialize::<VoteStateVersions>(vote_state_data) {
-        Ok(versions) => {
-            // Extract the current VoteState from the versioned enum
-            match versions {
-                VoteStateVersions::V0_23_5(_state) => {
-                    // V0_23_5 is very old and has a different structure
-                    // For now, return empty as these are unlikely to be encountered
-                    warn!("Encountered old V0_23_5 vote state format, skipping");
-                    return Ok(vec![]);
-                }
-                VoteStateVersions::V1_14_11(state) => {
-                    // Convert V1_14_11 to current - this version has similar structure
-                    let mut current = VoteState::default();
-                    current.node_pubkey = state.node_pubkey;
-                    current.authorized_withdrawer = state.authorized_withdrawer;
-                    current.commission = state.commission;
-                    // Convert the votes - V1_14_11 uses Lockout, current uses LandedVote
-                    current.votes = state.votes.into_iter()
-                        .map(|lockout| lockout.into())
-                        .collect();
-                    current.root_slot = state.root_slot;
-                    current.authorized_voters = state.authorized_voters;
-                    // Note: prior_voters has different tuple structure between versions
-                    // For simplicity, we'll leave it as default
-                    current.epoch_credits = state.epoch_credits;
-                    current.last_timestamp = state.last_timestamp;
-                    current
-                }
-                VoteStateVersions::Current(state) => *state,
-            }
+    let vote_state = match versions {
+        VoteStateVersions::V0_23_5(_state) => {
+            // V0_23_5 is very old and has a different structure
+            return Err(anyhow::anyhow!("Unsupported V0_23_5 vote state format"));
         }
-        Err(e) => {
-            // If that fails, try direct VoteState deserialization
-            debug!("Failed to deserialize as VoteStateVersions: {}, trying direct VoteState", e);
-            bincode::deserialize::<VoteState>(vote_state_data)
-                .map_err(|e| anyhow::anyhow!("Failed to deserialize vote state: {}", e))?
+        VoteStateVersions::V1_14_11(state) => {
+            // Convert V1_14_11 to current - this version has similar structure
+            let mut current = VoteState::default();
+            current.node_pubkey = state.node_pubkey;
+            current.authorized_withdrawer = state.authorized_withdrawer;
+            current.commission = state.commission;
+            // Convert the votes - V1_14_11 uses Lockout, current uses LandedVote
+            current.votes = state.votes.into_iter()
+                .map(|lockout| lockout.into())
+                .collect();
+            current.root_slot = state.root_slot;
+            current.authorized_voters = state.authorized_voters;
+            // Note: prior_voters has different tuple structure between versions
+            // For simplicity, we'll leave it as default
+            current.epoch_credits = state.epoch_credits;
+            current.last_timestamp = state.last_timestamp;
+            current
         }
+        VoteStateVersions::Current(state) => *state,
     };
     
     debug!("Vote state has {} votes in tower", vote_state.votes.len());
@@ -569,28 +623,28 @@ pub fn parse_vote_account_data(
     // because we don't know when the vote transaction actually landed.
     // Account updates happen asynchronously and the slot of the account update
     // is not the same as the slot when the vote transaction was processed.
+    let (epoch, epoch_credits) = vote_state
+        .epoch_credits
+        .last()
+        .map(|&(epoch, credits, prev_credits)| (Some(epoch), credits.saturating_sub(prev_credits)))
+        .unwrap_or((None, 0));
     
-    // For now, we return an empty vector since account-based latency is unreliable
-    // In the future, we could use this to track vote state for other purposes
-    let vote_latencies = Vec::new();
-    
-    // Log vote state information for debugging
-    let recent_votes: Vec<_> = vote_state.votes
-        .iter()
-        .rev()
-        .take(5)
-        .collect();
-    
-    debug!("Vote account has {} votes in tower, most recent: {:?}", 
-        vote_state.votes.len(),
-        recent_votes.iter().map(|v| v.slot()).collect::<Vec<_>>()
-    );
-    
-    // Log additional vote state information if available
-    debug!("Vote state last timestamp: {:?}", vote_state.last_timestamp);
-    
-    debug!("Extracted {} vote latencies from account data", vote_latencies.len());
-    Ok(vote_latencies)
+    Ok(VoteAccountState {
+        validator_pubkey,
+        vote_pubkey,
+        slot: account_slot,
+        node_pubkey: vote_state.node_pubkey,
+        commission: vote_state.commission,
+        root_slot: vote_state.root_slot,
+        last_voted_slot: vote_state.last_voted_slot(),
+        lockout_depth: vote_state.votes.len() as u32,
+        epoch,
+        epoch_credits,
+        total_credits: vote_state.credits(),
+        last_timestamp_slot: vote_state.last_timestamp.slot,
+        last_timestamp: vote_state.last_timestamp.timestamp,
+        observed_at: Utc::now(),
+    })
 }
 
 /// Vote instruction information
@@ -610,6 +664,7 @@ struct VoteInfo {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::LandingStatus;
     use solana_sdk::{
         hash::Hash,
         message::Message,
@@ -707,6 +762,7 @@ mod tests {
             raw_data: vec![], // Empty for this test
             voted_on_slots: vec![12340, 12341, 12342, 12343, 12344, 12345],
             landed_slot: Some(12350),
+            landing_status: LandingStatus::Finalized,
         };
         
         // Parse the transaction
@@ -744,6 +800,7 @@ mod tests {
             raw_data: vec![], // Empty for this test
             voted_on_slots: vec![12345], // Single slot
             landed_slot: Some(12350),
+            landing_status: LandingStatus::Finalized,
         };
         
         // Parse the transaction
@@ -758,6 +815,7 @@ mod tests {
         // Check calculated latencies
         assert_eq!(result.latency_slots, vec![5]);
         assert_eq!(result.latency_slot(), 5);
+        assert_eq!(result.landing_status, LandingStatus::Finalized);
         assert_eq!(result.max_latency_slots(), 5);
         assert_eq!(result.avg_latency_slots(), 5.0);
         
@@ -765,6 +823,41 @@ mod tests {
         assert!(result.verify_slot_latency());
     }
     
+    #[tokio::test]
+    async fn test_parse_vote_transaction_from_raw_data() {
+        use solana_sdk::vote::state::Vote;
+        
+        let parser = VoteParser::new().unwrap();
+        let vote_pubkey = Pubkey::new_unique();
+        let authority = Keypair::new();
+        let hash = Hash::new_unique();
+        
+        let instruction = solana_sdk::vote::instruction::vote(
+            &vote_pubkey,
+            &authority.pubkey(),
+            Vote::new(vec![200, 201], hash),
+        );
+        let message = Message::new(&[instruction], Some(&authority.pubkey()));
+        let transaction = Transaction::new_unsigned(message);
+        
+        let vote_tx = VoteTransaction {
+            signature: "test_sig_raw".to_string(),
+            validator_pubkey: authority.pubkey(),
+            vote_pubkey,
+            slot: 201,
+            timestamp: chrono::Utc::now(),
+            raw_data: bincode::serialize(&transaction).unwrap(),
+            voted_on_slots: vec![],
+            landed_slot: Some(203),
+            landing_status: LandingStatus::Processed,
+        };
+        
+        let result = parser.parse(&vote_tx).await.unwrap();
+        assert_eq!(result.voted_on_slots, vec![200, 201]);
+        assert_eq!(result.latency_slots, vec![3, 2]);
+        assert_eq!(result.vote_hash, Some(hash.to_string()));
+    }
+    
     #[test]
     fn test_parse_vote_instruction_data() {
         use solana_sdk::vote::state::Vote;
@@ -790,7 +883,7 @@ mod tests {
     
     #[test]
     fn test_parse_vote_account_data() {
-        use solana_sdk::vote::state::{Lockout, VoteStateVersions};
+        use solana_sdk::vote::state::{BlockTimestamp, Lockout, VoteStateVersions};
         
         // Create test data
         let validator_pubkey = Pubkey::new_unique();
@@ -799,6 +892,11 @@ mod tests {
         
         // Create a test vote state with some votes
         let mut vote_state = solana_sdk::vote::state::VoteState::default();
+        vote_state.node_pubkey = validator_pubkey;
+        vote_state.commission = 5;
+        vote_state.root_slot = Some(960);
+        vote_state.epoch_credits = vec![(9, 4_000, 0), (10, 6_400, 4_000)];
+        vote_state.last_timestamp = BlockTimestamp { slot: 999, timestamp: 1_700_000_000 };
         
         // Add some test votes to the tower
         // In newer versions, votes are LandedVote, not Lockout
@@ -807,26 +905,29 @@ mod tests {
         vote_state.votes.push_back(Lockout::new_with_confirmation_count(998, 3).into());
         vote_state.votes.push_back(Lockout::new_with_confirmation_count(999, 4).into());
         
-        // Wrap in VoteStateVersions
-        let vote_state_versions = VoteStateVersions::Current(Box::new(vote_state));
-        
-        // Serialize the vote state using bincode
-        let vote_state_data = bincode::serialize(&vote_state_versions).unwrap();
-        
-        // Create the full account data with 4-byte version prefix
-        let mut account_data = vec![1, 0, 0, 0]; // Version 1
-        account_data.extend_from_slice(&vote_state_data);
+        // Vote accounts hold the versioned state, zero padded to the account size
+        let mut account_data = bincode::serialize(&VoteStateVersions::Current(Box::new(vote_state))).unwrap();
+        account_data.resize(3762, 0);
         
         // Parse the account data
-        let result = parse_vote_account_data(
+        let state = parse_vote_account_data(
             &account_data,
             validator_pubkey,
             vote_pubkey,
             account_slot
         ).unwrap();
         
-        // Verify results - should be empty since we're not calculating latencies from account data
-        assert_eq!(result.len(), 0); // No latencies from account data
+        assert_eq!(state.vote_pubkey, vote_pubkey);
+        assert_eq!(state.node_pubkey, validator_pubkey);
+        assert_eq!(state.slot, account_slot);
+        assert_eq!(state.commission, 5);
+        assert_eq!(state.root_slot, Some(960));
+        assert_eq!(state.last_voted_slot, Some(999));
+        assert_eq!(state.lockout_depth, 4);
+        assert_eq!(state.epoch, Some(10));
+        assert_eq!(state.epoch_credits, 2_400);
+        assert_eq!(state.total_credits, 6_400);
+        assert_eq!(state.last_timestamp, 1_700_000_000);
     }
     
     #[test]
@@ -91,6 +91,7 @@ mod tests {
         // Verify extracted slots
         assert_eq!(result.voted_on_slots, voted_slots);
         assert_eq!(result.landed_slot, landed_slot);
+        assert_eq!(result.vote_hash, Some(Hash::default().to_string()));
         
         // Verify latencies
         let expected_latencies = vec![10, 8, 6, 4, 2];
@@ -100,6 +101,47 @@ mod tests {
         assert_eq!(result.max_latency_slots(), 10);
     }
 
+    #[test]
+    fn test_parse_yellowstone_vote_transaction_wall_clock_latency() {
+        // The test vote is stamped 1234567890 by the validator
+        let vote_created_at = chrono::DateTime::from_timestamp(1234567890, 0).unwrap();
+        let landed_at = vote_created_at + chrono::Duration::milliseconds(900);
+        let created_at = landed_at + chrono::Duration::milliseconds(250);
+        let received_at = created_at + chrono::Duration::milliseconds(30);
+        let timing = StreamTiming {
+            landed_at: Some(landed_at),
+            created_at: Some(created_at),
+            received_at,
+        };
+        
+        let tx_info = create_test_vote_tx_info(vec![12348]);
+        let result = parse_yellowstone_vote_transaction_with_timing(
+            &tx_info,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            12350,
+            &timing,
+        ).unwrap();
+        
+        assert_eq!(result.vote_timestamp, landed_at);
+        assert_eq!(result.received_timestamp, received_at);
+        assert_eq!(result.latency_ms, 280);
+        assert_eq!(result.breakdown.create_to_land_ms, Some(900));
+        assert_eq!(result.breakdown.land_to_emit_ms, Some(250));
+        assert_eq!(result.breakdown.emit_to_receive_ms, Some(30));
+        
+        // The vote's timestamp doesn't stand in for an unknown landing time
+        let result = parse_yellowstone_vote_transaction(
+            &tx_info,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            12350,
+        ).unwrap();
+        assert_eq!(result.vote_timestamp, result.received_timestamp);
+        assert_eq!(result.latency_ms, 0);
+        assert_eq!(result.breakdown, LatencyBreakdown::default());
+    }
+    
     #[test]
     fn test_parse_yellowstone_vote_transaction_empty_slots() {
         let validator_pubkey = Pubkey::new_unique();
@@ -200,4 +242,34 @@ mod tests {
         assert_eq!(result.landed_slot, landed_slot);
         assert_eq!(result.max_latency_slots(), 10);
     }
+
+    #[test]
+    fn test_parse_yellowstone_tower_sync_reports_whole_tower() {
+        use crate::modules::tower::VoteTowers;
+        use solana_sdk::vote::state::TowerSync;
+        
+        let validator_pubkey = Pubkey::new_unique();
+        let vote_pubkey = Pubkey::new_unique();
+        let tower_sync_tx = |slots: Vec<(u64, u32)>| {
+            let mut tx_info = create_test_vote_tx_info(vec![]);
+            let message = tx_info.transaction.as_mut().unwrap().message.as_mut().unwrap();
+            message.instructions[0].data =
+                bincode::serialize(&VoteInstruction::TowerSync(TowerSync::from(slots))).unwrap();
+            tx_info
+        };
+        let towers = VoteTowers::new();
+        
+        let tx_info = tower_sync_tx(vec![(96, 3), (98, 2), (99, 1)]);
+        let mut result = parse_yellowstone_vote_transaction(&tx_info, validator_pubkey, vote_pubkey, 100).unwrap();
+        assert_eq!(result.voted_on_slots, vec![96, 98, 99]);
+        assert!(towers.retain_new_slots(&mut result, false));
+        assert_eq!(result.voted_on_slots, vec![99]);
+        
+        // Catching up: the next vote adds three slots, each with its own latency
+        let tx_info = tower_sync_tx(vec![(98, 5), (99, 4), (100, 3), (101, 2), (102, 1)]);
+        let mut result = parse_yellowstone_vote_transaction(&tx_info, validator_pubkey, vote_pubkey, 104).unwrap();
+        assert!(towers.retain_new_slots(&mut result, false));
+        assert_eq!(result.voted_on_slots, vec![100, 101, 102]);
+        assert_eq!(result.latency_slots, vec![4, 3, 2]);
+    }
 }
\ No newline at end of file
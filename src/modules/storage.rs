@@ -7,7 +7,7 @@ use chrono::{DateTime, Utc};
 use solana_sdk::pubkey::Pubkey;
 
 use crate::error::Result;
-use crate::models::{LatencyMetrics, ValidatorInfo, VoteLatency};
+use crate::models::{ForkVote, HashMismatch, LatencyMetrics, MissedVote, ValidatorInfo, VoteAccountUpdate, VoteLatency};
 
 /// Trait for storage implementations
 #[async_trait]
@@ -18,6 +18,18 @@ pub trait StorageManagerTrait: Send + Sync {
     /// Store a vote latency record
     async fn store_vote_latency(&self, latency: &VoteLatency) -> Result<()>;
     
+    /// Store a confirmed slot a validator did not vote on
+    async fn store_missed_vote(&self, missed: &MissedVote) -> Result<()>;
+    
+    /// Store a vote for slots that ended up off the finalized chain
+    async fn store_fork_vote(&self, fork_vote: &ForkVote) -> Result<()>;
+    
+    /// Store a vote whose bank hash disagreed with the cluster's
+    async fn store_hash_mismatch(&self, mismatch: &HashMismatch) -> Result<()>;
+    
+    /// Store a vote account state update
+    async fn store_vote_account_update(&self, update: &VoteAccountUpdate) -> Result<()>;
+    
     /// Store aggregated metrics
     async fn store_metrics(
         &self,
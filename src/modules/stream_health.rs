@@ -0,0 +1,303 @@
+//! Stream Health Module
+//!
+//! Tracks the liveness of every gRPC stream owned by the subscription manager.
+//! Stream tasks report each received message and the last slot they saw; the
+//! manager's health check uses this to tear down streams that went silent or
+//! fell behind the cluster, and to report each stream's state.
+
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Identifies a stream owned by the subscription manager
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StreamId {
+    /// Dedicated stream for a validator, by identity pubkey
+    Validator(Pubkey),
+    /// Shared multiplexed stream, by shard index
+    Shard(usize),
+}
+
+/// Lifecycle state of a stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StreamState {
+    /// Connecting and sending the subscription request
+    Connecting,
+    /// Subscribed and receiving updates
+    Streaming,
+    /// Torn down by the health check, about to reconnect
+    Stalled,
+    /// Waiting before the next reconnect attempt
+    BackingOff,
+    /// Stopped after an error that retrying can't fix
+    Failed,
+}
+
+impl StreamState {
+    /// Label used in logs and metrics
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StreamState::Connecting => "connecting",
+            StreamState::Streaming => "streaming",
+            StreamState::Stalled => "stalled",
+            StreamState::BackingOff => "backing_off",
+            StreamState::Failed => "failed",
+        }
+    }
+}
+
+/// Liveness snapshot of a single stream
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamHealth {
+    /// Current lifecycle state
+    pub state: StreamState,
+    
+    /// When the stream last received any message, including pings
+    pub last_message_at: Option<DateTime<Utc>>,
+    
+    /// Last slot seen on the stream
+    pub last_slot: Option<u64>,
+    
+    /// Whether the stream is replaying a reconnect gap (it lags by design)
+    pub replaying: bool,
+    
+    /// Endpoint the stream is connected or connecting to
+    pub endpoint: Option<String>,
+}
+
+/// Why the health check considers a stream stalled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StallReason {
+    /// No message received for this long
+    Silent(Duration),
+    /// Last slot is this many slots behind the highest slot seen
+    Lagging(u64),
+}
+
+/// Limits beyond which a streaming connection is torn down
+#[derive(Debug, Clone, Copy)]
+pub struct StallThresholds {
+    /// Maximum time without any message
+    pub max_silence: Duration,
+    
+    /// Maximum distance behind the highest slot seen across streams
+    pub max_slot_lag: u64,
+}
+
+impl StreamHealth {
+    /// Check the stream against the stall thresholds
+    ///
+    /// Only streaming connections are checked; connecting streams are bounded
+    /// by the connection timeout and backing-off streams are already down.
+    pub fn check(
+        &self,
+        now: DateTime<Utc>,
+        highest_slot: u64,
+        thresholds: &StallThresholds,
+    ) -> Option<StallReason> {
+        if self.state != StreamState::Streaming {
+            return None;
+        }
+        
+        if let Some(last_message_at) = self.last_message_at {
+            let silence = (now - last_message_at).to_std().unwrap_or_default();
+            if silence > thresholds.max_silence {
+                return Some(StallReason::Silent(silence));
+            }
+        }
+        
+        if !self.replaying {
+            if let Some(last_slot) = self.last_slot {
+                let lag = highest_slot.saturating_sub(last_slot);
+                if lag > thresholds.max_slot_lag {
+                    return Some(StallReason::Lagging(lag));
+                }
+            }
+        }
+        
+        None
+    }
+}
+
+/// Shared handle between a stream task and the health check
+pub struct StreamMonitor {
+    health: Mutex<StreamHealth>,
+    /// Set until the stream task picks up a teardown request
+    teardown_pending: AtomicBool,
+    teardown: Notify,
+}
+
+impl StreamMonitor {
+    /// Create a monitor for a stream that is about to connect
+    pub fn new() -> Self {
+        Self {
+            health: Mutex::new(StreamHealth {
+                state: StreamState::Connecting,
+                last_message_at: None,
+                last_slot: None,
+                replaying: false,
+                endpoint: None,
+            }),
+            teardown_pending: AtomicBool::new(false),
+            teardown: Notify::new(),
+        }
+    }
+    
+    /// Current liveness snapshot
+    pub fn health(&self) -> StreamHealth {
+        self.health.lock().clone()
+    }
+    
+    /// Move the stream to a new state
+    pub fn set_state(&self, state: StreamState) {
+        self.health.lock().state = state;
+    }
+    
+    /// Mark the stream as connecting to an endpoint
+    pub fn start_connecting(&self, endpoint: &str) {
+        let mut health = self.health.lock();
+        health.state = StreamState::Connecting;
+        // A request against the previous connection is moot
+        self.teardown_pending.store(false, Ordering::Release);
+        if health.endpoint.as_deref() != Some(endpoint) {
+            // Slots from another endpoint say nothing about this one
+            health.endpoint = Some(endpoint.to_string());
+            health.last_slot = None;
+        }
+    }
+    
+    /// Mark the stream as subscribed; silence is measured from here
+    pub fn start_streaming(&self, replaying: bool) {
+        let mut health = self.health.lock();
+        health.state = StreamState::Streaming;
+        health.last_message_at = Some(Utc::now());
+        health.replaying = replaying;
+    }
+    
+    /// Record a received message, with its slot if it carries one
+    pub fn record_message(&self, slot: Option<u64>) {
+        let mut health = self.health.lock();
+        health.last_message_at = Some(Utc::now());
+        if let Some(slot) = slot {
+            health.last_slot = Some(health.last_slot.map_or(slot, |last| last.max(slot)));
+        }
+    }
+    
+    /// Mark the end of a replay, after which slot lag is checked again
+    pub fn finish_replay(&self) {
+        self.health.lock().replaying = false;
+    }
+    
+    /// Mark the stream stalled and wake its task so it reconnects
+    ///
+    /// The request is kept until the task picks it up, so it isn't lost while
+    /// the task is busy with an update rather than waiting on the stream.
+    pub fn request_teardown(&self) {
+        self.set_state(StreamState::Stalled);
+        self.teardown_pending.store(true, Ordering::Release);
+        self.teardown.notify_waiters();
+    }
+    
+    /// Resolves when the health check requests a teardown
+    pub async fn teardown_requested(&self) {
+        loop {
+            // Registered before checking the flag so a request in between wakes it
+            let notified = self.teardown.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            
+            if self.teardown_pending.swap(false, Ordering::AcqRel) {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+impl Default for StreamMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn thresholds() -> StallThresholds {
+        StallThresholds {
+            max_silence: Duration::from_secs(30),
+            max_slot_lag: 100,
+        }
+    }
+    
+    #[test]
+    fn test_healthy_stream_passes() {
+        let monitor = StreamMonitor::new();
+        monitor.start_streaming(false);
+        monitor.record_message(Some(1_000));
+        
+        assert_eq!(monitor.health().check(Utc::now(), 1_050, &thresholds()), None);
+    }
+    
+    #[test]
+    fn test_silent_stream_is_stalled() {
+        let monitor = StreamMonitor::new();
+        monitor.start_streaming(false);
+        
+        let later = Utc::now() + chrono::Duration::seconds(45);
+        assert!(matches!(
+            monitor.health().check(later, 0, &thresholds()),
+            Some(StallReason::Silent(_))
+        ));
+    }
+    
+    #[test]
+    fn test_lagging_stream_is_stalled_unless_replaying() {
+        let monitor = StreamMonitor::new();
+        monitor.start_streaming(true);
+        monitor.record_message(Some(1_000));
+        assert_eq!(monitor.health().check(Utc::now(), 1_500, &thresholds()), None);
+        
+        monitor.finish_replay();
+        assert_eq!(
+            monitor.health().check(Utc::now(), 1_500, &thresholds()),
+            Some(StallReason::Lagging(500))
+        );
+    }
+    
+    #[test]
+    fn test_only_streaming_state_is_checked() {
+        let monitor = StreamMonitor::new();
+        let later = Utc::now() + chrono::Duration::seconds(120);
+        assert_eq!(monitor.health().check(later, 10_000, &thresholds()), None);
+        
+        monitor.start_streaming(false);
+        monitor.request_teardown();
+        assert_eq!(monitor.health().state, StreamState::Stalled);
+        assert_eq!(monitor.health().check(later, 10_000, &thresholds()), None);
+    }
+    
+    #[tokio::test]
+    async fn test_teardown_requested_while_busy_is_kept() {
+        let monitor = StreamMonitor::new();
+        monitor.start_connecting("http://a");
+        monitor.start_streaming(false);
+        
+        // Nobody is waiting when the health check fires
+        monitor.request_teardown();
+        tokio::time::timeout(Duration::from_secs(1), monitor.teardown_requested())
+            .await
+            .expect("teardown request was lost");
+        
+        // Picked up once, and cleared for the next connection
+        monitor.request_teardown();
+        monitor.start_connecting("http://a");
+        assert!(tokio::time::timeout(Duration::from_millis(50), monitor.teardown_requested())
+            .await
+            .is_err());
+    }
+}
@@ -3,28 +3,67 @@
 //! This module manages gRPC connections to validator nodes and handles
 //! subscription to their transaction streams. It maintains active connections,
 //! handles reconnections, and distributes incoming transactions to parsers.
+//!
+//! Two stream layouts are supported (see [`SubscriptionMode`]):
+//! - per-validator: one connection and subscription per validator
+//! - multiplexed: a small pool of shared streams whose vote filters cover many
+//!   validators; votes are routed back to their validator by vote account
+//!
+//! After a reconnect each stream asks the endpoint to replay from the last slot
+//! it saw a vote land in (`from_slot`), and drops replayed duplicates by
+//! signature.
+//!
+//! With several endpoints configured, each stream either fails over down the
+//! list or, in race mode, subscribes to all of them at once; the shared
+//! signature cache keeps the first copy of each vote and records which
+//! endpoint delivered it.
+//!
+//! A health check watches every stream (see [`crate::modules::stream_health`])
+//! and reconnects streams that go silent or fall behind the highest slot seen.
+//!
+//! Per-validator subscriptions are capped at `max_subscriptions`; the
+//! admission controller (see [`crate::modules::admission`]) ranks discovered
+//! validators and queues the ones that don't fit. Multiplexed mode tracks
+//! every discovered validator on the shared streams.
 
 use crate::error::Result;
 use async_trait::async_trait;
 use dashmap::DashMap;
 use futures::stream::StreamExt;
-use futures::SinkExt;
+use futures::{Sink, SinkExt};
+use lru::LruCache;
+use parking_lot::Mutex;
 use solana_sdk::pubkey::Pubkey;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
-use std::collections::HashMap;
-use tokio::sync::mpsc;
+use std::time::{Duration, Instant};
+use std::collections::{HashMap, HashSet};
+use tokio::sync::watch;
 use tokio::task::JoinHandle;
 use tracing::{debug, error, info, warn};
 
-use crate::config::Config;
-use crate::models::{ValidatorInfo, VoteTransaction};
+use crate::config::{Config, EndpointMode, GrpcCommitment, GrpcConfig, GrpcEndpointConfig, SubscriptionMode};
+use crate::models::{ForkVote, LandingStatus, PipelineEvent, ValidatorInfo, VoteEvent};
+use crate::modules::admission::{AdmissionController, AdmissionPlan};
+use crate::modules::block_meta::{BlockMeta, BlockMetaIndex};
+use crate::modules::missed_votes::MissedVoteDetector;
+use crate::modules::tower::VoteTowers;
+use crate::modules::vote_hash::VoteHashTracker;
+use crate::modules::vote_state::VoteStateTracker;
+use crate::modules::parser::StreamTiming;
+use crate::modules::finality::{FinalityTracker, SlotUpdate};
+use crate::modules::vote_queue::{vote_queue, DropReason, PushOutcome, VoteQueueReceiver, VoteQueueSender};
+use crate::modules::stream_health::{StallThresholds, StreamHealth, StreamId, StreamMonitor, StreamState};
 use crate::modules::{Shutdown, ShutdownSignal};
+use crate::retry::{DefaultRetryPolicy, RetryConfig, RetryPolicy};
 
 // Use the official Yellowstone gRPC client
 use yellowstone_grpc_client::{
     GeyserGrpcClient, 
+    GeyserGrpcClientError,
     ClientTlsConfig, 
+    Interceptor,
 };
 use yellowstone_grpc_proto::{
     geyser::{
@@ -32,12 +71,28 @@ use yellowstone_grpc_proto::{
         SubscribeRequestFilterTransactions,
         SubscribeRequestFilterSlots,
         SubscribeRequestFilterAccounts,
+        SubscribeRequestFilterBlocksMeta,
+        SubscribeRequestPing,
         SubscribeUpdate,
         subscribe_update::UpdateOneof,
         CommitmentLevel,
+        SlotStatus,
     },
 };
-use tonic::Status;
+use tonic::transport::{Certificate, Identity};
+use tonic::{Code, Status};
+
+/// Number of recent vote signatures remembered for duplicate detection
+const SIGNATURE_CACHE_SIZE: usize = 100_000;
+
+/// How often stream health is checked
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Jitter applied to reconnect delays, as a fraction of the delay
+const RECONNECT_JITTER_RATIO: f64 = 0.5;
+
+/// Streaming this long without errors resets the reconnect backoff
+const BACKOFF_RESET_AFTER: Duration = Duration::from_secs(60);
 
 /// Trait for subscription management
 #[async_trait]
@@ -56,12 +111,416 @@ pub trait SubscriptionManagerTrait: Send + Sync {
 pub struct SubscriptionManager {
     config: Arc<Config>,
     active_connections: Arc<DashMap<Pubkey, JoinHandle<()>>>,
-    tx_channel: mpsc::Sender<VoteTransaction>,
-    rx_channel: Option<mpsc::Receiver<VoteTransaction>>,
+    tx_channel: VoteQueueSender<PipelineEvent>,
+    rx_channel: Option<VoteQueueReceiver<PipelineEvent>>,
     shutdown_rx: Option<tokio::sync::broadcast::Receiver<ShutdownSignal>>,
     grpc_endpoint: String,
     /// Tracks the global highest slot atomically
     highest_slot: Arc<std::sync::atomic::AtomicU64>,
+    /// Highest finalized slot seen across streams (0 until one is seen)
+    finalized_slot: Arc<std::sync::atomic::AtomicU64>,
+    /// Validators carried by the multiplexed stream pool, keyed by vote account
+    tracked_validators: Arc<DashMap<Pubkey, ValidatorInfo>>,
+    /// Filter change notifiers, one per multiplexed stream
+    shard_notifiers: Vec<watch::Sender<u64>>,
+    /// Multiplexed stream tasks, keyed by shard index
+    shard_handles: Arc<DashMap<usize, JoinHandle<()>>>,
+    /// Whether `start` spawned the multiplexed stream pool
+    pool_started: AtomicBool,
+    /// Recently forwarded vote signatures, used to drop replayed duplicates
+    recent_signatures: Arc<Mutex<LruCache<String, SeenVote>>>,
+    /// Reconnect and replay counters across all streams
+    reconnect_counters: Arc<ReconnectCounters>,
+    /// Endpoints streams connect to, in priority order
+    endpoints: Arc<Vec<GrpcEndpointConfig>>,
+    /// Liveness of every running stream, keyed by stream and endpoint lane
+    stream_monitors: Arc<DashMap<(StreamId, usize), Arc<StreamMonitor>>>,
+    /// Health check task, once started
+    health_handle: Mutex<Option<JoinHandle<()>>>,
+    /// Race mode delivery statistics, keyed by endpoint label
+    endpoint_stats: Arc<DashMap<String, EndpointStats>>,
+    /// Votes held until their landing slot resolves, when finality is tracked
+    finality: Option<Arc<FinalityTracker>>,
+    /// Caps and ranks subscriptions
+    admission: Arc<AdmissionController>,
+    /// Recent slot start times and block metadata
+    block_meta: Arc<BlockMetaIndex>,
+    /// Last vote seen per vote account, to count each voted slot once
+    towers: Arc<VoteTowers>,
+    /// Confirmed slots checked against voted slots, when missed votes are tracked
+    missed_votes: Option<Arc<MissedVoteDetector>>,
+    /// Last decoded vote account state per validator
+    vote_states: Arc<VoteStateTracker>,
+    /// Vote hashes per slot, compared against the cluster's
+    vote_hashes: Arc<VoteHashTracker>,
+}
+
+/// Race mode delivery statistics for one endpoint
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EndpointStats {
+    /// Votes this endpoint delivered before any other endpoint
+    pub first_deliveries: u64,
+    /// Votes this endpoint delivered after another endpoint
+    pub late_deliveries: u64,
+    /// Total time late deliveries trailed the first copy, in milliseconds
+    pub total_delay_ms: u64,
+}
+
+impl EndpointStats {
+    /// Share of votes this endpoint delivered first
+    pub fn win_rate(&self) -> f64 {
+        let total = self.first_deliveries + self.late_deliveries;
+        if total == 0 {
+            return 0.0;
+        }
+        self.first_deliveries as f64 / total as f64
+    }
+    
+    /// Average time late deliveries trailed the first copy, in milliseconds
+    pub fn avg_delay_ms(&self) -> f64 {
+        if self.late_deliveries == 0 {
+            return 0.0;
+        }
+        self.total_delay_ms as f64 / self.late_deliveries as f64
+    }
+}
+
+/// Reconnect and replay statistics across all streams
+///
+/// `unrecoverable_slots / gap_slots` approximates the share of reconnect gaps
+/// that could not be replayed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReconnectStats {
+    /// Reconnects after a stream error
+    pub reconnects: u64,
+    /// Reconnects that resumed with `from_slot` replay
+    pub replayed_reconnects: u64,
+    /// Slots between the last landed vote and the live tip on reconnect
+    pub gap_slots: u64,
+    /// Gap slots that could not be replayed
+    pub unrecoverable_slots: u64,
+    /// Votes recovered through replay
+    pub recovered_votes: u64,
+    /// Votes dropped because their signature was already forwarded
+    pub duplicate_votes: u64,
+}
+
+/// Atomic counters backing [`ReconnectStats`]
+#[derive(Default)]
+struct ReconnectCounters {
+    reconnects: AtomicU64,
+    replayed_reconnects: AtomicU64,
+    gap_slots: AtomicU64,
+    unrecoverable_slots: AtomicU64,
+    recovered_votes: AtomicU64,
+    duplicate_votes: AtomicU64,
+}
+
+impl ReconnectCounters {
+    fn snapshot(&self) -> ReconnectStats {
+        ReconnectStats {
+            reconnects: self.reconnects.load(Ordering::Relaxed),
+            replayed_reconnects: self.replayed_reconnects.load(Ordering::Relaxed),
+            gap_slots: self.gap_slots.load(Ordering::Relaxed),
+            unrecoverable_slots: self.unrecoverable_slots.load(Ordering::Relaxed),
+            recovered_votes: self.recovered_votes.load(Ordering::Relaxed),
+            duplicate_votes: self.duplicate_votes.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// State shared by every stream task spawned by the manager
+#[derive(Clone)]
+struct StreamContext {
+    config: Arc<Config>,
+    endpoints: Arc<Vec<GrpcEndpointConfig>>,
+    tx_channel: VoteQueueSender<PipelineEvent>,
+    highest_slot: Arc<std::sync::atomic::AtomicU64>,
+    finalized_slot: Arc<std::sync::atomic::AtomicU64>,
+    recent_signatures: Arc<Mutex<LruCache<String, SeenVote>>>,
+    reconnect_counters: Arc<ReconnectCounters>,
+    endpoint_stats: Arc<DashMap<String, EndpointStats>>,
+    finality: Option<Arc<FinalityTracker>>,
+    block_meta: Arc<BlockMetaIndex>,
+    towers: Arc<VoteTowers>,
+    missed_votes: Option<Arc<MissedVoteDetector>>,
+    vote_states: Arc<VoteStateTracker>,
+    vote_hashes: Arc<VoteHashTracker>,
+}
+
+/// First delivery of a vote signature
+#[derive(Debug, Clone)]
+struct SeenVote {
+    /// When the first copy arrived
+    received_at: Instant,
+    /// Endpoint that delivered the first copy
+    endpoint: Arc<str>,
+}
+
+impl StreamContext {
+    /// Remember a vote signature delivered by `endpoint`
+    ///
+    /// Returns the first delivery if the signature was already forwarded.
+    fn remember_signature(&self, signature: &str, endpoint: &Arc<str>) -> Option<SeenVote> {
+        let mut signatures = self.recent_signatures.lock();
+        if let Some(seen) = signatures.get(signature) {
+            return Some(seen.clone());
+        }
+        signatures.put(
+            signature.to_string(),
+            SeenVote {
+                received_at: Instant::now(),
+                endpoint: Arc::clone(endpoint),
+            },
+        );
+        None
+    }
+}
+
+/// Per-stream progress carried across reconnects
+#[derive(Debug, Default)]
+struct StreamProgress {
+    /// Highest slot a tracked vote landed in
+    last_landed_slot: Option<u64>,
+    /// Whether the endpoint accepts `from_slot`; `None` until known
+    replay_supported: Option<bool>,
+    /// Replay in progress after a reconnect
+    replay: Option<ReplayWindow>,
+    /// When the current connection started streaming
+    streaming_since: Option<Instant>,
+    /// Label of the endpoint currently streaming
+    endpoint: Arc<str>,
+}
+
+/// Lanes of a stream that are not streaming
+///
+/// In race mode one lane per endpoint carries the same validators, so their
+/// votes go unobserved only while every lane is down.
+struct LaneOutage {
+    lanes: usize,
+    down: AtomicUsize,
+}
+
+impl LaneOutage {
+    /// Start with every lane down, none has connected yet
+    fn new(lanes: usize) -> Self {
+        Self { lanes, down: AtomicUsize::new(lanes) }
+    }
+    
+    /// Mark a lane down, returning whether every lane is down now
+    fn lane_down(&self) -> bool {
+        self.down.fetch_add(1, Ordering::AcqRel) + 1 == self.lanes
+    }
+    
+    /// Mark a lane up, returning whether it is the only lane up
+    fn lane_up(&self) -> bool {
+        self.down.fetch_sub(1, Ordering::AcqRel) == self.lanes
+    }
+}
+
+/// Outcome of checking a received vote against replay and duplicate state
+#[derive(Debug, PartialEq, Eq)]
+enum VoteAdmission {
+    /// New vote received live
+    Live,
+    /// New vote received while replaying a reconnect gap
+    Recovered,
+    /// Signature already forwarded, drop it; `behind` is how long this copy
+    /// trailed the first one when another endpoint delivered it first
+    Duplicate {
+        replaying: bool,
+        behind: Option<Duration>,
+    },
+}
+
+/// Slots being replayed after a reconnect
+#[derive(Debug)]
+struct ReplayWindow {
+    /// Live tip when the stream reconnected; votes up to here are recovered
+    until_slot: u64,
+    /// Votes recovered so far
+    recovered: u64,
+}
+
+/// The set of validators whose votes a single gRPC stream carries
+#[derive(Clone)]
+enum StreamTargets {
+    /// Dedicated stream for one validator
+    Single(ValidatorInfo),
+    /// Shared stream for every tracked validator assigned to this shard
+    Shard {
+        index: usize,
+        pool_size: usize,
+        tracked: Arc<DashMap<Pubkey, ValidatorInfo>>,
+    },
+}
+
+impl StreamTargets {
+    /// Human readable name used in logs
+    fn label(&self) -> String {
+        match self {
+            StreamTargets::Single(validator) => format!("validator {}", validator.pubkey),
+            StreamTargets::Shard { index, .. } => format!("multiplexed stream {}", index),
+        }
+    }
+    
+    /// Endpoints this stream may connect to, in priority order
+    ///
+    /// A validator with its own endpoint uses only that endpoint.
+    fn endpoints(&self, ctx: &StreamContext) -> Vec<GrpcEndpointConfig> {
+        match self {
+            StreamTargets::Single(ValidatorInfo { grpc_endpoint: Some(url), .. }) => {
+                vec![GrpcEndpointConfig {
+                    name: None,
+                    url: url.clone(),
+                    access_token: ctx.config.grpc.access_token.clone(),
+                }]
+            }
+            _ => ctx.endpoints.to_vec(),
+        }
+    }
+    
+    /// Vote accounts that must be covered by this stream's filter
+    fn vote_accounts(&self) -> Vec<Pubkey> {
+        match self {
+            StreamTargets::Single(validator) => vec![validator.vote_account],
+            StreamTargets::Shard { index, pool_size, tracked } => {
+                let mut accounts: Vec<Pubkey> = tracked
+                    .iter()
+                    .map(|entry| *entry.key())
+                    .filter(|vote_account| shard_for(vote_account, *pool_size) == *index)
+                    .collect();
+                accounts.sort_unstable();
+                accounts
+            }
+        }
+    }
+    
+    /// Find the validator a vote transaction belongs to from its account keys
+    fn resolve_transaction(&self, account_keys: &[Vec<u8>]) -> Option<ValidatorInfo> {
+        match self {
+            StreamTargets::Single(validator) => Some(validator.clone()),
+            StreamTargets::Shard { tracked, .. } => account_keys
+                .iter()
+                .filter_map(|key| Pubkey::try_from(key.as_slice()).ok())
+                .find_map(|key| tracked.get(&key).map(|entry| entry.value().clone())),
+        }
+    }
+    
+    /// Find the validator owning an updated vote account
+    fn resolve_account(&self, vote_account: &Pubkey) -> Option<ValidatorInfo> {
+        match self {
+            StreamTargets::Single(validator) => {
+                (validator.vote_account == *vote_account).then(|| validator.clone())
+            }
+            StreamTargets::Shard { tracked, .. } => {
+                tracked.get(vote_account).map(|entry| entry.value().clone())
+            }
+        }
+    }
+}
+
+/// Shard index of a vote account within a pool of `pool_size` streams
+fn shard_for(vote_account: &Pubkey, pool_size: usize) -> usize {
+    let bytes = vote_account.to_bytes();
+    let mut prefix = [0u8; 8];
+    prefix.copy_from_slice(&bytes[..8]);
+    (u64::from_le_bytes(prefix) % pool_size.max(1) as u64) as usize
+}
+
+/// TLS settings for an endpoint, `None` for a plaintext connection
+///
+/// `http://` endpoints never use TLS. A configured CA bundle replaces the
+/// system roots, so only servers signed by that CA are trusted.
+fn client_tls_config(grpc: &GrpcConfig, endpoint_url: &str) -> Result<Option<ClientTlsConfig>> {
+    if endpoint_url.starts_with("http://") {
+        return Ok(None);
+    }
+    
+    if !grpc.enable_tls {
+        return Err(crate::error::Error::config(format!(
+            "Endpoint {} requires TLS but grpc.enable_tls is false",
+            endpoint_url
+        )));
+    }
+    
+    let read_pem = |path: &str, what: &str| {
+        std::fs::read(path)
+            .map_err(|e| crate::error::Error::config(format!("Failed to read TLS {} {}: {}", what, path, e)))
+    };
+    
+    let mut tls_config = ClientTlsConfig::new();
+    
+    tls_config = match &grpc.tls_ca_cert_path {
+        Some(path) => tls_config.ca_certificate(Certificate::from_pem(read_pem(path, "CA certificate")?)),
+        None => tls_config.with_native_roots(),
+    };
+    
+    if let (Some(cert_path), Some(key_path)) = (&grpc.tls_client_cert_path, &grpc.tls_client_key_path) {
+        let identity = Identity::from_pem(
+            read_pem(cert_path, "client certificate")?,
+            read_pem(key_path, "client key")?,
+        );
+        tls_config = tls_config.identity(identity);
+    }
+    
+    if let Some(domain_name) = &grpc.tls_domain_name {
+        tls_config = tls_config.domain_name(domain_name.clone());
+    }
+    
+    Ok(Some(tls_config))
+}
+
+/// Convert a client error, keeping the gRPC status so auth failures aren't retried
+fn client_error(error: GeyserGrpcClientError, context: &str) -> crate::error::Error {
+    match error {
+        GeyserGrpcClientError::TonicStatus(status) => crate::error::Error::Grpc(status),
+        other => crate::error::Error::network(format!("{}: {}", context, other)),
+    }
+}
+
+/// Whether the slot stream must deliver every status, not only the subscribed commitment
+///
+/// Finality tracking follows each slot to finalized, missed vote detection
+/// needs confirmed slots and root lag needs finalized ones.
+fn needs_all_slot_statuses(grpc: &GrpcConfig) -> bool {
+    tracks_finality(grpc) || grpc.track_missed_votes || grpc.track_root_lag
+}
+
+/// Whether votes are held until their landing slot is finalized or skipped
+///
+/// A finalized subscription only delivers finalized votes, so there is
+/// nothing left to track.
+fn tracks_finality(grpc: &GrpcConfig) -> bool {
+    grpc.track_finality && grpc.commitment != GrpcCommitment::Finalized
+}
+
+/// Landing status implied by the subscription commitment
+fn commitment_landing_status(commitment: GrpcCommitment) -> LandingStatus {
+    match commitment {
+        GrpcCommitment::Processed => LandingStatus::Processed,
+        GrpcCommitment::Confirmed => LandingStatus::Confirmed,
+        GrpcCommitment::Finalized => LandingStatus::Finalized,
+    }
+}
+
+/// Slot carried by an update, if any, for stream liveness tracking
+fn update_slot(update: &SubscribeUpdate) -> Option<u64> {
+    match update.update_oneof.as_ref()? {
+        UpdateOneof::Slot(slot_update) => Some(slot_update.slot),
+        UpdateOneof::Transaction(tx_update) => Some(tx_update.slot),
+        UpdateOneof::Account(account_update) => Some(account_update.slot),
+        UpdateOneof::BlockMeta(block_meta) => Some(block_meta.slot),
+        _ => None,
+    }
+}
+
+/// Wait for a filter change notification; never resolves without a receiver
+async fn filter_changed(filter_rx: &mut Option<watch::Receiver<u64>>) -> bool {
+    match filter_rx {
+        Some(rx) => rx.changed().await.is_ok(),
+        None => std::future::pending().await,
+    }
 }
 
 impl SubscriptionManager {
@@ -75,29 +534,303 @@ impl SubscriptionManager {
         self.highest_slot.load(std::sync::atomic::Ordering::Acquire)
     }
     
+    /// Identity pubkeys of all validators currently subscribed
+    pub fn subscribed_validators(&self) -> Vec<Pubkey> {
+        match self.config.grpc.subscription_mode {
+            SubscriptionMode::PerValidator => self.active_connections
+                .iter()
+                .map(|entry| *entry.key())
+                .collect(),
+            SubscriptionMode::Multiplexed => self.tracked_validators
+                .iter()
+                .map(|entry| entry.value().pubkey)
+                .collect(),
+        }
+    }
+    
+    /// Admission controller, used to report validator anomalies
+    pub fn admission(&self) -> Arc<AdmissionController> {
+        Arc::clone(&self.admission)
+    }
+    
+    /// Missed vote detector, when missed votes are tracked
+    pub fn missed_votes(&self) -> Option<Arc<MissedVoteDetector>> {
+        self.missed_votes.clone()
+    }
+    
+    /// Validators queued for a subscription but not monitored
+    pub fn queued_validators(&self) -> Vec<ValidatorInfo> {
+        self.admission.queued_validators()
+    }
+    
+    /// Subscribe to the best ranked candidates and evict the rest
+    ///
+    /// Called with the full discovered set; validators beyond
+    /// `max_subscriptions` are queued until they outrank an active one.
+    pub async fn reconcile(&self, candidates: &[ValidatorInfo]) -> Result<AdmissionPlan> {
+        let active: HashSet<Pubkey> = self.subscribed_validators().into_iter().collect();
+        let plan = self.admission.plan(candidates, &active);
+        
+        for pubkey in &plan.evict {
+            if let Err(e) = self.unsubscribe(pubkey).await {
+                error!("Failed to unsubscribe from validator {}: {}", pubkey, e);
+            }
+        }
+        
+        for validator in &plan.admit {
+            if let Err(e) = self.subscribe(validator).await {
+                error!("Failed to subscribe to validator {}: {}", validator.pubkey, e);
+            }
+        }
+        
+        // Restart multiplexed streams that ended, like per-validator streams
+        // are resubscribed above once their task is gone
+        if self.pool_started.load(Ordering::Acquire) {
+            self.start_stream_pool();
+        }
+        
+        if !plan.queued.is_empty() {
+            info!(
+                "{} validators queued but not monitored (max_subscriptions = {})",
+                plan.queued.len(),
+                self.admission.max_subscriptions()
+            );
+        }
+        crate::metrics::METRICS.set_queued_subscriptions(plan.queued.len() as i64);
+        
+        Ok(plan)
+    }
+    
+    /// Build the context handed to stream tasks
+    fn stream_context(&self) -> StreamContext {
+        StreamContext {
+            config: Arc::clone(&self.config),
+            endpoints: Arc::clone(&self.endpoints),
+            tx_channel: self.tx_channel.clone(),
+            highest_slot: Arc::clone(&self.highest_slot),
+            finalized_slot: Arc::clone(&self.finalized_slot),
+            recent_signatures: Arc::clone(&self.recent_signatures),
+            reconnect_counters: Arc::clone(&self.reconnect_counters),
+            endpoint_stats: Arc::clone(&self.endpoint_stats),
+            finality: self.finality.clone(),
+            block_meta: Arc::clone(&self.block_meta),
+            towers: Arc::clone(&self.towers),
+            missed_votes: self.missed_votes.clone(),
+            vote_states: Arc::clone(&self.vote_states),
+            vote_hashes: Arc::clone(&self.vote_hashes),
+        }
+    }
+    
+    /// Endpoints streams connect to, in priority order
+    pub fn endpoints(&self) -> &[GrpcEndpointConfig] {
+        &self.endpoints
+    }
+    
+    /// Race mode delivery statistics per endpoint
+    pub fn endpoint_stats(&self) -> HashMap<String, EndpointStats> {
+        self.endpoint_stats
+            .iter()
+            .map(|entry| (entry.key().clone(), *entry.value()))
+            .collect()
+    }
+    
+    /// Reconnect and replay statistics across all streams
+    pub fn reconnect_stats(&self) -> ReconnectStats {
+        self.reconnect_counters.snapshot()
+    }
+    
+    /// Liveness of every running stream
+    ///
+    /// In race mode a stream has one entry per endpoint.
+    pub fn stream_health(&self) -> Vec<(StreamId, StreamHealth)> {
+        let mut health: Vec<_> = self.stream_monitors
+            .iter()
+            .map(|entry| (entry.key().0, entry.key().1, entry.value().health()))
+            .collect();
+        health.sort_by_key(|(_, lane, _)| *lane);
+        health.into_iter().map(|(id, _, health)| (id, health)).collect()
+    }
+    
+    /// Build the task driving a stream over the configured endpoints
+    ///
+    /// Failover mode runs one connection that moves down the endpoint list;
+    /// race mode runs one connection per endpoint, each with its own monitor.
+    fn stream_task(
+        &self,
+        id: StreamId,
+        targets: StreamTargets,
+        filter_rx: Option<watch::Receiver<u64>>,
+    ) -> impl std::future::Future<Output = ()> + Send + 'static {
+        let ctx = self.stream_context();
+        let endpoints = targets.endpoints(&ctx);
+        let lanes: Vec<Vec<GrpcEndpointConfig>> = match self.config.grpc.endpoint_mode {
+            EndpointMode::Race if endpoints.len() > 1 => {
+                endpoints.into_iter().map(|endpoint| vec![endpoint]).collect()
+            }
+            _ => vec![endpoints],
+        };
+        let outage = Arc::new(LaneOutage::new(lanes.len()));
+        
+        let loops: Vec<_> = lanes
+            .into_iter()
+            .enumerate()
+            .map(|(lane, endpoints)| {
+                let monitor = Arc::new(StreamMonitor::new());
+                self.stream_monitors.insert((id, lane), Arc::clone(&monitor));
+                Self::run_stream_loop(targets.clone(), ctx.clone(), filter_rx.clone(), endpoints, monitor, Arc::clone(&outage))
+            })
+            .collect();
+        
+        async move {
+            futures::future::join_all(loops).await;
+        }
+    }
+    
+    /// Drop the monitors of every lane of a stream
+    fn remove_stream_monitors(&self, id: StreamId) {
+        self.stream_monitors.retain(|(stream, _), _| *stream != id);
+    }
+    
+    /// Notify the multiplexed stream owning `vote_account` that its filter changed
+    fn notify_shard(&self, vote_account: &Pubkey) {
+        let shard = shard_for(vote_account, self.shard_notifiers.len());
+        if let Some(notifier) = self.shard_notifiers.get(shard) {
+            notifier.send_modify(|generation| *generation += 1);
+        }
+    }
+    
+    /// Keep a stream connected, reconnecting after errors
+    ///
+    /// Reconnects back off exponentially with jitter and stop on errors that
+    /// retrying can't fix, such as rejected credentials. With several
+    /// endpoints a failed connection moves straight to the next usable one,
+    /// and the backoff only applies once every endpoint has failed.
+    async fn run_stream_loop(
+        targets: StreamTargets,
+        ctx: StreamContext,
+        mut filter_rx: Option<watch::Receiver<u64>>,
+        endpoints: Vec<GrpcEndpointConfig>,
+        monitor: Arc<StreamMonitor>,
+        outage: Arc<LaneOutage>,
+    ) {
+        let mut progress = StreamProgress::default();
+        let retry_config = RetryConfig::new()
+            .with_initial_delay(Duration::from_secs(ctx.config.grpc.reconnect_interval_secs))
+            .with_max_delay(Duration::from_secs(ctx.config.grpc.reconnect_max_delay_secs))
+            .with_jitter_ratio(RECONNECT_JITTER_RATIO);
+        let policy = DefaultRetryPolicy::new(retry_config.clone());
+        let mut attempt: u32 = 0;
+        
+        // Endpoints that rejected us with a non-retryable error
+        let mut unusable = vec![false; endpoints.len()];
+        let mut current = 0;
+        let mut failed_in_cycle = 0;
+        
+        loop {
+            let endpoint = &endpoints[current];
+            progress.endpoint = Arc::from(endpoint.label());
+            monitor.start_connecting(endpoint.label());
+            
+            let result = Self::run_subscription(&targets, &ctx, endpoint, &mut filter_rx, &mut progress, &monitor, &outage).await;
+            let streaming_since = progress.streaming_since.take();
+            if streaming_since.is_some() && outage.lane_down() {
+                if let Some(missed_votes) = &ctx.missed_votes {
+                    missed_votes.pause(&targets.vote_accounts());
+                }
+            }
+            
+            match result {
+                Ok(_) => {
+                    info!("Subscription ended normally for {}", targets.label());
+                    break;
+                }
+                Err(e) => {
+                    progress.replay = None;
+                    
+                    // Auth and configuration errors won't fix themselves
+                    if policy.should_retry(&e) {
+                        error!("Subscription error for {} on {}: {}", targets.label(), endpoint.label(), e);
+                        ctx.reconnect_counters.reconnects.fetch_add(1, Ordering::Relaxed);
+                    } else {
+                        error!("Endpoint {} unusable for {}, non-retryable error: {}", endpoint.label(), targets.label(), e);
+                        unusable[current] = true;
+                        if unusable.iter().all(|rejected| *rejected) {
+                            error!("Giving up on {}, no usable endpoints left", targets.label());
+                            monitor.set_state(StreamState::Failed);
+                            break;
+                        }
+                    }
+                    monitor.set_state(StreamState::BackingOff);
+                    
+                    // A stream that stayed up for a while starts over from the initial delay
+                    if streaming_since.is_some_and(|since| since.elapsed() >= BACKOFF_RESET_AFTER) {
+                        attempt = 0;
+                        failed_in_cycle = 0;
+                    }
+                    failed_in_cycle += 1;
+                    
+                    // Fail over to the next usable endpoint without waiting
+                    // until each of them has failed once
+                    if endpoints.len() > 1 {
+                        let previous = current;
+                        loop {
+                            current = (current + 1) % endpoints.len();
+                            if !unusable[current] {
+                                break;
+                            }
+                        }
+                        if current != previous {
+                            // Replay support differs between providers
+                            progress.replay_supported = None;
+                        }
+                        
+                        let usable = unusable.iter().filter(|rejected| !**rejected).count();
+                        if failed_in_cycle < usable {
+                            info!("Failing over {} to {}", targets.label(), endpoints[current].label());
+                            continue;
+                        }
+                    }
+                    failed_in_cycle = 0;
+                    
+                    // Wait before reconnecting, jittered so streams dropped
+                    // together don't reconnect together
+                    let delay = policy.next_delay(attempt, retry_config.initial_delay);
+                    attempt = attempt.saturating_add(1);
+                    debug!("Reconnecting {} in {:?} (attempt {})", targets.label(), delay, attempt);
+                    tokio::time::sleep(delay).await;
+                    
+                    info!("Attempting to reconnect {}", targets.label());
+                }
+            }
+        }
+    }
+    
     /// Run the actual subscription (separated for easier error handling)
     async fn run_subscription(
-        validator: &ValidatorInfo,
-        tx_channel: mpsc::Sender<VoteTransaction>,
-        config: Arc<Config>,
-        grpc_endpoint: String,
-        highest_slot: Arc<std::sync::atomic::AtomicU64>,
+        targets: &StreamTargets,
+        ctx: &StreamContext,
+        endpoint: &GrpcEndpointConfig,
+        filter_rx: &mut Option<watch::Receiver<u64>>,
+        progress: &mut StreamProgress,
+        monitor: &StreamMonitor,
+        outage: &LaneOutage,
     ) -> Result<()> {
+        let config = &ctx.config;
+        
         // Create gRPC connection using the official client
-        let endpoint_url = validator.grpc_endpoint.as_ref()
-            .unwrap_or(&grpc_endpoint);
+        let endpoint_url = &endpoint.url;
         
-        info!("Connecting to gRPC endpoint: {}", endpoint_url);
+        info!("Connecting to gRPC endpoint: {}", endpoint.label());
         
         // Build client with authentication if provided
         let client_builder = GeyserGrpcClient::build_from_shared(endpoint_url.to_string())
-            .map_err(|e| crate::error::Error::internal(format!("Invalid endpoint: {}", e)))?;
+            .map_err(|e| crate::error::Error::config(format!("Invalid endpoint: {}", e)))?;
         
-        let client_builder = if let Some(access_token) = &config.grpc.access_token {
+        let client_builder = if let Some(access_token) = &endpoint.access_token {
             if !access_token.trim().is_empty() {
                 debug!("Adding x-token authentication");
                 client_builder.x_token(Some(access_token.trim().to_string()))
-                    .map_err(|e| crate::error::Error::internal(format!("Invalid access token: {}", e)))?
+                    .map_err(|e| crate::error::Error::config(format!("Invalid access token: {}", e)))?
             } else {
                 warn!("Access token is empty, connecting without authentication");
                 client_builder
@@ -107,81 +840,217 @@ impl SubscriptionManager {
             client_builder
         };
         
-        let mut client = client_builder
+        let client_builder = client_builder
             .connect_timeout(Duration::from_secs(config.grpc.connection_timeout_secs))
-            .timeout(Duration::from_secs(config.grpc.connection_timeout_secs))
-            .tls_config(ClientTlsConfig::new().with_native_roots())
-            .map_err(|e| crate::error::Error::internal(format!("TLS config error: {}", e)))?
+            .timeout(Duration::from_secs(config.grpc.connection_timeout_secs));
+        
+        let client_builder = match client_tls_config(&config.grpc, endpoint_url)? {
+            Some(tls_config) => client_builder.tls_config(tls_config)
+                .map_err(|e| crate::error::Error::config(format!("TLS config error: {}", e)))?,
+            None => client_builder,
+        };
+        
+        let mut client = client_builder
             .max_decoding_message_size(1024 * 1024 * 1024) // 1GB max message size
             .connect()
             .await
             .map_err(|e| crate::error::Error::network(format!("Failed to connect: {}", e)))?;
         
+        // Resume from the last landed vote if this is a reconnect
+        let from_slot = Self::plan_replay(&mut client, targets, ctx, progress).await;
+        
         // Create subscription
         let (mut subscribe_tx, subscribe_rx) = client.subscribe().await
-            .map_err(|e| crate::error::Error::network(format!("Failed to create subscription: {}", e)))?;
+            .map_err(|e| client_error(e, "Failed to create subscription"))?;
         
-        // Create subscription request for vote transactions
-        let request = Self::create_vote_subscription_request_static(&validator.vote_account);
+        // Build the request from the current target set; any filter change that
+        // happened while disconnected is covered by this request
+        if let Some(rx) = filter_rx.as_mut() {
+            rx.borrow_and_update();
+        }
+        let vote_accounts = targets.vote_accounts();
+        let mut request = Self::create_vote_subscription_request_static(&vote_accounts, &ctx.config.grpc);
+        request.from_slot = from_slot;
         
         // Send the subscription request
         subscribe_tx.send(request).await
             .map_err(|e| crate::error::Error::network(format!("Failed to send subscription request: {}", e)))?;
         
-        info!("Successfully subscribed to validator {} vote updates", validator.pubkey);
+        info!(
+            "Successfully subscribed {} to vote updates for {} vote accounts",
+            targets.label(),
+            vote_accounts.len()
+        );
+        monitor.start_streaming(progress.replay.is_some());
+        progress.streaming_since = Some(Instant::now());
+        
+        // Votes are observed again from the replayed slot, or the live tip
+        if outage.lane_up() {
+            if let Some(missed_votes) = &ctx.missed_votes {
+                let observed_from = from_slot.unwrap_or_else(|| ctx.highest_slot.load(Ordering::Acquire));
+                missed_votes.resume(&vote_accounts, observed_from);
+            }
+        }
         
         // Handle the stream
-        Self::handle_stream_static(validator.clone(), subscribe_rx, tx_channel, highest_slot).await
+        Self::handle_stream_static(targets, ctx, subscribe_tx, subscribe_rx, filter_rx, progress, monitor).await
     }
     
-    /// Static version of create_vote_subscription_request for use in static context
-    fn create_vote_subscription_request_static(vote_pubkey: &Pubkey) -> SubscribeRequest {
-        // Create filter for vote transactions (as backup/verification)
-        let tx_filter = SubscribeRequestFilterTransactions {
-            vote: Some(true),
-            failed: Some(false),
-            account_include: vec![vote_pubkey.to_string()],
-            ..Default::default()
+    /// Decide where a reconnecting stream should resume from
+    ///
+    /// Returns the `from_slot` to request, or `None` to start from the live tip
+    /// (first connection, replay disabled, or not supported by the endpoint).
+    /// The gap is recorded either way.
+    async fn plan_replay<F>(
+        client: &mut GeyserGrpcClient<F>,
+        targets: &StreamTargets,
+        ctx: &StreamContext,
+        progress: &mut StreamProgress,
+    ) -> Option<u64>
+    where
+        F: Interceptor,
+    {
+        let last_slot = progress.last_landed_slot?;
+        let counters = &ctx.reconnect_counters;
+        
+        // Measure the gap against the endpoint's current tip
+        let tip = match client.get_slot(Some(CommitmentLevel::Processed)).await {
+            Ok(response) => response.slot,
+            Err(e) => {
+                debug!("Failed to get current slot for {}: {}", targets.label(), e);
+                ctx.highest_slot.load(Ordering::Acquire)
+            }
         };
+        let gap = tip.saturating_sub(last_slot);
+        counters.gap_slots.fetch_add(gap, Ordering::Relaxed);
         
-        let mut tx_map = HashMap::new();
-        tx_map.insert("vote_transactions".to_string(), tx_filter);
+        let unrecoverable = |slots: u64| {
+            counters.unrecoverable_slots.fetch_add(slots, Ordering::Relaxed);
+            crate::metrics::METRICS.record_reconnect_gap(false, slots);
+        };
+        
+        if !ctx.config.grpc.replay_on_reconnect || progress.replay_supported == Some(false) {
+            unrecoverable(gap);
+            warn!("Reconnected {} without replay, {} slots not recovered", targets.label(), gap);
+            return None;
+        }
+        
+        // Check how far back the endpoint can replay
+        let first_available = match client.subscribe_replay_info().await {
+            Ok(info) => info.first_available,
+            Err(GeyserGrpcClientError::TonicStatus(status))
+                if status.code() == Code::Unimplemented =>
+            {
+                warn!("Endpoint does not support from_slot replay, reconnecting {} at the live tip", targets.label());
+                progress.replay_supported = Some(false);
+                None
+            }
+            Err(e) => {
+                debug!("Failed to get replay info for {}: {}", targets.label(), e);
+                None
+            }
+        };
+        
+        let Some(first_available) = first_available else {
+            unrecoverable(gap);
+            return None;
+        };
+        
+        // Slots older than the replay buffer are lost
+        let from_slot = last_slot.max(first_available);
+        let lost = from_slot - last_slot;
+        if lost > 0 {
+            unrecoverable(lost);
+        }
+        crate::metrics::METRICS.record_reconnect_gap(true, gap.saturating_sub(lost));
+        counters.replayed_reconnects.fetch_add(1, Ordering::Relaxed);
+        
+        info!(
+            "Resuming {} from slot {} (gap of {} slots, {} unrecoverable)",
+            targets.label(),
+            from_slot,
+            gap,
+            lost
+        );
         
-        // Create filter for slot updates (we need ALL slots to track current slot)
+        progress.replay = Some(ReplayWindow { until_slot: tip, recovered: 0 });
+        Some(from_slot)
+    }
+    
+    /// Static version of create_vote_subscription_request for use in static context
+    ///
+    /// All given vote accounts share one transaction filter and one account
+    /// filter. With no vote accounts only the slot filter is requested, since an
+    /// empty `account_include` would match every vote in the cluster.
+    fn create_vote_subscription_request_static(vote_pubkeys: &[Pubkey], grpc: &GrpcConfig) -> SubscribeRequest {
+        let vote_accounts: Vec<String> = vote_pubkeys.iter().map(|p| p.to_string()).collect();
+        
+        // Create filter for slot updates (we need ALL slots to track current slot).
+        // Finality tracking needs every status update, not just the subscribed commitment;
+        // interslot updates mark when each slot starts, which is when votes land in it
         let slot_filter = SubscribeRequestFilterSlots {
-            filter_by_commitment: Some(true),
-            interslot_updates: Some(false), // We only need finalized slot updates
+            filter_by_commitment: Some(!needs_all_slot_statuses(grpc)),
+            interslot_updates: Some(true),
         };
         
         let mut slot_map = HashMap::new();
         slot_map.insert("all_slots".to_string(), slot_filter);
         
-        // Create filter for vote account updates
-        let account_filter = SubscribeRequestFilterAccounts {
-            account: vec![vote_pubkey.to_string()],
-            owner: vec![], // Vote accounts are owned by the Vote program
-            filters: vec![],
-            nonempty_txn_signature: Some(false), // We want all account updates
-        };
-        
+        let mut tx_map = HashMap::new();
         let mut account_map = HashMap::new();
-        account_map.insert("vote_account".to_string(), account_filter);
+        
+        if !vote_accounts.is_empty() {
+            // Create filter for vote transactions (as backup/verification)
+            let tx_filter = SubscribeRequestFilterTransactions {
+                vote: Some(true),
+                failed: Some(false),
+                account_include: vote_accounts.clone(),
+                ..Default::default()
+            };
+            tx_map.insert("vote_transactions".to_string(), tx_filter);
+            
+            // Create filter for vote account updates
+            let account_filter = SubscribeRequestFilterAccounts {
+                account: vote_accounts,
+                owner: vec![], // Vote accounts are owned by the Vote program
+                filters: vec![],
+                nonempty_txn_signature: Some(false), // We want all account updates
+            };
+            account_map.insert("vote_account".to_string(), account_filter);
+        }
+        
+        // Block metadata carries block times and, through the fee reward, the leader
+        let mut blocks_meta_map = HashMap::new();
+        if grpc.subscribe_block_meta {
+            blocks_meta_map.insert("block_meta".to_string(), SubscribeRequestFilterBlocksMeta {});
+        }
         
         SubscribeRequest {
             transactions: tx_map,
             slots: slot_map,
             accounts: account_map,
-            commitment: Some(CommitmentLevel::Processed as i32),
+            blocks_meta: blocks_meta_map,
+            commitment: Some(match grpc.commitment {
+                GrpcCommitment::Processed => CommitmentLevel::Processed,
+                GrpcCommitment::Confirmed => CommitmentLevel::Confirmed,
+                GrpcCommitment::Finalized => CommitmentLevel::Finalized,
+            } as i32),
             ..Default::default()
         }
     }
+    
     /// Create a new subscription manager
     pub async fn new(
         config: Arc<Config>,
         shutdown_rx: tokio::sync::broadcast::Receiver<ShutdownSignal>,
     ) -> Result<Self> {
         // Create channel for vote transactions
-        let (tx_channel, rx_channel) = mpsc::channel(config.grpc.buffer_size);
+        let (tx_channel, rx_channel) = vote_queue(
+            config.grpc.buffer_size,
+            config.grpc.overflow_policy,
+            std::path::Path::new(&config.grpc.spill_dir),
+            config.grpc.spill_max_mb * 1024 * 1024,
+        )?;
         
         // Determine gRPC endpoint with the following priority:
         // 1. Environment variable SVLM_GRPC_ENDPOINT
@@ -190,6 +1059,9 @@ impl SubscriptionManager {
         let grpc_endpoint = if let Ok(endpoint) = std::env::var("SVLM_GRPC_ENDPOINT") {
             info!("Using gRPC endpoint from environment variable");
             endpoint
+        } else if let Some(endpoint) = config.grpc.endpoints.first() {
+            info!("Using {} gRPC endpoints from config", config.grpc.endpoints.len());
+            endpoint.url.clone()
         } else if let Some(endpoint) = &config.grpc.endpoint {
             info!("Using gRPC endpoint from config");
             endpoint.clone()
@@ -228,6 +1100,32 @@ impl SubscriptionManager {
         
         info!("gRPC endpoint: {}", grpc_endpoint);
         
+        // The endpoint list is used as-is unless the environment overrides it
+        let endpoints = if !config.grpc.endpoints.is_empty() && std::env::var("SVLM_GRPC_ENDPOINT").is_err() {
+            config.grpc.endpoints.clone()
+        } else {
+            vec![GrpcEndpointConfig {
+                name: None,
+                url: grpc_endpoint.clone(),
+                access_token: config.grpc.access_token.clone(),
+            }]
+        };
+        
+        // One filter notifier per shared stream in multiplexed mode
+        let shard_notifiers = match config.grpc.subscription_mode {
+            SubscriptionMode::PerValidator => Vec::new(),
+            SubscriptionMode::Multiplexed => (0..config.grpc.stream_pool_size)
+                .map(|_| watch::channel(0u64).0)
+                .collect(),
+        };
+        
+        // Votes are held for finality tracking across all streams
+        let finality = tracks_finality(&config.grpc).then(|| Arc::new(FinalityTracker::new()));
+        let admission = Arc::new(AdmissionController::new(&config.grpc));
+        let block_meta = Arc::new(BlockMetaIndex::new(config.grpc.subscribe_block_meta));
+        let missed_votes = config.grpc.track_missed_votes
+            .then(|| Arc::new(MissedVoteDetector::new(config.grpc.missed_vote_tolerance_slots)));
+        
         Ok(Self {
             config,
             active_connections: Arc::new(DashMap::new()),
@@ -236,11 +1134,31 @@ impl SubscriptionManager {
             shutdown_rx: Some(shutdown_rx),
             grpc_endpoint,
             highest_slot: Arc::new(std::sync::atomic::AtomicU64::new(0)),
+            finalized_slot: Arc::new(std::sync::atomic::AtomicU64::new(0)),
+            tracked_validators: Arc::new(DashMap::new()),
+            shard_notifiers,
+            shard_handles: Arc::new(DashMap::new()),
+            pool_started: AtomicBool::new(false),
+            recent_signatures: Arc::new(Mutex::new(LruCache::new(
+                NonZeroUsize::new(SIGNATURE_CACHE_SIZE).unwrap(),
+            ))),
+            reconnect_counters: Arc::new(ReconnectCounters::default()),
+            endpoints: Arc::new(endpoints),
+            stream_monitors: Arc::new(DashMap::new()),
+            health_handle: Mutex::new(None),
+            endpoint_stats: Arc::new(DashMap::new()),
+            finality,
+            admission,
+            block_meta,
+            towers: Arc::new(VoteTowers::new()),
+            missed_votes,
+            vote_states: Arc::new(VoteStateTracker::new()),
+            vote_hashes: Arc::new(VoteHashTracker::new()),
         })
     }
 
     /// Get the receiver channel for vote transactions
-    pub fn take_receiver(&mut self) -> Option<mpsc::Receiver<VoteTransaction>> {
+    pub fn take_receiver(&mut self) -> Option<VoteQueueReceiver<PipelineEvent>> {
         self.rx_channel.take()
     }
     
@@ -248,241 +1166,697 @@ impl SubscriptionManager {
     pub async fn start(&self) -> Result<()> {
         info!("Starting subscription manager");
         
-        // TODO: Start health check task
+        if self.config.grpc.subscription_mode == SubscriptionMode::Multiplexed {
+            info!("Starting {} multiplexed vote streams", self.shard_notifiers.len());
+            self.pool_started.store(true, Ordering::Release);
+            self.start_stream_pool();
+        }
+        
         self.start_health_check().await?;
         
         Ok(())
     }
-
+    
+    /// Spawn the shared streams used in multiplexed mode that aren't running
+    ///
+    /// Each stream picks up the validators assigned to its shard when it
+    /// connects, so validators subscribed before `start` are carried as well.
+    /// A stream that ended or gave up is started again on the next call.
+    fn start_stream_pool(&self) {
+        let pool_size = self.shard_notifiers.len();
+        
+        for (index, notifier) in self.shard_notifiers.iter().enumerate() {
+            if self.shard_handles.contains_key(&index) {
+                continue;
+            }
+            
+            let targets = StreamTargets::Shard {
+                index,
+                pool_size,
+                tracked: Arc::clone(&self.tracked_validators),
+            };
+            let filter_rx = Some(notifier.subscribe());
+            let shards = Arc::clone(&self.shard_handles);
+            let stream = self.stream_task(StreamId::Shard(index), targets, filter_rx);
+            
+            // Held until the handle is recorded, so a stream that ends right
+            // away can't remove its entry before it is inserted
+            let (registered_tx, registered_rx) = tokio::sync::oneshot::channel::<()>();
+            let handle = tokio::spawn(async move {
+                let _ = registered_rx.await;
+                stream.await;
+                
+                // Forget the stream once it is done so the pool starts it
+                // again; the monitor stays so a failed stream remains visible
+                shards.remove(&index);
+            });
+            self.shard_handles.insert(index, handle);
+            let _ = registered_tx.send(());
+        }
+    }
+    
     /// Start health check task
+    ///
+    /// Streams that stay silent longer than `stall_timeout_secs` or fall more
+    /// than `max_slot_lag` slots behind the highest slot seen are torn down
+    /// and reconnect through their normal retry loop.
     async fn start_health_check(&self) -> Result<()> {
-        let connections = Arc::clone(&self.active_connections);
+        let monitors = Arc::clone(&self.stream_monitors);
+        let tracked = Arc::clone(&self.tracked_validators);
+        let highest_slot = Arc::clone(&self.highest_slot);
+        let reconnect_counters = Arc::clone(&self.reconnect_counters);
+        let vote_queue = self.tx_channel.monitor();
+        let thresholds = StallThresholds {
+            max_silence: Duration::from_secs(self.config.grpc.stall_timeout_secs),
+            max_slot_lag: self.config.grpc.max_slot_lag,
+        };
+        let mut shutdown_rx = self.shutdown_rx.as_ref()
+            .ok_or_else(|| crate::error::Error::config("Shutdown receiver not initialized"))?
+            .resubscribe();
         
-        tokio::spawn(async move {
-            let mut interval = tokio::time::interval(
-                tokio::time::Duration::from_secs(30)
-            );
+        let handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(HEALTH_CHECK_INTERVAL);
             
             loop {
-                interval.tick().await;
-                debug!("Active gRPC connections: {}", connections.len());
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = shutdown_rx.recv() => {
+                        info!("Stream health check received shutdown signal");
+                        break;
+                    }
+                }
                 
-                // TODO: Check connection health and reconnect if needed
-            }
-        });
-        
-        Ok(())
-    }
-
-
-    /// Handle incoming updates from gRPC stream (static version)
-    async fn handle_stream_static(
-        validator: ValidatorInfo,
-        mut stream: impl futures::Stream<Item = std::result::Result<SubscribeUpdate, Status>> + Unpin,
-        tx_channel: mpsc::Sender<VoteTransaction>,
-        highest_slot: Arc<std::sync::atomic::AtomicU64>,
-    ) -> Result<()> {
-        info!("Starting to handle stream for validator {}", validator.pubkey);
-        
-        while let Some(update_result) = stream.next().await {
-            match update_result {
-                Ok(update) => {
-                    if let Some(update_oneof) = update.update_oneof {
-                        match update_oneof {
-                            UpdateOneof::Transaction(tx_update) => {
-                                if let Some(tx_info) = tx_update.transaction {
-                                    if tx_info.is_vote {
-                                        debug!(
-                                            "Received vote transaction from validator {}",
-                                            validator.pubkey
-                                        );
+                let now = chrono::Utc::now();
+                let highest = highest_slot.load(Ordering::Acquire);
+                let mut state_counts: HashMap<StreamState, i64> = HashMap::new();
+                
+                for entry in monitors.iter() {
+                    let health = entry.value().health();
+                    let mut state = health.state;
+                    
+                    if let Some(reason) = health.check(now, highest, &thresholds) {
+                        let (id, _) = entry.key();
+                        warn!(
+                            "Stream {:?} on {} stalled ({:?}), reconnecting",
+                            id,
+                            health.endpoint.as_deref().unwrap_or("unknown endpoint"),
+                            reason
+                        );
+                        entry.value().request_teardown();
+                        state = StreamState::Stalled;
+                    }
+                    
+                    *state_counts.entry(state).or_default() += 1;
+                }
+                
+                for state in [
+                    StreamState::Connecting,
+                    StreamState::Streaming,
+                    StreamState::Stalled,
+                    StreamState::BackingOff,
+                    StreamState::Failed,
+                ] {
+                    let count = state_counts.get(&state).copied().unwrap_or(0);
+                    crate::metrics::METRICS.set_streams_in_state(state.as_str(), count);
+                }
+                
+                debug!(
+                    "gRPC streams: {} ({:?}), multiplexed validators: {}, highest slot: {}",
+                    monitors.len(),
+                    state_counts,
+                    tracked.len(),
+                    highest
+                );
+                
+                // Report the queue depth even while no votes arrive
+                let depth = vote_queue.depth();
+                crate::metrics::METRICS.set_vote_queue_depth(depth.memory, depth.spilled);
+                if depth.spilled > 0 {
+                    debug!("Vote queue: {} in memory, {} spilled", depth.memory, depth.spilled);
+                }
+                
+                let stats = reconnect_counters.snapshot();
+                if stats.reconnects > 0 {
+                    debug!(
+                        "Reconnects: {} ({} replayed), gap slots: {} ({} unrecoverable), recovered votes: {}, duplicates dropped: {}",
+                        stats.reconnects,
+                        stats.replayed_reconnects,
+                        stats.gap_slots,
+                        stats.unrecoverable_slots,
+                        stats.recovered_votes,
+                        stats.duplicate_votes
+                    );
+                }
+            }
+        });
+        *self.health_handle.lock() = Some(handle);
+        
+        Ok(())
+    }
+
+
+    /// Handle incoming updates from gRPC stream (static version)
+    ///
+    /// Filter changes signalled through `filter_rx` are applied in place by
+    /// sending a replacement `SubscribeRequest` on the open stream.
+    async fn handle_stream_static<S>(
+        targets: &StreamTargets,
+        ctx: &StreamContext,
+        mut subscribe_tx: S,
+        mut stream: impl futures::Stream<Item = std::result::Result<SubscribeUpdate, Status>> + Unpin,
+        filter_rx: &mut Option<watch::Receiver<u64>>,
+        progress: &mut StreamProgress,
+        monitor: &StreamMonitor,
+    ) -> Result<()>
+    where
+        S: Sink<SubscribeRequest> + Unpin,
+        S::Error: std::fmt::Display,
+    {
+        info!("Starting to handle stream for {}", targets.label());
+        let mut received_update = false;
+        
+        loop {
+            tokio::select! {
+                update_result = stream.next() => {
+                    match update_result {
+                        Some(Ok(update)) => {
+                            received_update = true;
+                            monitor.record_message(update_slot(&update));
+                            
+                            // Answer server pings so the connection isn't dropped as idle
+                            if matches!(update.update_oneof, Some(UpdateOneof::Ping(_))) {
+                                let pong = SubscribeRequest {
+                                    ping: Some(SubscribeRequestPing { id: 1 }),
+                                    ..Default::default()
+                                };
+                                subscribe_tx.send(pong).await
+                                    .map_err(|e| crate::error::Error::network(format!("Failed to answer ping: {}", e)))?;
+                            }
+                            
+                            let replaying = progress.replay.is_some();
+                            Self::process_update(targets, ctx, update, progress).await;
+                            if replaying && progress.replay.is_none() {
+                                monitor.finish_replay();
+                            }
+                        }
+                        Some(Err(e)) => {
+                            error!("Error receiving from {}: {}", targets.label(), e);
+                            
+                            // An immediate rejection of a replay request means the
+                            // endpoint can't serve from_slot, fall back to live
+                            if progress.replay.is_some()
+                                && !received_update
+                                && e.code() == Code::InvalidArgument
+                            {
+                                warn!("Endpoint rejected from_slot replay for {}, falling back to live stream", targets.label());
+                                progress.replay_supported = Some(false);
+                                return Err(crate::error::Error::network(format!(
+                                    "Replay rejected: {}",
+                                    e
+                                )));
+                            }
+                            
+                            return Err(crate::error::Error::Grpc(e));
+                        }
+                        None => break,
+                    }
+                }
+                _ = monitor.teardown_requested() => {
+                    return Err(crate::error::Error::network(format!(
+                        "Stream for {} stalled, reconnecting",
+                        targets.label()
+                    )));
+                }
+                changed = filter_changed(filter_rx) => {
+                    if !changed {
+                        // Manager dropped, keep streaming with the current filter
+                        *filter_rx = None;
+                        continue;
+                    }
                                         
-                                        // Parse the vote transaction directly using the Yellowstone data
-                                        match crate::modules::parser::parse_yellowstone_vote_transaction(
-                                            &tx_info,
-                                            validator.pubkey,
-                                            validator.vote_account,
-                                            tx_update.slot,
-                                        ) {
-                                            Ok(vote_latency) => {
-                                                debug!(
-                                                    "Parsed vote transaction: slot={}, latency={}ms",
-                                                    vote_latency.slot,
-                                                    vote_latency.latency_ms
-                                                );
+                    let vote_accounts = targets.vote_accounts();
+                    let request = Self::create_vote_subscription_request_static(&vote_accounts, &ctx.config.grpc);
+                    subscribe_tx.send(request).await
+                        .map_err(|e| crate::error::Error::network(format!("Failed to update subscription filter: {}", e)))?;
                                                 
-                                                // Send the parsed vote latency directly to storage
-                                                // Note: We need to update the channel type or create a new channel
-                                                // For now, let's create a VoteTransaction for compatibility
-                                                let vote_tx = VoteTransaction {
-                                                    signature: vote_latency.signature.clone(),
-                                                    validator_pubkey: validator.pubkey,
-                                                    vote_pubkey: validator.vote_account,
-                                                    slot: tx_update.slot,
-                                                    timestamp: chrono::Utc::now(),
-                                                    raw_data: Vec::new(),
-                                                    voted_on_slots: vote_latency.voted_on_slots.clone(),
-                                                    landed_slot: Some(vote_latency.landed_slot),
-                                                };
+                    info!(
+                        "Updated filter for {} to {} vote accounts",
+                        targets.label(),
+                        vote_accounts.len()
+                    );
+                }
+            }
+        }
                                                 
-                                                // Send to processing channel
-                                                if let Err(e) = tx_channel.send(vote_tx).await {
-                                                    error!("Failed to send vote transaction: {}", e);
-                                                }
-                                            }
-                                            Err(e) => {
-                                                error!("Failed to parse vote transaction: {}", e);
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                            UpdateOneof::Account(account_update) => {
+        warn!("Stream ended for {}", targets.label());
+        Ok(())
+    }
+    
+    /// Check a vote for duplicates and account for votes recovered by replay
+    ///
+    /// In race mode the same vote arrives once per endpoint; the first copy is
+    /// forwarded and the later ones feed the per-endpoint delivery stats.
+    fn admit_vote(
+        targets: &StreamTargets,
+        ctx: &StreamContext,
+        progress: &mut StreamProgress,
+        signature: &str,
+        landed_slot: u64,
+    ) -> VoteAdmission {
+        let counters = &ctx.reconnect_counters;
+        let first_delivery = ctx.remember_signature(signature, &progress.endpoint);
+        
+        if progress.replay.as_ref().is_some_and(|replay| landed_slot > replay.until_slot) {
+            // Caught up with the live tip seen at reconnect
+            if let Some(replay) = progress.replay.take() {
+                info!("Replay complete for {}, recovered {} votes", targets.label(), replay.recovered);
+            }
+        }
+        
+        // Replayed copies trail the live ones by the whole outage, so they
+        // don't say anything about how fast the endpoint delivers
+        let racing = ctx.config.grpc.endpoint_mode == EndpointMode::Race
+            && ctx.endpoints.len() > 1
+            && progress.replay.is_none();
+        
+        if let Some(first) = first_delivery {
+            let behind = (racing && first.endpoint != progress.endpoint)
+                .then(|| first.received_at.elapsed());
+            if let Some(behind) = behind {
+                let mut stats = ctx.endpoint_stats.entry(progress.endpoint.to_string()).or_default();
+                stats.late_deliveries += 1;
+                stats.total_delay_ms += behind.as_millis() as u64;
+            } else {
+                counters.duplicate_votes.fetch_add(1, Ordering::Relaxed);
+                debug!("Dropping duplicate vote {}", signature);
+            }
+            return VoteAdmission::Duplicate {
+                replaying: progress.replay.is_some(),
+                behind,
+            };
+        }
+        
+        if racing {
+            ctx.endpoint_stats.entry(progress.endpoint.to_string()).or_default().first_deliveries += 1;
+        }
+        
+        if let Some(replay) = progress.replay.as_mut() {
+            replay.recovered += 1;
+            counters.recovered_votes.fetch_add(1, Ordering::Relaxed);
+            return VoteAdmission::Recovered;
+        }
+        
+        VoteAdmission::Live
+    }
+    
+    /// Enrich a vote from the block meta index and forward it, unless it
+    /// has to wait for its landing block's metadata
+    async fn release_vote(ctx: &StreamContext, vote: VoteEvent) {
+        // Votes for abandoned slots are reported on their own as well
+        if let Some(fork_vote) = ForkVote::from_vote(&vote.latency) {
+            debug!(
+                "Validator {} voted for abandoned slot {} (hash {:?})",
+                fork_vote.validator_pubkey, fork_vote.slot, fork_vote.vote_hash
+            );
+            Self::forward_event(ctx, PipelineEvent::ForkVote(fork_vote)).await;
+        }
+        
+        if let Some(vote) = ctx.block_meta.hold(vote) {
+            Self::forward_vote(ctx, vote).await;
+        }
+    }
+    
+    /// Send a vote to the processing queue, applying the overflow policy
+    async fn forward_vote(ctx: &StreamContext, vote: VoteEvent) {
+        crate::metrics::METRICS.record_vote_landing(vote.latency.landing_status.as_str());
+        Self::forward_event(ctx, PipelineEvent::Vote(vote)).await;
+    }
+    
+    /// Send an event to the processing queue, applying the overflow policy
+    async fn forward_event(ctx: &StreamContext, event: PipelineEvent) {
+        match ctx.tx_channel.push(event).await {
+            PushOutcome::Queued | PushOutcome::Spilled => {}
+            PushOutcome::Dropped(PipelineEvent::Vote(dropped), DropReason::Closed) => {
+                error!("Failed to send vote {}: processing queue closed", dropped.latency.signature);
+            }
+            PushOutcome::Dropped(PipelineEvent::MissedVote(dropped), DropReason::Closed) => {
+                error!(
+                    "Failed to send missed vote of {} at slot {}: processing queue closed",
+                    dropped.validator_pubkey, dropped.slot
+                );
+            }
+            PushOutcome::Dropped(PipelineEvent::MissedVote(dropped), reason) => {
+                debug!(
+                    "Vote queue full, dropped missed vote of {} at slot {} ({})",
+                    dropped.validator_pubkey,
+                    dropped.slot,
+                    reason.as_str()
+                );
+            }
+            PushOutcome::Dropped(PipelineEvent::HashMismatch(dropped), reason) => {
+                debug!(
+                    "Vote queue full, dropped hash mismatch of {} at slot {} ({})",
+                    dropped.validator_pubkey,
+                    dropped.slot,
+                    reason.as_str()
+                );
+            }
+            PushOutcome::Dropped(PipelineEvent::ForkVote(dropped), reason) => {
+                debug!(
+                    "Vote queue full, dropped fork vote of {} at slot {} ({})",
+                    dropped.validator_pubkey,
+                    dropped.slot,
+                    reason.as_str()
+                );
+            }
+            PushOutcome::Dropped(PipelineEvent::VoteAccount(dropped), reason) => {
+                debug!(
+                    "Vote queue full, dropped vote account update of {} at slot {} ({})",
+                    dropped.state.validator_pubkey,
+                    dropped.state.slot,
+                    reason.as_str()
+                );
+            }
+            PushOutcome::Dropped(PipelineEvent::Vote(dropped), reason) => {
+                debug!(
+                    "Vote queue full, dropped vote {} from {} ({})",
+                    dropped.latency.signature,
+                    dropped.latency.validator_pubkey,
+                    reason.as_str()
+                );
+                crate::metrics::METRICS.record_vote_drop(&dropped.latency.validator_pubkey.to_string(), reason.as_str());
+            }
+        }
+        
+        let depth = ctx.tx_channel.depth();
+        crate::metrics::METRICS.set_vote_queue_depth(depth.memory, depth.spilled);
+    }
+    
+    /// Process a single update received on a stream
+    async fn process_update(
+        targets: &StreamTargets,
+        ctx: &StreamContext,
+        update: SubscribeUpdate,
+        progress: &mut StreamProgress,
+    ) {
+        let received_at = chrono::Utc::now();
+        let created_at = update.created_at
+            .and_then(|ts| chrono::DateTime::from_timestamp(ts.seconds, ts.nanos.max(0) as u32));
+        let Some(update_oneof) = update.update_oneof else {
+            return;
+        };
+        
+        match update_oneof {
+            UpdateOneof::Transaction(tx_update) => {
+                if let Some(tx_info) = tx_update.transaction {
+                    if tx_info.is_vote {
+                        // Route the vote to its validator by the vote account in the transaction
+                        let account_keys = tx_info.transaction.as_ref()
+                            .and_then(|tx| tx.message.as_ref())
+                            .map(|message| message.account_keys.as_slice())
+                            .unwrap_or_default();
+                        
+                        let Some(validator) = targets.resolve_transaction(account_keys) else {
+                            debug!(
+                                "Vote transaction on {} does not reference a tracked vote account",
+                                targets.label()
+                            );
+                            return;
+                        };
+                        
+                        debug!(
+                            "Received vote transaction from validator {}",
+                            validator.pubkey
+                        );
+                        
+                        progress.last_landed_slot = Some(
+                            progress.last_landed_slot.map_or(tx_update.slot, |slot| slot.max(tx_update.slot))
+                        );
+                        
+                        // Parse the vote transaction directly using the Yellowstone data
+                        let timing = StreamTiming {
+                            landed_at: ctx.block_meta.started_at(tx_update.slot),
+                            created_at,
+                            received_at,
+                        };
+                        match crate::modules::parser::parse_yellowstone_vote_transaction_with_timing(
+                            &tx_info,
+                            validator.pubkey,
+                            validator.vote_account,
+                            tx_update.slot,
+                            &timing,
+                        ) {
+                            Ok(mut vote_latency) => {
                                 debug!(
-                                    "Received account update for slot {} from validator {}",
-                                    account_update.slot,
-                                    validator.pubkey
+                                    "Parsed vote transaction: slot={}, latency={}ms",
+                                    vote_latency.slot,
+                                    vote_latency.latency_ms
                                 );
                                 
-                                // Account updates are logged for debugging but not used for latency calculation
-                                // The slot in an account update is when the account was updated, not when
-                                // the vote transaction landed, so it's not accurate for latency measurement.
-                                
-                                if let Some(account_info) = &account_update.account {
-                                    // Check if this is the vote account we're interested in
-                                    if let Ok(pubkey) = Pubkey::try_from(account_info.pubkey.as_slice()) {
-                                        if pubkey == validator.vote_account {
-                                            debug!(
-                                                "Vote account update for validator {} at slot {} (for tracking only)",
-                                                validator.pubkey,
-                                                account_update.slot
-                                            );
-                                            
-                                            // We could parse vote state here for debugging/tracking purposes
-                                            // but we don't use it for latency calculation
-                                            match crate::modules::parser::parse_vote_account_data(
-                                                &account_info.data,
-                                                validator.pubkey,
-                                                validator.vote_account,
-                                                account_update.slot,
-                                            ) {
-                                                Ok(_) => {
-                                                    debug!("Successfully parsed vote account state");
-                                                }
-                                                Err(e) => {
-                                                    debug!("Failed to parse vote account data: {}", e);
-                                                }
-                                            }
-                                        }
+                                let admission = Self::admit_vote(targets, ctx, progress, &vote_latency.signature, tx_update.slot);
+                                if ctx.config.grpc.endpoint_mode == EndpointMode::Race && ctx.endpoints.len() > 1 {
+                                    let behind = match admission {
+                                        VoteAdmission::Duplicate { behind, .. } => behind,
+                                        _ => None,
+                                    };
+                                    if !matches!(admission, VoteAdmission::Duplicate { behind: None, .. }) {
+                                        crate::metrics::METRICS.record_endpoint_vote(&progress.endpoint, behind);
                                     }
                                 }
-                            }
-                            UpdateOneof::Slot(slot_update) => {
-                                debug!(
-                                    "Received slot update: slot={}, status={}",
-                                    slot_update.slot,
-                                    slot_update.status
-                                );
                                 
-                                // Update the highest slot atomically - only move forward
-                                // Use compare-and-swap to ensure we only update if this is a higher slot
-                                let mut current = highest_slot.load(std::sync::atomic::Ordering::Acquire);
-                                loop {
-                                    if slot_update.slot <= current {
-                                        // This slot is not higher, no update needed
-                                        break;
+                                match admission {
+                                    VoteAdmission::Live => {}
+                                    VoteAdmission::Recovered => {
+                                        crate::metrics::METRICS.record_replay_vote("recovered");
                                     }
-                                    
-                                    match highest_slot.compare_exchange_weak(
-                                        current,
-                                        slot_update.slot,
-                                        std::sync::atomic::Ordering::Release,
-                                        std::sync::atomic::Ordering::Acquire,
-                                    ) {
-                                        Ok(_) => {
-                                            debug!("Updated highest slot from {} to {}", current, slot_update.slot);
-                                            break;
-                                        }
-                                        Err(actual) => {
-                                            // Another thread updated the value, retry with the new value
-                                            current = actual;
+                                    VoteAdmission::Duplicate { replaying, behind } => {
+                                        if replaying && behind.is_none() {
+                                            crate::metrics::METRICS.record_replay_vote("duplicate");
                                         }
+                                        return;
                                     }
                                 }
+                                
+                                // Tower votes repeat earlier slots; keep the newly voted ones.
+                                // Refreshed copies are only told apart by finality
+                                if !ctx.towers.retain_new_slots(&mut vote_latency, ctx.finality.is_some()) {
+                                    debug!("Vote {} adds no new slots", vote_latency.signature);
+                                    return;
+                                }
+                                
+                                if let Some(missed_votes) = &ctx.missed_votes {
+                                    missed_votes.record_vote(&vote_latency);
+                                }
+                                
+                                for mismatch in ctx.vote_hashes.record_vote(&vote_latency) {
+                                    warn!(
+                                        "Validator {} voted hash {} for slot {}, the cluster voted {}",
+                                        mismatch.validator_pubkey, mismatch.vote_hash, mismatch.slot, mismatch.cluster_hash
+                                    );
+                                    crate::metrics::METRICS.record_hash_mismatch(&mismatch.validator_pubkey.to_string());
+                                    Self::forward_event(ctx, PipelineEvent::HashMismatch(mismatch)).await;
+                                }
+                                
+                                // Send the parsed vote on with the stream metadata
+                                vote_latency.landing_status = commitment_landing_status(ctx.config.grpc.commitment);
+                                let vote = VoteEvent {
+                                    latency: vote_latency,
+                                    created_at,
+                                    received_at,
+                                    tip_slot: ctx.highest_slot.load(Ordering::Acquire),
+                                    endpoint: progress.endpoint.to_string(),
+                                };
+                                
+                                // Hold the vote until its landing slot resolves when tracking finality
+                                let vote = match &ctx.finality {
+                                    Some(finality) => finality.hold(vote),
+                                    None => Some(vote),
+                                };
+                                if let Some(vote) = vote {
+                                    Self::release_vote(ctx, vote).await;
+                                }
                             }
-                            UpdateOneof::Ping(_ping) => {
-                                debug!("Received ping from validator {}", validator.pubkey);
-                                // Could implement pong response here if needed
+                            Err(e) => {
+                                error!("Failed to parse vote transaction: {}", e);
                             }
-                            _ => {
-                                // Other update types not needed for MVP
+                        }
+                    }
+                }
+            }
+            UpdateOneof::Account(account_update) => {
+                debug!(
+                    "Received account update for slot {} on {}",
+                    account_update.slot,
+                    targets.label()
+                );
+                
+                // Account updates are recorded as vote state but not used for latency calculation
+                // The slot in an account update is when the account was updated, not when
+                // the vote transaction landed, so it's not accurate for latency measurement.
+                
+                if let Some(account_info) = &account_update.account {
+                    // Check if this is a vote account we're interested in
+                    if let Ok(pubkey) = Pubkey::try_from(account_info.pubkey.as_slice()) {
+                        if let Some(validator) = targets.resolve_account(&pubkey) {
+                            debug!(
+                                "Vote account update for validator {} at slot {}",
+                                validator.pubkey,
+                                account_update.slot
+                            );
+                            
+                            match crate::modules::parser::parse_vote_account_data(
+                                &account_info.data,
+                                validator.pubkey,
+                                validator.vote_account,
+                                account_update.slot,
+                            ) {
+                                Ok(state) => {
+                                    debug!("Successfully parsed vote account state");
+                                    if let Some(mut update) = ctx.vote_states.observe(state) {
+                                        let finalized_slot = ctx.finalized_slot.load(Ordering::Acquire);
+                                        update.finalized_slot = (finalized_slot > 0).then_some(finalized_slot);
+                                        Self::forward_event(ctx, PipelineEvent::VoteAccount(update)).await;
+                                    }
+                                }
+                                Err(e) => {
+                                    debug!("Failed to parse vote account data: {}", e);
+                                }
                             }
                         }
                     }
                 }
-                Err(e) => {
-                    error!(
-                        "Error receiving from validator {}: {}",
-                        validator.pubkey, e
-                    );
-                    return Err(crate::error::Error::network(format!(
-                        "Stream error: {}",
-                        e
-                    )));
+            }
+            UpdateOneof::Slot(slot_update) => {
+                debug!(
+                    "Received slot update: slot={}, status={}",
+                    slot_update.slot,
+                    slot_update.status
+                );
+                
+                let slot_start = created_at.unwrap_or(received_at);
+                for vote in ctx.block_meta.record_slot_start(slot_update.slot, slot_start) {
+                    Self::forward_vote(ctx, vote).await;
+                }
+                
+                // Update the highest slot atomically - only move forward
+                // Use compare-and-swap to ensure we only update if this is a higher slot
+                let highest_slot = &ctx.highest_slot;
+                let mut current = highest_slot.load(std::sync::atomic::Ordering::Acquire);
+                loop {
+                    if slot_update.slot <= current {
+                        // This slot is not higher, no update needed
+                        break;
+                    }
+                    
+                    match highest_slot.compare_exchange_weak(
+                        current,
+                        slot_update.slot,
+                        std::sync::atomic::Ordering::Release,
+                        std::sync::atomic::Ordering::Acquire,
+                    ) {
+                        Ok(_) => {
+                            debug!("Updated highest slot from {} to {}", current, slot_update.slot);
+                            break;
+                        }
+                        Err(actual) => {
+                            // Another thread updated the value, retry with the new value
+                            current = actual;
+                        }
+                    }
                 }
+                
+                // Root lag is measured against the highest finalized slot
+                if slot_update.status == SlotStatus::SlotFinalized as i32 {
+                    ctx.finalized_slot.fetch_max(slot_update.slot, Ordering::AcqRel);
+                }
+                
+                // Check validators' votes against the slots that left the tolerance window
+                if let Some(missed_votes) = &ctx.missed_votes {
+                    if slot_update.status == SlotStatus::SlotConfirmed as i32 {
+                        for missed in missed_votes.on_confirmed(slot_update.slot) {
+                            debug!("Validator {} missed confirmed slot {}", missed.validator_pubkey, missed.slot);
+                            Self::forward_event(ctx, PipelineEvent::MissedVote(missed)).await;
+                        }
+                    }
+                }
+                
+                // Resolve held votes as their landing slots are finalized or skipped
+                if let Some(finality) = &ctx.finality {
+                    let update = match SlotStatus::try_from(slot_update.status) {
+                        Ok(SlotStatus::SlotProcessed) => Some(SlotUpdate::Processed),
+                        Ok(SlotStatus::SlotConfirmed) => Some(SlotUpdate::Confirmed),
+                        Ok(SlotStatus::SlotFinalized) => Some(SlotUpdate::Finalized),
+                        Ok(SlotStatus::SlotDead) => Some(SlotUpdate::Dead),
+                        _ => None,
+                    };
+                    if let Some(update) = update {
+                        for vote in finality.on_slot(slot_update.slot, slot_update.parent, update) {
+                            Self::release_vote(ctx, vote).await;
+                        }
+                    }
+                }
+            }
+            UpdateOneof::Ping(_ping) => {
+                // Answered in handle_stream_static, which owns the request sink
+                debug!("Received ping on {}", targets.label());
+            }
+            UpdateOneof::BlockMeta(block_meta) => {
+                debug!("Received block meta: slot={}", block_meta.slot);
+                
+                // Release the votes that were waiting for this block
+                for vote in ctx.block_meta.insert(BlockMeta::from_update(&block_meta)) {
+                    Self::forward_vote(ctx, vote).await;
+                }
+            }
+            _ => {
+                // Other update types not needed for MVP
             }
         }
-        
-        warn!("Stream ended for validator {}", validator.pubkey);
-        Ok(())
     }
 }
 
 #[async_trait]
 impl SubscriptionManagerTrait for SubscriptionManager {
     async fn subscribe(&self, validator: &ValidatorInfo) -> Result<()> {
+        let subscribed = match self.config.grpc.subscription_mode {
+            SubscriptionMode::PerValidator => self.active_connections.contains_key(&validator.pubkey),
+            SubscriptionMode::Multiplexed => self.tracked_validators.contains_key(&validator.vote_account),
+        };
+        if subscribed {
+            debug!("Already subscribed to validator: {}", validator.pubkey);
+            return Ok(());
+        }
+        
+        // Queue the validator instead of going over the subscription cap
+        if self.active_subscriptions().await >= self.admission.max_subscriptions() {
+            debug!("Subscription limit reached, queueing validator: {}", validator.pubkey);
+            self.admission.queue(validator);
+            return Ok(());
+        }
+        
         info!("Subscribing to validator: {}", validator.pubkey);
+        self.admission.dequeue(&validator.pubkey);
         
-        // Check if already subscribed
-        if self.active_connections.contains_key(&validator.pubkey) {
-            debug!("Already subscribed to validator: {}", validator.pubkey);
+        if self.config.grpc.subscription_mode == SubscriptionMode::Multiplexed {
+            // Add the vote account to its shard's filter
+            self.tracked_validators.insert(validator.vote_account, validator.clone());
+            self.notify_shard(&validator.vote_account);
             return Ok(());
         }
         
         // Clone necessary data for the spawned task
-        let validator_clone = validator.clone();
-        let tx_channel = self.tx_channel.clone();
-        let config = Arc::clone(&self.config);
+        let pubkey = validator.pubkey;
+        let targets = StreamTargets::Single(validator.clone());
         let connections = Arc::clone(&self.active_connections);
-        let grpc_endpoint = self.grpc_endpoint.clone();
-        let highest_slot = Arc::clone(&self.highest_slot);
+        let stream = self.stream_task(StreamId::Validator(pubkey), targets, None);
         
         // Spawn subscription task
         let handle = tokio::spawn(async move {
-            loop {
-                match Self::run_subscription(&validator_clone, tx_channel.clone(), config.clone(), grpc_endpoint.clone(), highest_slot.clone()).await {
-                    Ok(_) => {
-                        info!("Subscription ended normally for validator {}", validator_clone.pubkey);
-                        break;
-                    }
-                    Err(e) => {
-                        error!(
-                            "Subscription error for validator {}: {}",
-                            validator_clone.pubkey, e
-                        );
-                        
-                        // Wait before reconnecting
-                        tokio::time::sleep(tokio::time::Duration::from_secs(
-                            config.grpc.reconnect_interval_secs
-                        )).await;
-                        
-                        info!("Attempting to reconnect to validator {}", validator_clone.pubkey);
-                    }
-                }
-            }
+            stream.await;
             
-            // Remove from active connections when done
-            connections.remove(&validator_clone.pubkey);
+            // Remove from active connections when done; the monitor stays so
+            // a failed stream remains visible until the validator is dropped
+            connections.remove(&pubkey);
         });
         
         self.active_connections.insert(validator.pubkey, handle);
@@ -492,9 +1866,30 @@ impl SubscriptionManagerTrait for SubscriptionManager {
 
     async fn unsubscribe(&self, pubkey: &Pubkey) -> Result<()> {
         info!("Unsubscribing from validator: {}", pubkey);
+        self.towers.remove(pubkey);
+        self.vote_states.remove(pubkey);
+        if let Some(missed_votes) = &self.missed_votes {
+            missed_votes.remove(pubkey);
+        }
+        
+        if self.config.grpc.subscription_mode == SubscriptionMode::Multiplexed {
+            // Tracked validators are keyed by vote account, find the identity's entry
+            let vote_account = self.tracked_validators
+                .iter()
+                .find(|entry| entry.value().pubkey == *pubkey)
+                .map(|entry| *entry.key());
+            
+            if let Some(vote_account) = vote_account {
+                self.tracked_validators.remove(&vote_account);
+                self.notify_shard(&vote_account);
+                debug!("Unsubscribed from validator: {}", pubkey);
+            }
+            return Ok(());
+        }
         
         if let Some((_, handle)) = self.active_connections.remove(pubkey) {
             handle.abort();
+            self.remove_stream_monitors(StreamId::Validator(*pubkey));
             debug!("Unsubscribed from validator: {}", pubkey);
         }
         
@@ -502,7 +1897,10 @@ impl SubscriptionManagerTrait for SubscriptionManager {
     }
 
     async fn active_subscriptions(&self) -> usize {
-        self.active_connections.len()
+        match self.config.grpc.subscription_mode {
+            SubscriptionMode::PerValidator => self.active_connections.len(),
+            SubscriptionMode::Multiplexed => self.tracked_validators.len(),
+        }
     }
 }
 
@@ -511,10 +1909,22 @@ impl Shutdown for SubscriptionManager {
     async fn shutdown(&mut self) -> Result<()> {
         info!("Shutting down subscription manager");
         
+        // Nothing may restart the multiplexed streams from here on
+        self.pool_started.store(false, Ordering::Release);
+        
+        let health_handle = self.health_handle.lock().take();
+        if let Some(handle) = health_handle {
+            handle.abort();
+            let _ = tokio::time::timeout(Duration::from_secs(5), handle).await;
+        }
+        
         // Cancel all active connections
         for entry in self.active_connections.iter() {
             entry.value().abort();
         }
+        for entry in self.shard_handles.iter() {
+            entry.value().abort();
+        }
         
         // Wait for all tasks to finish
         let handles: Vec<_> = self.active_connections
@@ -531,6 +1941,29 @@ impl Shutdown for SubscriptionManager {
             }
         }
         
+        let shards: Vec<usize> = self.shard_handles.iter().map(|entry| *entry.key()).collect();
+        for index in shards {
+            if let Some((_, handle)) = self.shard_handles.remove(&index) {
+                let _ = tokio::time::timeout(
+                    std::time::Duration::from_secs(5),
+                    handle
+                ).await;
+            }
+        }
+        
+        self.stream_monitors.clear();
+        
+        // Votes still waiting for block metadata won't get it now
+        let ctx = self.stream_context();
+        for vote in self.block_meta.drain() {
+            Self::forward_vote(&ctx, vote).await;
+        }
+        
+        // Streams are down; keep votes the pipeline didn't get to for the next run
+        self.tx_channel.persist().await;
+        // The pipeline finishes what is left in memory and stops
+        self.tx_channel.close();
+        
         info!("Subscription manager shutdown complete");
         Ok(())
     }
@@ -621,6 +2054,339 @@ mod tests {
         }
         // Test passes if we reach here without panic
     }
+
+    #[test]
+    fn test_shard_assignment_is_stable_and_in_range() {
+        for _ in 0..100 {
+            let vote_account = Pubkey::new_unique();
+            let shard = shard_for(&vote_account, 4);
+            assert!(shard < 4);
+            assert_eq!(shard, shard_for(&vote_account, 4));
+            assert_eq!(shard_for(&vote_account, 1), 0);
+        }
+    }
+
+    #[test]
+    fn test_vote_request_covers_all_accounts() {
+        let accounts = vec![Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+        let request = SubscriptionManager::create_vote_subscription_request_static(&accounts, &Config::default().grpc);
+        
+        let tx_filter = &request.transactions["vote_transactions"];
+        assert_eq!(tx_filter.vote, Some(true));
+        assert_eq!(tx_filter.account_include.len(), 3);
+        assert_eq!(request.accounts["vote_account"].account.len(), 3);
+        assert!(request.slots.contains_key("all_slots"));
+    }
+
+    #[test]
+    fn test_vote_request_without_accounts_only_tracks_slots() {
+        let request = SubscriptionManager::create_vote_subscription_request_static(&[], &Config::default().grpc);
+        
+        assert!(request.transactions.is_empty());
+        assert!(request.accounts.is_empty());
+        assert!(request.slots.contains_key("all_slots"));
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_votes_dropped_by_signature() {
+        let config = Arc::new(Config::default());
+        let (_shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+        let manager = SubscriptionManager::new(config, shutdown_rx).await.unwrap();
+        let ctx = manager.stream_context();
+        let targets = StreamTargets::Single(ValidatorInfo {
+            pubkey: Pubkey::new_unique(),
+            vote_account: Pubkey::new_unique(),
+            name: None,
+            description: None,
+            website: None,
+            grpc_endpoint: None,
+            activated_stake: 0,
+            delinquent: false,
+        });
+        let mut progress = StreamProgress::default();
+        
+        assert_eq!(SubscriptionManager::admit_vote(&targets, &ctx, &mut progress, "sig1", 100), VoteAdmission::Live);
+        assert_eq!(SubscriptionManager::admit_vote(&targets, &ctx, &mut progress, "sig1", 100), VoteAdmission::Duplicate { replaying: false, behind: None });
+        assert_eq!(SubscriptionManager::admit_vote(&targets, &ctx, &mut progress, "sig2", 101), VoteAdmission::Live);
+        
+        let stats = manager.reconnect_stats();
+        assert_eq!(stats.duplicate_votes, 1);
+        assert_eq!(stats.recovered_votes, 0);
+    }
+    
+    #[tokio::test]
+    async fn test_replayed_votes_counted_until_caught_up() {
+        let config = Arc::new(Config::default());
+        let (_shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+        let manager = SubscriptionManager::new(config, shutdown_rx).await.unwrap();
+        let ctx = manager.stream_context();
+        let targets = StreamTargets::Shard {
+            index: 0,
+            pool_size: 1,
+            tracked: Arc::clone(&manager.tracked_validators),
+        };
+        let mut progress = StreamProgress {
+            last_landed_slot: Some(100),
+            replay_supported: Some(true),
+            replay: Some(ReplayWindow { until_slot: 110, recovered: 0 }),
+            streaming_since: None,
+            endpoint: Arc::from("primary"),
+        };
+        
+        // Already forwarded before the disconnect
+        assert!(ctx.remember_signature("before", &progress.endpoint).is_none());
+        
+        assert_eq!(SubscriptionManager::admit_vote(&targets, &ctx, &mut progress, "before", 100), VoteAdmission::Duplicate { replaying: true, behind: None });
+        assert_eq!(SubscriptionManager::admit_vote(&targets, &ctx, &mut progress, "gap1", 105), VoteAdmission::Recovered);
+        assert_eq!(SubscriptionManager::admit_vote(&targets, &ctx, &mut progress, "gap2", 110), VoteAdmission::Recovered);
+        assert!(progress.replay.is_some());
+        
+        // A vote past the reconnect tip ends the replay window
+        assert_eq!(SubscriptionManager::admit_vote(&targets, &ctx, &mut progress, "live", 111), VoteAdmission::Live);
+        assert!(progress.replay.is_none());
+        
+        let stats = manager.reconnect_stats();
+        assert_eq!(stats.recovered_votes, 2);
+        assert_eq!(stats.duplicate_votes, 1);
+    }
+    
+    #[tokio::test]
+    async fn test_race_mode_tracks_first_and_late_deliveries() {
+        let mut config = Config::default();
+        config.grpc.endpoint_mode = EndpointMode::Race;
+        config.grpc.endpoints = vec![
+            GrpcEndpointConfig { name: Some("fast".to_string()), url: "http://fast:10000".to_string(), access_token: None },
+            GrpcEndpointConfig { name: Some("slow".to_string()), url: "http://slow:10000".to_string(), access_token: None },
+        ];
+        let (_shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+        let manager = SubscriptionManager::new(Arc::new(config), shutdown_rx).await.unwrap();
+        let ctx = manager.stream_context();
+        let targets = StreamTargets::Single(ValidatorInfo {
+            pubkey: Pubkey::new_unique(),
+            vote_account: Pubkey::new_unique(),
+            name: None,
+            description: None,
+            website: None,
+            grpc_endpoint: None,
+            activated_stake: 0,
+            delinquent: false,
+        });
+        let mut fast = StreamProgress { endpoint: Arc::from("fast"), ..Default::default() };
+        let mut slow = StreamProgress { endpoint: Arc::from("slow"), ..Default::default() };
+        
+        assert_eq!(SubscriptionManager::admit_vote(&targets, &ctx, &mut fast, "sig1", 100), VoteAdmission::Live);
+        assert!(matches!(
+            SubscriptionManager::admit_vote(&targets, &ctx, &mut slow, "sig1", 100),
+            VoteAdmission::Duplicate { replaying: false, behind: Some(_) }
+        ));
+        assert_eq!(SubscriptionManager::admit_vote(&targets, &ctx, &mut slow, "sig2", 101), VoteAdmission::Live);
+        
+        let stats = manager.endpoint_stats();
+        assert_eq!(stats["fast"].first_deliveries, 1);
+        assert_eq!(stats["slow"].first_deliveries, 1);
+        assert_eq!(stats["slow"].late_deliveries, 1);
+        assert_eq!(stats["slow"].win_rate(), 0.5);
+        
+        // Race copies are expected, not duplicates
+        assert_eq!(manager.reconnect_stats().duplicate_votes, 0);
+        
+        // A replaying endpoint's copies say nothing about its speed
+        slow.replay = Some(ReplayWindow { until_slot: 200, recovered: 0 });
+        assert_eq!(SubscriptionManager::admit_vote(&targets, &ctx, &mut fast, "sig3", 102), VoteAdmission::Live);
+        assert_eq!(
+            SubscriptionManager::admit_vote(&targets, &ctx, &mut slow, "sig3", 102),
+            VoteAdmission::Duplicate { replaying: true, behind: None }
+        );
+        assert_eq!(SubscriptionManager::admit_vote(&targets, &ctx, &mut slow, "sig4", 103), VoteAdmission::Recovered);
+        let stats = manager.endpoint_stats();
+        assert_eq!(stats["fast"].first_deliveries, 2);
+        assert_eq!(stats["slow"].first_deliveries, 1);
+        assert_eq!(stats["slow"].late_deliveries, 1);
+    }
+    
+    #[test]
+    fn test_endpoint_stats_rates() {
+        let stats = EndpointStats {
+            first_deliveries: 3,
+            late_deliveries: 1,
+            total_delay_ms: 40,
+        };
+        assert_eq!(stats.win_rate(), 0.75);
+        assert_eq!(stats.avg_delay_ms(), 40.0);
+        assert_eq!(EndpointStats::default().win_rate(), 0.0);
+    }
+    
+    #[test]
+    fn test_plaintext_endpoint_skips_tls() {
+        let mut config = Config::default();
+        config.grpc.tls_ca_cert_path = Some("/nonexistent/ca.pem".to_string());
+        
+        assert!(client_tls_config(&config.grpc, "http://localhost:10000").unwrap().is_none());
+        
+        // TLS endpoints fail on the missing CA bundle
+        assert!(client_tls_config(&config.grpc, "https://example.com:443").is_err());
+        
+        config.grpc.tls_ca_cert_path = None;
+        assert!(client_tls_config(&config.grpc, "https://example.com:443").unwrap().is_some());
+        
+        config.grpc.enable_tls = false;
+        assert!(client_tls_config(&config.grpc, "https://example.com:443").is_err());
+    }
+    
+    #[test]
+    fn test_vote_request_commitment_and_slot_statuses() {
+        let mut config = Config::default();
+        let request = SubscriptionManager::create_vote_subscription_request_static(&[], &config.grpc);
+        assert_eq!(request.commitment, Some(CommitmentLevel::Processed as i32));
+        assert_eq!(request.slots["all_slots"].filter_by_commitment, Some(true));
+        assert_eq!(request.slots["all_slots"].interslot_updates, Some(true));
+        assert!(request.blocks_meta.is_empty());
+        
+        config.grpc.subscribe_block_meta = true;
+        let request = SubscriptionManager::create_vote_subscription_request_static(&[], &config.grpc);
+        assert!(request.blocks_meta.contains_key("block_meta"));
+        
+        // Missed vote detection needs confirmed slot statuses
+        config.grpc.track_missed_votes = true;
+        let request = SubscriptionManager::create_vote_subscription_request_static(&[], &config.grpc);
+        assert_eq!(request.slots["all_slots"].filter_by_commitment, Some(false));
+        config.grpc.track_missed_votes = false;
+        
+        // Root lag needs finalized slot statuses
+        config.grpc.track_root_lag = true;
+        let request = SubscriptionManager::create_vote_subscription_request_static(&[], &config.grpc);
+        assert_eq!(request.slots["all_slots"].filter_by_commitment, Some(false));
+        config.grpc.track_root_lag = false;
+        
+        // Finality tracking needs every slot status
+        config.grpc.commitment = GrpcCommitment::Confirmed;
+        config.grpc.track_finality = true;
+        let request = SubscriptionManager::create_vote_subscription_request_static(&[], &config.grpc);
+        assert_eq!(request.commitment, Some(CommitmentLevel::Confirmed as i32));
+        assert_eq!(request.slots["all_slots"].filter_by_commitment, Some(false));
+        
+        config.grpc.commitment = GrpcCommitment::Finalized;
+        assert!(!tracks_finality(&config.grpc));
+    }
+    
+    #[test]
+    fn test_vote_request_starts_live_by_default() {
+        let request = SubscriptionManager::create_vote_subscription_request_static(&[Pubkey::new_unique()], &Config::default().grpc);
+        assert_eq!(request.from_slot, None);
+    }
+    
+    #[tokio::test]
+    async fn test_multiplexed_subscribe_and_unsubscribe() {
+        let mut config = Config::default();
+        config.grpc.subscription_mode = SubscriptionMode::Multiplexed;
+        config.grpc.stream_pool_size = 2;
+        let (_shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+        let manager = SubscriptionManager::new(Arc::new(config), shutdown_rx).await.unwrap();
+        
+        let validator = ValidatorInfo {
+            pubkey: Pubkey::new_unique(),
+            vote_account: Pubkey::new_unique(),
+            name: None,
+            description: None,
+            website: None,
+            grpc_endpoint: None,
+            activated_stake: 0,
+            delinquent: false,
+        };
+        manager.subscribe(&validator).await.unwrap();
+        manager.subscribe(&validator).await.unwrap();
+        assert_eq!(manager.active_subscriptions().await, 1);
+        assert_eq!(manager.subscribed_validators(), vec![validator.pubkey]);
+        
+        // The validator must land in exactly one shard's filter
+        let shard = shard_for(&validator.vote_account, 2);
+        let targets = StreamTargets::Shard {
+            index: shard,
+            pool_size: 2,
+            tracked: Arc::clone(&manager.tracked_validators),
+        };
+        assert_eq!(targets.vote_accounts(), vec![validator.vote_account]);
+        let other = StreamTargets::Shard {
+            index: 1 - shard,
+            pool_size: 2,
+            tracked: Arc::clone(&manager.tracked_validators),
+        };
+        assert!(other.vote_accounts().is_empty());
+        
+        // Votes are routed back by vote account key
+        let keys = vec![Pubkey::new_unique().to_bytes().to_vec(), validator.vote_account.to_bytes().to_vec()];
+        assert_eq!(targets.resolve_transaction(&keys).map(|v| v.pubkey), Some(validator.pubkey));
+        
+        manager.unsubscribe(&validator.pubkey).await.unwrap();
+        assert_eq!(manager.active_subscriptions().await, 0);
+        assert!(targets.vote_accounts().is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_subscribe_respects_max_subscriptions() {
+        // Accepts connections but never answers, so the stream stays up
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut config = Config::default();
+        config.grpc.endpoint = Some(format!("http://{}", listener.local_addr().unwrap()));
+        config.grpc.max_subscriptions = 1;
+        let (_shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+        let manager = SubscriptionManager::new(Arc::new(config), shutdown_rx).await.unwrap();
+        
+        let first = ValidatorInfo::new(Pubkey::new_unique(), Pubkey::new_unique());
+        let second = ValidatorInfo::new(Pubkey::new_unique(), Pubkey::new_unique());
+        manager.subscribe(&first).await.unwrap();
+        manager.subscribe(&second).await.unwrap();
+        
+        assert_eq!(manager.subscribed_validators(), vec![first.pubkey]);
+        let queued: Vec<Pubkey> = manager.queued_validators().iter().map(|v| v.pubkey).collect();
+        assert_eq!(queued, vec![second.pubkey]);
+        
+        // A freed slot goes to the queued validator on the next subscribe
+        manager.unsubscribe(&first.pubkey).await.unwrap();
+        manager.subscribe(&second).await.unwrap();
+        assert_eq!(manager.subscribed_validators(), vec![second.pubkey]);
+        assert!(manager.queued_validators().is_empty());
+        manager.unsubscribe(&second.pubkey).await.unwrap();
+    }
+    
+    #[tokio::test]
+    async fn test_multiplexed_subscribe_not_capped() {
+        let mut config = Config::default();
+        config.grpc.subscription_mode = SubscriptionMode::Multiplexed;
+        config.grpc.max_subscriptions = 1;
+        let (_shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+        let manager = SubscriptionManager::new(Arc::new(config), shutdown_rx).await.unwrap();
+        
+        for _ in 0..3 {
+            manager.subscribe(&ValidatorInfo::new(Pubkey::new_unique(), Pubkey::new_unique())).await.unwrap();
+        }
+        assert_eq!(manager.active_subscriptions().await, 3);
+        assert!(manager.queued_validators().is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_ended_shard_streams_restart() {
+        let mut config = Config::default();
+        config.grpc.subscription_mode = SubscriptionMode::Multiplexed;
+        config.grpc.stream_pool_size = 2;
+        // Rejected before connecting, so every stream gives up right away
+        config.grpc.endpoint = Some("https://127.0.0.1:1".to_string());
+        config.grpc.enable_tls = false;
+        let (_shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+        let manager = SubscriptionManager::new(Arc::new(config), shutdown_rx).await.unwrap();
+        
+        manager.start_stream_pool();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !manager.shard_handles.is_empty() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("failed streams should leave the pool");
+        
+        manager.start_stream_pool();
+        assert_eq!(manager.shard_handles.len(), 2);
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,183 @@
+//! Vote Tower Module
+//!
+//! `UpdateVoteState` and `TowerSync` votes carry the validator's whole tower
+//! of lockouts rather than the slots voted on by that transaction. To give
+//! each newly voted slot its own latency exactly once, every vote is diffed
+//! against the last vote seen from the same validator.
+//!
+//! A validator's votes only ever add slots above its last voted slot (expired
+//! lockouts are popped, never re-voted), so the diff reduces to keeping the
+//! slots above the previous vote's highest slot. Late copies of older votes
+//! contribute nothing.
+//!
+//! Refreshed votes are the exception: when a vote landed on a fork, the
+//! validator re-signs the same tower and it lands again elsewhere. Only one
+//! copy can succeed on the finalized chain, so when landings are tracked
+//! through finality every copy is counted and the finality tracker resolves
+//! the ones left on a fork as skipped, keeping them out of the stats. Without
+//! finality tracking there is no telling which copy landed for good, and the
+//! first one seen is kept.
+
+use dashmap::DashMap;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::models::VoteLatency;
+
+/// The vote that last added slots to a validator's tower
+#[derive(Debug, Clone, Copy)]
+struct TowerTip {
+    /// Highest voted slot
+    highest: u64,
+    /// Highest slot of the vote before it; the slots above were newly voted
+    base: u64,
+    /// Slot the vote landed in
+    landed_slot: u64,
+}
+
+/// Last voted slot per validator identity
+#[derive(Default)]
+pub struct VoteTowers {
+    last_voted: DashMap<Pubkey, TowerTip>,
+}
+
+impl VoteTowers {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Trim a vote down to the slots it newly voted on
+    ///
+    /// The first vote seen from a validator keeps only its highest slot, since
+    /// the rest of its tower may have been voted on long before. A refreshed
+    /// copy of the last vote, landing in another slot, keeps the same slots
+    /// when `recount_refreshes` is set (see the module docs). Returns false
+    /// when the vote adds nothing to the validator's tower.
+    pub fn retain_new_slots(&self, vote: &mut VoteLatency, recount_refreshes: bool) -> bool {
+        let Some(highest) = vote.voted_on_slots.iter().max().copied() else {
+            return false;
+        };
+        
+        let landed_slot = vote.landed_slot;
+        let mut tip = self.last_voted.entry(vote.validator_pubkey).or_insert(TowerTip {
+            highest: highest.saturating_sub(1),
+            base: highest.saturating_sub(1),
+            landed_slot,
+        });
+        let base = if highest > tip.highest {
+            tip.highest
+        } else if recount_refreshes && highest == tip.highest && landed_slot != tip.landed_slot {
+            tip.base
+        } else {
+            return false;
+        };
+        *tip = TowerTip { highest, base, landed_slot };
+        drop(tip);
+        
+        let (voted_on_slots, latency_slots): (Vec<u64>, Vec<u8>) = vote
+            .voted_on_slots
+            .iter()
+            .zip(&vote.latency_slots)
+            .filter(|(&slot, _)| slot > base)
+            .unzip();
+        vote.voted_on_slots = voted_on_slots;
+        vote.latency_slots = latency_slots;
+        vote.slot = highest;
+        true
+    }
+    
+    /// Forget a validator's tower once it is no longer monitored
+    pub fn remove(&self, validator_pubkey: &Pubkey) {
+        self.last_voted.remove(validator_pubkey);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    
+    fn tower_vote(validator: Pubkey, tower: Vec<u64>, landed_slot: u64) -> VoteLatency {
+        VoteLatency::new_with_slots(
+            validator,
+            Pubkey::new_unique(),
+            tower.iter().max().copied().unwrap_or(landed_slot),
+            Utc::now(),
+            Utc::now(),
+            "sig".to_string(),
+            tower,
+            landed_slot,
+        )
+    }
+    
+    #[test]
+    fn test_first_vote_keeps_highest_slot() {
+        let towers = VoteTowers::new();
+        let mut vote = tower_vote(Pubkey::new_unique(), vec![90, 95, 99], 100);
+        
+        assert!(towers.retain_new_slots(&mut vote, false));
+        assert_eq!(vote.voted_on_slots, vec![99]);
+        assert_eq!(vote.latency_slots, vec![1]);
+        assert_eq!(vote.slot, 99);
+    }
+    
+    #[test]
+    fn test_catch_up_vote_counts_every_new_slot_once() {
+        let towers = VoteTowers::new();
+        let validator = Pubkey::new_unique();
+        
+        let mut vote = tower_vote(validator, vec![90, 95, 99], 100);
+        assert!(towers.retain_new_slots(&mut vote, false));
+        
+        // After falling behind, one vote adds several slots at once
+        let mut vote = tower_vote(validator, vec![90, 99, 100, 101, 102], 106);
+        assert!(towers.retain_new_slots(&mut vote, false));
+        assert_eq!(vote.voted_on_slots, vec![100, 101, 102]);
+        assert_eq!(vote.latency_slots, vec![6, 5, 4]);
+        
+        // A late copy of an older vote adds nothing
+        let mut stale = tower_vote(validator, vec![90, 95, 99, 100], 101);
+        assert!(!towers.retain_new_slots(&mut stale, false));
+        
+        // Other validators are tracked separately
+        let mut other = tower_vote(Pubkey::new_unique(), vec![101, 102], 103);
+        assert!(towers.retain_new_slots(&mut other, false));
+        assert_eq!(other.voted_on_slots, vec![102]);
+        
+        // A validator that is monitored again starts from its latest vote
+        towers.remove(&validator);
+        let mut vote = tower_vote(validator, vec![100, 101, 102, 110], 111);
+        assert!(towers.retain_new_slots(&mut vote, false));
+        assert_eq!(vote.voted_on_slots, vec![110]);
+    }
+    
+    #[test]
+    fn test_refreshed_vote_recounted_with_finality() {
+        let towers = VoteTowers::new();
+        let validator = Pubkey::new_unique();
+        
+        let mut vote = tower_vote(validator, vec![90, 95, 99], 100);
+        assert!(towers.retain_new_slots(&mut vote, true));
+        let mut vote = tower_vote(validator, vec![95, 99, 100, 101], 102);
+        assert!(towers.retain_new_slots(&mut vote, true));
+        
+        // Slot 102 went to a fork; the same tower is re-signed and lands in 104
+        let mut refresh = tower_vote(validator, vec![95, 99, 100, 101], 104);
+        assert!(towers.retain_new_slots(&mut refresh, true));
+        assert_eq!(refresh.voted_on_slots, vec![100, 101]);
+        assert_eq!(refresh.latency_slots, vec![4, 3]);
+        
+        // Another copy of the same landing adds nothing
+        let mut copy = tower_vote(validator, vec![95, 99, 100, 101], 104);
+        assert!(!towers.retain_new_slots(&mut copy, true));
+        
+        // Without finality there's no telling which copy landed for good
+        let mut refresh = tower_vote(validator, vec![95, 99, 100, 101], 105);
+        assert!(!towers.retain_new_slots(&mut refresh, false));
+        
+        // The next vote diffs against the refreshed tower
+        let mut vote = tower_vote(validator, vec![99, 100, 101, 105], 106);
+        assert!(towers.retain_new_slots(&mut vote, true));
+        assert_eq!(vote.voted_on_slots, vec![105]);
+    }
+}
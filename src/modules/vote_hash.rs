@@ -0,0 +1,275 @@
+//! Vote Hash Module
+//!
+//! Every vote carries the bank hash of the slot it votes for, and validators
+//! replaying the same chain compute the same bank hash. A validator voting a
+//! different hash than the rest of the cluster for the same slot is running a
+//! bad build or has diverged state.
+//!
+//! The cluster's hash for a slot is the one most monitored validators voted
+//! for, settled once the tip is `SETTLE_AFTER_SLOTS` past the slot; votes
+//! arriving later are compared as they come. Block metadata can't serve as
+//! the reference: its blockhash is the PoH hash of the block's last entry,
+//! not the bank hash votes carry. Slots where fewer than
+//! `MIN_AGREEING_VALIDATORS` validators agree, or where two hashes tie, have
+//! no cluster hash.
+//!
+//! Validators refresh votes that don't land, re-signing the same slot and
+//! hash, so a validator counts once per hash and is flagged at most once per
+//! slot.
+
+use chrono::Utc;
+use parking_lot::Mutex;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::models::{HashMismatch, VoteLatency};
+
+/// Slots past a voted slot before its cluster hash is settled
+pub const SETTLE_AFTER_SLOTS: u64 = 32;
+
+/// Validators that must agree on a hash before it is taken as the cluster's
+pub const MIN_AGREEING_VALIDATORS: usize = 3;
+
+/// Settled slots remembered for late votes
+const SETTLED_HISTORY: u64 = 512;
+
+/// A vote waiting for its slot to settle
+struct HashVote {
+    validator_pubkey: Pubkey,
+    vote_pubkey: Pubkey,
+    signature: String,
+}
+
+/// A slot whose cluster hash has been settled
+struct SettledSlot {
+    /// Cluster hash and agreeing validators, `None` without consensus
+    cluster: Option<(String, usize)>,
+    /// Validators already flagged for the slot
+    flagged: HashSet<Pubkey>,
+}
+
+#[derive(Default)]
+struct TrackerState {
+    /// Voters per hash, keyed by voted slot, one vote per validator and hash
+    pending: BTreeMap<u64, HashMap<String, Vec<HashVote>>>,
+    /// Slots already settled
+    settled: BTreeMap<u64, SettledSlot>,
+    /// Highest landing slot seen
+    tip: u64,
+}
+
+/// Compares each vote's hash against the hash the cluster voted for the same slot
+#[derive(Default)]
+pub struct VoteHashTracker {
+    state: Mutex<TrackerState>,
+}
+
+impl VoteHashTracker {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Record a vote's hash, returning the mismatches of slots settled by now
+    ///
+    /// The hash belongs to the vote's highest voted slot; votes without a
+    /// hash are ignored.
+    pub fn record_vote(&self, vote: &VoteLatency) -> Vec<HashMismatch> {
+        let Some(vote_hash) = &vote.vote_hash else {
+            return Vec::new();
+        };
+        let slot = vote.slot;
+        let hash_vote = HashVote {
+            validator_pubkey: vote.validator_pubkey,
+            vote_pubkey: vote.vote_pubkey,
+            signature: vote.signature.clone(),
+        };
+        
+        let mut state = self.state.lock();
+        let mut mismatches = Vec::new();
+        let too_late = state.settled.last_key_value().is_some_and(|(&newest, _)| slot < newest)
+            && !state.pending.contains_key(&slot);
+        
+        match state.settled.get_mut(&slot) {
+            // Late vote for a settled slot
+            Some(SettledSlot { cluster: Some((cluster_hash, agreeing)), flagged }) => {
+                if cluster_hash != vote_hash && flagged.insert(hash_vote.validator_pubkey) {
+                    mismatches.push(mismatch(hash_vote, slot, vote_hash, cluster_hash, *agreeing));
+                }
+            }
+            Some(SettledSlot { cluster: None, .. }) => {}
+            None if too_late => {
+                // Older than anything still pending; too late to take part
+            }
+            None => {
+                let voters = state.pending
+                    .entry(slot)
+                    .or_default()
+                    .entry(vote_hash.clone())
+                    .or_default();
+                // A refreshed vote re-signs the same slot and hash
+                if !voters.iter().any(|v| v.validator_pubkey == hash_vote.validator_pubkey) {
+                    voters.push(hash_vote);
+                }
+            }
+        }
+        
+        state.tip = state.tip.max(vote.landed_slot);
+        mismatches.extend(state.settle());
+        mismatches
+    }
+}
+
+impl TrackerState {
+    /// Settle slots that left the voting window, returning their mismatches
+    fn settle(&mut self) -> Vec<HashMismatch> {
+        let Some(cutoff) = self.tip.checked_sub(SETTLE_AFTER_SLOTS) else {
+            return Vec::new();
+        };
+        
+        let waiting = self.pending.split_off(&(cutoff + 1));
+        let due = std::mem::replace(&mut self.pending, waiting);
+        
+        let mut mismatches = Vec::new();
+        for (slot, hashes) in due {
+            let cluster = cluster_hash(&hashes);
+            let mut flagged = HashSet::new();
+            if let Some((cluster_hash, agreeing)) = &cluster {
+                for (vote_hash, votes) in hashes {
+                    if &vote_hash == cluster_hash {
+                        continue;
+                    }
+                    for vote in votes {
+                        // A validator that voted several wrong hashes is flagged once
+                        if flagged.insert(vote.validator_pubkey) {
+                            mismatches.push(mismatch(vote, slot, &vote_hash, cluster_hash, *agreeing));
+                        }
+                    }
+                }
+            }
+            self.settled.insert(slot, SettledSlot { cluster, flagged });
+        }
+        
+        let horizon = self.tip.saturating_sub(SETTLED_HISTORY);
+        self.settled = self.settled.split_off(&horizon);
+        mismatches
+    }
+}
+
+/// The hash most validators agree on, with their count, if it is a clear winner
+///
+/// Voters are already deduplicated per hash, so each vote is one validator.
+fn cluster_hash(hashes: &HashMap<String, Vec<HashVote>>) -> Option<(String, usize)> {
+    let mut counts: Vec<(&String, usize)> = hashes.iter().map(|(hash, voters)| (hash, voters.len())).collect();
+    counts.sort_unstable_by_key(|&(_, voters)| std::cmp::Reverse(voters));
+    
+    let (hash, voters) = counts.first().copied()?;
+    let tied = counts.get(1).is_some_and(|&(_, runner_up)| runner_up == voters);
+    (voters >= MIN_AGREEING_VALIDATORS && !tied).then(|| (hash.clone(), voters))
+}
+
+fn mismatch(vote: HashVote, slot: u64, vote_hash: &str, cluster_hash: &str, agreeing: usize) -> HashMismatch {
+    HashMismatch {
+        validator_pubkey: vote.validator_pubkey,
+        vote_pubkey: vote.vote_pubkey,
+        slot,
+        vote_hash: vote_hash.to_string(),
+        cluster_hash: cluster_hash.to_string(),
+        agreeing_votes: agreeing as u64,
+        signature: vote.signature,
+        detected_at: Utc::now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn vote(validator: Pubkey, slot: u64, landed_slot: u64, hash: &str) -> VoteLatency {
+        let mut vote = VoteLatency::new_single_vote(
+            validator,
+            Pubkey::new_unique(),
+            slot,
+            Utc::now(),
+            Utc::now(),
+            format!("sig-{}-{}", validator, slot),
+            landed_slot,
+        );
+        vote.vote_hash = Some(hash.to_string());
+        vote
+    }
+    
+    #[test]
+    fn test_minority_hash_flagged_once_slot_settles() {
+        let tracker = VoteHashTracker::new();
+        let diverged = Pubkey::new_unique();
+        
+        for _ in 0..3 {
+            assert!(tracker.record_vote(&vote(Pubkey::new_unique(), 100, 101, "good")).is_empty());
+        }
+        assert!(tracker.record_vote(&vote(diverged, 100, 102, "bad")).is_empty());
+        
+        // Slot 100 settles once a vote lands past the window
+        let mismatches = tracker.record_vote(&vote(Pubkey::new_unique(), 132, 133, "other"));
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].validator_pubkey, diverged);
+        assert_eq!(mismatches[0].slot, 100);
+        assert_eq!(mismatches[0].vote_hash, "bad");
+        assert_eq!(mismatches[0].cluster_hash, "good");
+        assert_eq!(mismatches[0].agreeing_votes, 3);
+        
+        // Late votes are compared against the settled hash straight away
+        let late = tracker.record_vote(&vote(Pubkey::new_unique(), 100, 140, "bad"));
+        assert_eq!(late.len(), 1);
+        assert!(tracker.record_vote(&vote(Pubkey::new_unique(), 100, 141, "good")).is_empty());
+    }
+    
+    #[test]
+    fn test_no_cluster_hash_without_clear_majority() {
+        let tracker = VoteHashTracker::new();
+        
+        // Too few votes agree
+        tracker.record_vote(&vote(Pubkey::new_unique(), 100, 101, "a"));
+        tracker.record_vote(&vote(Pubkey::new_unique(), 100, 101, "a"));
+        tracker.record_vote(&vote(Pubkey::new_unique(), 100, 101, "b"));
+        
+        // A tie
+        for hash in ["c", "c", "c", "d", "d", "d"] {
+            tracker.record_vote(&vote(Pubkey::new_unique(), 101, 102, hash));
+        }
+        
+        assert!(tracker.record_vote(&vote(Pubkey::new_unique(), 140, 141, "e")).is_empty());
+        assert!(tracker.record_vote(&vote(Pubkey::new_unique(), 101, 142, "d")).is_empty());
+    }
+    
+    #[test]
+    fn test_refreshed_votes_count_once() {
+        let tracker = VoteHashTracker::new();
+        let repeater = Pubkey::new_unique();
+        let diverged = Pubkey::new_unique();
+        
+        // One validator refreshing its vote doesn't make a cluster hash
+        for landed_slot in 101..104 {
+            tracker.record_vote(&vote(repeater, 100, landed_slot, "a"));
+        }
+        tracker.record_vote(&vote(Pubkey::new_unique(), 100, 104, "b"));
+        
+        // Nor does it outvote two other validators
+        tracker.record_vote(&vote(repeater, 101, 102, "c"));
+        tracker.record_vote(&vote(repeater, 101, 103, "c"));
+        for _ in 0..3 {
+            tracker.record_vote(&vote(Pubkey::new_unique(), 101, 102, "d"));
+        }
+        tracker.record_vote(&vote(diverged, 101, 103, "e"));
+        tracker.record_vote(&vote(diverged, 101, 104, "e"));
+        
+        let mismatches = tracker.record_vote(&vote(Pubkey::new_unique(), 140, 141, "f"));
+        assert_eq!(mismatches.len(), 2);
+        assert!(mismatches.iter().all(|m| m.slot == 101 && m.cluster_hash == "d" && m.agreeing_votes == 3));
+        assert_eq!(mismatches.iter().filter(|m| m.validator_pubkey == diverged).count(), 1);
+        
+        // A validator already flagged isn't flagged again by a late vote
+        assert!(tracker.record_vote(&vote(diverged, 101, 142, "e")).is_empty());
+        assert_eq!(tracker.record_vote(&vote(Pubkey::new_unique(), 101, 143, "e")).len(), 1);
+    }
+}
@@ -0,0 +1,880 @@
+//! Vote Queue Module
+//!
+//! Bounded queue between the gRPC streams and the processing pipeline. When
+//! the pipeline falls behind, the configured overflow policy decides what
+//! happens to new votes: block the stream, drop the newest or oldest vote, or
+//! spill votes to a local file that is drained once the queue has room again.
+//! Spilled votes survive restarts and are delivered in arrival order: the
+//! file's read offset only moves past a batch of votes read back once the
+//! receiver consumed all of them, and votes still held in memory are written
+//! back to the file on shutdown. A crash can deliver the last batch again.
+//!
+//! The size cap applies to the votes not yet consumed; the consumed front of
+//! the file is cut off once it grows past [`SPILL_COMPACT_BYTES`].
+
+use parking_lot::Mutex;
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::{Notify, OwnedMutexGuard};
+use tracing::{info, warn};
+
+use crate::config::OverflowPolicy;
+use crate::error::{Error, Result};
+
+/// File name of the spill queue inside the spill directory
+const SPILL_FILE_NAME: &str = "votes.jsonl";
+
+/// File holding the offset of the first unconsumed entry, so consumed
+/// entries aren't replayed after a restart
+const SPILL_OFFSET_FILE_NAME: &str = "votes.offset";
+
+/// Consumed bytes at the front of the spill file before it is compacted
+const SPILL_COMPACT_BYTES: u64 = 1024 * 1024;
+
+/// Result of pushing a vote onto the queue
+#[derive(Debug, PartialEq, Eq)]
+pub enum PushOutcome<T> {
+    /// Queued in memory
+    Queued,
+    /// Written to the spill file
+    Spilled,
+    /// The queue was full and this vote (newest or oldest) was dropped
+    Dropped(T, DropReason),
+}
+
+/// Why a vote was dropped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropReason {
+    /// Queue full, the incoming vote was dropped
+    Newest,
+    /// Queue full, the oldest queued vote was evicted
+    Oldest,
+    /// Spill file reached its size cap or could not be written
+    SpillFull,
+    /// Receiver is gone
+    Closed,
+}
+
+impl DropReason {
+    /// Label used in metrics
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DropReason::Newest => "drop_newest",
+            DropReason::Oldest => "drop_oldest",
+            DropReason::SpillFull => "spill_full",
+            DropReason::Closed => "closed",
+        }
+    }
+}
+
+/// Append-only spill file with a read cursor
+///
+/// Only used from blocking threads; the queue keeps the entry counts.
+struct SpillFile {
+    path: PathBuf,
+    offset_path: PathBuf,
+    file: File,
+    /// Bytes cut off the front of the file since it was opened; offsets handed
+    /// out count them, so votes read back before a compaction keep theirs
+    base: u64,
+    /// Byte offset of the first entry not yet consumed
+    committed: u64,
+    /// Byte offset of the first entry not yet read back
+    read_offset: u64,
+    /// Bytes written, read or not
+    len: u64,
+    max_bytes: u64,
+}
+
+impl SpillFile {
+    /// Open the spill file, returning it with the number of entries left by a
+    /// previous run
+    fn open(dir: &Path, max_bytes: u64) -> Result<(Self, usize)> {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| Error::config(format!("Failed to create spill directory {}: {}", dir.display(), e)))?;
+        let path = dir.join(SPILL_FILE_NAME);
+        let file = Self::open_file(&path)
+            .map_err(|e| Error::config(format!("Failed to open spill file {}: {}", path.display(), e)))?;
+        
+        let offset_path = dir.join(SPILL_OFFSET_FILE_NAME);
+        let len = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+        let committed = std::fs::read_to_string(&offset_path)
+            .ok()
+            .and_then(|offset| offset.trim().parse::<u64>().ok())
+            .filter(|offset| *offset <= len)
+            .unwrap_or(0);
+        
+        let mut reader = BufReader::new(&file);
+        reader.seek(SeekFrom::Start(committed))
+            .map_err(|e| Error::config(format!("Failed to read spill file {}: {}", path.display(), e)))?;
+        let entries = reader.lines().map_while(|line| line.ok()).count();
+        if entries > 0 {
+            info!("Recovered {} spilled votes from {}", entries, path.display());
+        }
+        
+        let spill = Self {
+            path,
+            offset_path,
+            file,
+            base: 0,
+            committed,
+            read_offset: committed,
+            len,
+            max_bytes,
+        };
+        Ok((spill, entries))
+    }
+    
+    fn open_file(path: &Path) -> std::io::Result<File> {
+        OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)
+    }
+    
+    /// Append an entry; `false` if the unconsumed entries would exceed the size cap
+    fn append(&mut self, line: &[u8]) -> bool {
+        if self.len - self.committed + line.len() as u64 > self.max_bytes {
+            return false;
+        }
+        if let Err(e) = self.file.write_all(line) {
+            warn!("Failed to write spill file {}: {}", self.path.display(), e);
+            return false;
+        }
+        
+        self.len += line.len() as u64;
+        true
+    }
+    
+    /// Read up to `max` entries back in order, each with the offset just past it
+    ///
+    /// The returned flag is set when the file ran out before `max` entries.
+    fn read(&mut self, max: usize) -> (Vec<(String, u64)>, bool) {
+        let mut lines = Vec::new();
+        let mut reader = BufReader::new(&self.file);
+        if let Err(e) = reader.seek(SeekFrom::Start(self.read_offset)) {
+            warn!("Failed to seek spill file {}: {}", self.path.display(), e);
+            return (lines, true);
+        }
+        
+        while lines.len() < max {
+            let mut line = String::new();
+            match reader.read_line(&mut line) {
+                Ok(0) => return (lines, true),
+                Ok(read) => {
+                    self.read_offset += read as u64;
+                    lines.push((line, self.base + self.read_offset));
+                }
+                Err(e) => {
+                    warn!("Failed to read spill file {}: {}", self.path.display(), e);
+                    return (lines, true);
+                }
+            }
+        }
+        
+        (lines, false)
+    }
+    
+    /// Record that entries up to `offset` were consumed
+    ///
+    /// `drained` starts the file over once nothing is left in it; otherwise
+    /// the consumed front is cut off once it passes the compaction threshold.
+    fn commit(&mut self, offset: u64, drained: bool) {
+        if drained {
+            if let Err(e) = self.file.set_len(0) {
+                warn!("Failed to truncate spill file {}: {}", self.path.display(), e);
+                return;
+            }
+            self.base += self.len;
+            self.read_offset = 0;
+            self.len = 0;
+            self.committed = 0;
+        } else {
+            self.committed = offset.saturating_sub(self.base).max(self.committed);
+            if self.committed >= SPILL_COMPACT_BYTES.min(self.max_bytes) {
+                if let Err(e) = self.compact() {
+                    warn!("Failed to compact spill file {}: {}", self.path.display(), e);
+                }
+            }
+        }
+        
+        if let Err(e) = std::fs::write(&self.offset_path, self.committed.to_string()) {
+            warn!("Failed to record spill offset {}: {}", self.offset_path.display(), e);
+        }
+    }
+    
+    /// Cut the consumed entries off the front of the file
+    fn compact(&mut self) -> std::io::Result<()> {
+        let mut rest = Vec::new();
+        let mut reader = BufReader::new(&self.file);
+        reader.seek(SeekFrom::Start(self.committed))?;
+        reader.read_to_end(&mut rest)?;
+        
+        let temp_path = self.path.with_extension("jsonl.tmp");
+        let mut temp = File::create(&temp_path)?;
+        temp.write_all(&rest)?;
+        temp.sync_all()?;
+        // A crash in between replays consumed entries rather than skipping live ones
+        std::fs::write(&self.offset_path, "0")?;
+        std::fs::rename(&temp_path, &self.path)?;
+        
+        self.file = Self::open_file(&self.path)?;
+        self.base += self.committed;
+        self.read_offset -= self.committed;
+        self.len = rest.len() as u64;
+        self.committed = 0;
+        Ok(())
+    }
+    
+    /// Write entries held in memory back into the file, ahead of the entries
+    /// not yet read, returning the number of entries left in it
+    ///
+    /// Entries read back but not consumed are still in the file and stay in
+    /// front of `held`.
+    fn rewrite(&mut self, held: &[u8]) -> std::io::Result<usize> {
+        let mut rest = Vec::new();
+        let mut reader = BufReader::new(&self.file);
+        reader.seek(SeekFrom::Start(self.committed))?;
+        reader.read_to_end(&mut rest)?;
+        
+        let split = (self.read_offset - self.committed) as usize;
+        let mut content = Vec::with_capacity(rest.len() + held.len());
+        content.extend_from_slice(&rest[..split]);
+        content.extend_from_slice(held);
+        content.extend_from_slice(&rest[split..]);
+        
+        let temp_path = self.path.with_extension("jsonl.tmp");
+        let mut temp = File::create(&temp_path)?;
+        temp.write_all(&content)?;
+        temp.sync_all()?;
+        std::fs::rename(&temp_path, &self.path)?;
+        
+        self.file = Self::open_file(&self.path)?;
+        self.committed = 0;
+        self.read_offset = 0;
+        self.len = content.len() as u64;
+        std::fs::write(&self.offset_path, "0")?;
+        
+        Ok(content.iter().filter(|&&byte| byte == b'\n').count())
+    }
+}
+
+/// A vote held in memory
+struct Queued<T> {
+    item: T,
+    /// Offset just past the vote in the spill file, if it was read back from it
+    spill_offset: Option<u64>,
+}
+
+/// What the receiver does next
+enum Next<T> {
+    /// Hand out a vote
+    Vote(Queued<T>),
+    /// Read up to this many spilled votes back
+    Refill(usize),
+    /// Wait for a vote to be pushed
+    Wait,
+}
+
+struct QueueState<T> {
+    items: VecDeque<Queued<T>>,
+    /// Entries in the spill file not yet read back
+    spilled: usize,
+    /// Votes on their way into the spill file
+    spilling: usize,
+    /// Votes read back from the spill file and not yet consumed
+    in_flight: usize,
+    /// Offset past the last consumed vote read back, until its batch is committed
+    consumed: Option<u64>,
+}
+
+struct Shared<T> {
+    state: Mutex<QueueState<T>>,
+    /// Spill file, locked across its blocking I/O
+    spill: Option<Arc<tokio::sync::Mutex<SpillFile>>>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    /// Signalled when a vote is queued or the last sender goes away
+    items_ready: Notify,
+    /// Signalled when a vote is taken off the queue
+    space_ready: Notify,
+    senders: AtomicUsize,
+    receiver_alive: AtomicBool,
+    /// Set on shutdown; the receiver stops once memory is drained
+    closed: AtomicBool,
+}
+
+/// Sending half of the vote queue
+pub struct VoteQueueSender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// Receiving half of the vote queue
+pub struct VoteQueueReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// Reads the queue depth without counting as a sender
+pub struct VoteQueueMonitor<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// Create a vote queue holding up to `capacity` votes in memory
+///
+/// `spill_dir` is only used by [`OverflowPolicy::Spill`].
+pub fn vote_queue<T>(
+    capacity: usize,
+    policy: OverflowPolicy,
+    spill_dir: &Path,
+    spill_max_bytes: u64,
+) -> Result<(VoteQueueSender<T>, VoteQueueReceiver<T>)> {
+    let (spill, spilled) = match policy {
+        OverflowPolicy::Spill => {
+            let (spill, entries) = SpillFile::open(spill_dir, spill_max_bytes)?;
+            (Some(Arc::new(tokio::sync::Mutex::new(spill))), entries)
+        }
+        _ => (None, 0),
+    };
+    
+    let shared = Arc::new(Shared {
+        state: Mutex::new(QueueState {
+            items: VecDeque::with_capacity(capacity),
+            spilled,
+            spilling: 0,
+            in_flight: 0,
+            consumed: None,
+        }),
+        spill,
+        capacity: capacity.max(1),
+        policy,
+        items_ready: Notify::new(),
+        space_ready: Notify::new(),
+        senders: AtomicUsize::new(1),
+        receiver_alive: AtomicBool::new(true),
+        closed: AtomicBool::new(false),
+    });
+    
+    Ok((
+        VoteQueueSender { shared: Arc::clone(&shared) },
+        VoteQueueReceiver { shared },
+    ))
+}
+
+impl<T: Serialize + DeserializeOwned> VoteQueueSender<T> {
+    /// Push a vote, applying the overflow policy when the queue is full
+    ///
+    /// Only [`OverflowPolicy::Block`] waits; the other policies return
+    /// immediately so a slow pipeline never stalls the gRPC streams.
+    pub async fn push(&self, item: T) -> PushOutcome<T> {
+        let shared = &self.shared;
+        let mut item = Some(item);
+        
+        loop {
+            let space_ready = shared.space_ready.notified();
+            tokio::pin!(space_ready);
+            space_ready.as_mut().enable();
+            
+            let to_spill = {
+                let mut state = shared.state.lock();
+                let vote = item.take().expect("vote pushed once");
+                
+                if !shared.receiver_alive.load(Ordering::Acquire) || shared.closed.load(Ordering::Acquire) {
+                    return PushOutcome::Dropped(vote, DropReason::Closed);
+                }
+                
+                // Keep arrival order: once votes are spilled, new ones follow them
+                let spilled = state.spilled + state.spilling > 0;
+                if state.items.len() < shared.capacity && !spilled {
+                    state.items.push_back(Queued { item: vote, spill_offset: None });
+                    drop(state);
+                    shared.items_ready.notify_one();
+                    return PushOutcome::Queued;
+                }
+                
+                match shared.policy {
+                    OverflowPolicy::Block => {
+                        item = Some(vote);
+                        None
+                    }
+                    OverflowPolicy::DropNewest => {
+                        return PushOutcome::Dropped(vote, DropReason::Newest);
+                    }
+                    OverflowPolicy::DropOldest => {
+                        let oldest = state.items.pop_front();
+                        state.items.push_back(Queued { item: vote, spill_offset: None });
+                        drop(state);
+                        shared.items_ready.notify_one();
+                        return match oldest {
+                            Some(oldest) => PushOutcome::Dropped(oldest.item, DropReason::Oldest),
+                            None => PushOutcome::Queued,
+                        };
+                    }
+                    OverflowPolicy::Spill => {
+                        state.spilling += 1;
+                        Some(vote)
+                    }
+                }
+            };
+            
+            if let Some(vote) = to_spill {
+                if !shared.spill_vote(&vote).await {
+                    return PushOutcome::Dropped(vote, DropReason::SpillFull);
+                }
+                shared.items_ready.notify_one();
+                return PushOutcome::Spilled;
+            }
+            
+            space_ready.await;
+        }
+    }
+    
+    /// Write votes still held in memory to the spill file
+    ///
+    /// Called on shutdown so they are delivered by the next run; a no-op
+    /// unless the queue spills.
+    pub async fn persist(&self) {
+        self.shared.persist().await;
+    }
+    
+    /// Close the queue on shutdown
+    ///
+    /// New votes are dropped and the receiver ends once the votes held in
+    /// memory are handed out; spilled votes are left for the next run.
+    pub fn close(&self) {
+        self.shared.closed.store(true, Ordering::Release);
+        self.shared.items_ready.notify_waiters();
+        self.shared.items_ready.notify_one();
+    }
+    
+    /// Votes held in memory and in the spill file
+    pub fn depth(&self) -> QueueDepth {
+        self.shared.depth()
+    }
+    
+    /// Handle reporting the depth that doesn't keep the queue open
+    pub fn monitor(&self) -> VoteQueueMonitor<T> {
+        VoteQueueMonitor {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T> VoteQueueMonitor<T> {
+    /// Votes held in memory and in the spill file
+    pub fn depth(&self) -> QueueDepth {
+        self.shared.depth()
+    }
+}
+
+impl<T> Clone for VoteQueueSender<T> {
+    fn clone(&self) -> Self {
+        self.shared.senders.fetch_add(1, Ordering::AcqRel);
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T> Drop for VoteQueueSender<T> {
+    fn drop(&mut self) {
+        if self.shared.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.shared.items_ready.notify_waiters();
+            self.shared.items_ready.notify_one();
+        }
+    }
+}
+
+impl<T: DeserializeOwned> VoteQueueReceiver<T> {
+    /// Receive the next vote, or `None` once every sender is gone or the
+    /// queue was closed, and the queue is drained
+    pub async fn recv(&mut self) -> Option<T> {
+        let shared = &self.shared;
+        
+        loop {
+            let items_ready = shared.items_ready.notified();
+            tokio::pin!(items_ready);
+            items_ready.as_mut().enable();
+            
+            let next = {
+                let mut state = shared.state.lock();
+                match state.items.pop_front() {
+                    Some(mut queued) => {
+                        // Commit once the whole batch read back is consumed
+                        if let Some(offset) = queued.spill_offset {
+                            state.in_flight -= 1;
+                            if state.in_flight == 0 {
+                                state.consumed = None;
+                            } else {
+                                state.consumed = Some(offset);
+                                queued.spill_offset = None;
+                            }
+                        }
+                        Next::Vote(queued)
+                    }
+                    // Refill from the spill file once memory is drained
+                    None if shared.closed.load(Ordering::Acquire) => return None,
+                    None if state.spilled > 0 => Next::Refill(state.spilled.min(shared.capacity)),
+                    None if shared.senders.load(Ordering::Acquire) == 0 => return None,
+                    None => Next::Wait,
+                }
+            };
+            
+            match next {
+                Next::Vote(queued) => {
+                    shared.space_ready.notify_one();
+                    if let Some(offset) = queued.spill_offset {
+                        shared.commit_spill(offset).await;
+                    }
+                    return Some(queued.item);
+                }
+                Next::Refill(max) => shared.refill(max).await,
+                Next::Wait => items_ready.await,
+            }
+        }
+    }
+    
+    /// Votes held in memory and in the spill file
+    pub fn depth(&self) -> QueueDepth {
+        self.shared.depth()
+    }
+}
+
+impl<T> Drop for VoteQueueReceiver<T> {
+    fn drop(&mut self) {
+        self.shared.receiver_alive.store(false, Ordering::Release);
+        self.shared.space_ready.notify_waiters();
+    }
+}
+
+/// Run spill file I/O on a blocking thread, handing the lock back with the result
+async fn with_spill<R: Send + 'static>(
+    spill: &Arc<tokio::sync::Mutex<SpillFile>>,
+    io: impl FnOnce(&mut SpillFile) -> R + Send + 'static,
+) -> Option<(OwnedMutexGuard<SpillFile>, R)> {
+    let mut guard = Arc::clone(spill).lock_owned().await;
+    match tokio::task::spawn_blocking(move || {
+        let result = io(&mut guard);
+        (guard, result)
+    })
+    .await
+    {
+        Ok(done) => Some(done),
+        Err(e) => {
+            warn!("Spill file I/O task failed: {}", e);
+            None
+        }
+    }
+}
+
+impl<T> Shared<T> {
+    fn depth(&self) -> QueueDepth {
+        let state = self.state.lock();
+        QueueDepth {
+            memory: state.items.len(),
+            spilled: state.spilled + state.spilling,
+        }
+    }
+    
+    /// Consume spill file entries up to `offset`
+    async fn commit_spill(&self, offset: u64) {
+        let Some(spill) = &self.spill else {
+            return;
+        };
+        
+        // Counts only change with the spill file locked, so this holds for the commit
+        let mut guard = Arc::clone(spill).lock_owned().await;
+        let drained = {
+            let state = self.state.lock();
+            state.spilled == 0 && state.in_flight == 0
+        };
+        let _ = tokio::task::spawn_blocking(move || guard.commit(offset, drained)).await;
+    }
+}
+
+impl<T: Serialize> Shared<T> {
+    /// Append a vote the caller counted in `spilling`, returning whether it was written
+    async fn spill_vote(&self, vote: &T) -> bool {
+        let line = match serde_json::to_vec(vote) {
+            Ok(mut line) => {
+                line.push(b'\n');
+                Some(line)
+            }
+            Err(e) => {
+                warn!("Failed to serialize vote for spilling: {}", e);
+                None
+            }
+        };
+        
+        let written = match (&self.spill, line) {
+            (Some(spill), Some(line)) => with_spill(spill, move |spill| spill.append(&line)).await,
+            _ => None,
+        };
+        
+        let mut state = self.state.lock();
+        state.spilling -= 1;
+        match written {
+            // Counted before the lock is released, so a refill never reads ahead of the count
+            Some((_guard, true)) => {
+                state.spilled += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+    
+    /// Move votes held in memory into the spill file
+    async fn persist(&self) {
+        let Some(spill) = &self.spill else {
+            return;
+        };
+        let mut guard = Arc::clone(spill).lock_owned().await;
+        
+        // Taken out so the receiver can't hand them out while they're written
+        let (items, in_flight, consumed, held) = {
+            let mut state = self.state.lock();
+            let items = std::mem::take(&mut state.items);
+            let in_flight = std::mem::take(&mut state.in_flight);
+            let consumed = state.consumed.take();
+            
+            let mut held = Vec::new();
+            for queued in items.iter().filter(|queued| queued.spill_offset.is_none()) {
+                match serde_json::to_vec(&queued.item) {
+                    Ok(line) => {
+                        held.extend_from_slice(&line);
+                        held.push(b'\n');
+                    }
+                    Err(e) => warn!("Failed to serialize vote for spilling: {}", e),
+                }
+            }
+            (items, in_flight, consumed, held)
+        };
+        
+        let rewritten = tokio::task::spawn_blocking(move || {
+            // Votes consumed from an unfinished batch aren't written back
+            if let Some(offset) = consumed {
+                guard.commit(offset, false);
+            }
+            let entries = guard.rewrite(&held);
+            (guard, entries)
+        })
+        .await;
+        
+        let mut state = self.state.lock();
+        match rewritten {
+            Ok((_guard, Ok(entries))) => {
+                info!("Persisted {} queued votes to the spill file", items.len());
+                state.spilled = entries;
+            }
+            Ok((_guard, Err(e))) => {
+                warn!("Failed to persist queued votes to the spill file: {}", e);
+                state.items = items;
+                state.in_flight = in_flight;
+            }
+            Err(e) => {
+                warn!("Failed to persist queued votes to the spill file: {}", e);
+                state.items = items;
+                state.in_flight = in_flight;
+            }
+        }
+    }
+}
+
+impl<T: DeserializeOwned> Shared<T> {
+    /// Read up to `max` spilled votes back into memory
+    async fn refill(&self, max: usize) {
+        let Some(spill) = &self.spill else {
+            return;
+        };
+        let Some((_guard, (lines, exhausted))) = with_spill(spill, move |spill| spill.read(max)).await else {
+            // Nothing more can be read; stop waiting on it
+            self.state.lock().spilled = 0;
+            return;
+        };
+        
+        let mut state = self.state.lock();
+        state.spilled = if exhausted {
+            // Counted entries that aren't there; nothing more to read
+            0
+        } else {
+            state.spilled.saturating_sub(lines.len())
+        };
+        for (line, offset) in lines {
+            match serde_json::from_str(line.trim_end()) {
+                Ok(item) => {
+                    state.items.push_back(Queued { item, spill_offset: Some(offset) });
+                    state.in_flight += 1;
+                }
+                Err(e) => warn!("Skipping unreadable spilled vote: {}", e),
+            }
+        }
+    }
+}
+
+/// Current queue depth
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueueDepth {
+    /// Votes queued in memory
+    pub memory: usize,
+    /// Votes waiting in the spill file
+    pub spilled: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    
+    fn queue(capacity: usize, policy: OverflowPolicy) -> (VoteQueueSender<u64>, VoteQueueReceiver<u64>) {
+        vote_queue(capacity, policy, Path::new("/nonexistent"), 0).unwrap()
+    }
+    
+    #[tokio::test]
+    async fn test_drop_newest_and_oldest() {
+        let (tx, mut rx) = queue(2, OverflowPolicy::DropNewest);
+        assert_eq!(tx.push(1).await, PushOutcome::Queued);
+        assert_eq!(tx.push(2).await, PushOutcome::Queued);
+        assert_eq!(tx.push(3).await, PushOutcome::Dropped(3, DropReason::Newest));
+        assert_eq!(rx.recv().await, Some(1));
+        
+        let (tx, mut rx) = queue(2, OverflowPolicy::DropOldest);
+        tx.push(1).await;
+        tx.push(2).await;
+        assert_eq!(tx.push(3).await, PushOutcome::Dropped(1, DropReason::Oldest));
+        assert_eq!(rx.recv().await, Some(2));
+        assert_eq!(rx.recv().await, Some(3));
+    }
+    
+    #[tokio::test]
+    async fn test_block_waits_for_space() {
+        let (tx, mut rx) = queue(1, OverflowPolicy::Block);
+        let monitor = tx.monitor();
+        tx.push(1).await;
+        
+        let blocked = tokio::spawn(async move { tx.push(2).await });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!blocked.is_finished());
+        
+        assert_eq!(rx.recv().await, Some(1));
+        assert_eq!(blocked.await.unwrap(), PushOutcome::Queued);
+        assert_eq!(rx.recv().await, Some(2));
+        
+        // Sender dropped by the finished task; the monitor doesn't keep the queue open
+        assert_eq!(rx.recv().await, None);
+        assert_eq!(monitor.depth(), QueueDepth::default());
+    }
+    
+    #[tokio::test]
+    async fn test_spill_keeps_order_and_survives_restart() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        {
+            let (tx, mut rx) = vote_queue::<u64>(2, OverflowPolicy::Spill, temp_dir.path(), 1024).unwrap();
+            for vote in 1..=5 {
+                tx.push(vote).await;
+            }
+            assert_eq!(tx.depth(), QueueDepth { memory: 2, spilled: 3 });
+            
+            assert_eq!(rx.recv().await, Some(1));
+            assert_eq!(rx.recv().await, Some(2));
+            // Memory drained, the next read pulls spilled votes back in
+            assert_eq!(rx.recv().await, Some(3));
+            // Still spilling until the file is drained
+            assert_eq!(tx.push(6).await, PushOutcome::Spilled);
+        }
+        
+        // Spilled votes are picked up by the next run; 3 and 4 were read back
+        // as one batch that was never finished, so both are delivered again
+        let (tx, mut rx) = vote_queue::<u64>(2, OverflowPolicy::Spill, temp_dir.path(), 1024).unwrap();
+        assert_eq!(tx.depth().spilled, 4);
+        assert_eq!(rx.recv().await, Some(3));
+        assert_eq!(rx.recv().await, Some(4));
+        assert_eq!(rx.recv().await, Some(5));
+        assert_eq!(rx.recv().await, Some(6));
+        assert_eq!(tx.depth(), QueueDepth::default());
+        
+        // Drained: the file starts over
+        assert_eq!(std::fs::metadata(temp_dir.path().join(SPILL_FILE_NAME)).unwrap().len(), 0);
+    }
+    
+    #[tokio::test]
+    async fn test_persist_keeps_memory_ahead_of_spill() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        {
+            let (tx, mut rx) = vote_queue::<u64>(2, OverflowPolicy::Spill, temp_dir.path(), 1024).unwrap();
+            for vote in 1..=5 {
+                tx.push(vote).await;
+            }
+            assert_eq!(rx.recv().await, Some(1));
+            
+            // 2 is only held in memory
+            tx.persist().await;
+            assert_eq!(tx.depth(), QueueDepth { memory: 0, spilled: 4 });
+            
+            // 3 is read back along with 2 but not consumed when the process stops
+            assert_eq!(rx.recv().await, Some(2));
+            tx.push(6).await;
+            tx.persist().await;
+        }
+        
+        let (_tx, mut rx) = vote_queue::<u64>(2, OverflowPolicy::Spill, temp_dir.path(), 1024).unwrap();
+        for vote in 3..=6 {
+            assert_eq!(rx.recv().await, Some(vote));
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_close_ends_after_memory_drained() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (tx, mut rx) = vote_queue::<u64>(1, OverflowPolicy::Spill, temp_dir.path(), 1024).unwrap();
+        tx.push(1).await;
+        tx.push(2).await;
+        
+        tx.close();
+        assert_eq!(tx.push(3).await, PushOutcome::Dropped(3, DropReason::Closed));
+        
+        // Memory is drained, the spilled vote is left for the next run
+        assert_eq!(rx.recv().await, Some(1));
+        assert_eq!(rx.recv().await, None);
+        drop((tx, rx));
+        
+        let (tx, _rx) = vote_queue::<u64>(1, OverflowPolicy::Spill, temp_dir.path(), 1024).unwrap();
+        assert_eq!(tx.depth().spilled, 1);
+    }
+    
+    #[tokio::test]
+    async fn test_spill_size_cap() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (tx, _rx) = vote_queue::<u64>(1, OverflowPolicy::Spill, temp_dir.path(), 4).unwrap();
+        tx.push(1).await;
+        assert_eq!(tx.push(22).await, PushOutcome::Spilled);
+        assert_eq!(tx.push(33).await, PushOutcome::Dropped(33, DropReason::SpillFull));
+    }
+    
+    #[tokio::test]
+    async fn test_spill_cap_counts_unconsumed_votes() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let (tx, mut rx) = vote_queue::<u64>(1, OverflowPolicy::Spill, temp_dir.path(), 12).unwrap();
+        tx.push(0).await;
+        for vote in 1..=3 {
+            assert_eq!(tx.push(vote).await, PushOutcome::Spilled);
+        }
+        
+        // The backlog never drains, but consumed votes make room for new ones
+        for vote in 4..40 {
+            assert_eq!(rx.recv().await, Some(vote - 4));
+            assert_eq!(tx.push(vote).await, PushOutcome::Spilled);
+        }
+        assert_eq!(tx.depth().spilled, 4);
+        
+        // And the consumed front of the file is cut off
+        assert!(std::fs::metadata(temp_dir.path().join(SPILL_FILE_NAME)).unwrap().len() < 24);
+        for vote in 36..40 {
+            assert_eq!(rx.recv().await, Some(vote));
+        }
+    }
+}
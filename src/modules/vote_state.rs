@@ -0,0 +1,144 @@
+//! Vote State Module
+//!
+//! Keeps the last decoded state of each monitored vote account and derives
+//! what changed between consecutive account updates: how far the root
+//! advanced per slot, the credits earned and commission changes. Updates at
+//! or below the last seen slot (copies from other streams or endpoints) are
+//! ignored.
+
+use dashmap::DashMap;
+use solana_sdk::pubkey::Pubkey;
+use tracing::info;
+
+use crate::models::{VoteAccountState, VoteAccountUpdate, VoteStateChange};
+
+/// Last vote account state per validator identity
+#[derive(Default)]
+pub struct VoteStateTracker {
+    states: DashMap<Pubkey, VoteAccountState>,
+}
+
+impl VoteStateTracker {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Record a decoded account update
+    ///
+    /// Returns the update with the changes since the previous one, or `None`
+    /// if the update is not newer than the last one seen.
+    pub fn observe(&self, state: VoteAccountState) -> Option<VoteAccountUpdate> {
+        let change = match self.states.get(&state.validator_pubkey) {
+            Some(previous) if state.slot <= previous.slot => return None,
+            Some(previous) => Some(change_between(&previous, &state)),
+            None => None,
+        };
+        
+        if let Some(previous_commission) = change.and_then(|change| change.previous_commission) {
+            info!(
+                "Validator {} changed commission from {}% to {}%",
+                state.validator_pubkey, previous_commission, state.commission
+            );
+        }
+        
+        self.states.insert(state.validator_pubkey, state.clone());
+        Some(VoteAccountUpdate {
+            state,
+            change,
+            finalized_slot: None,
+            root_lag_slots: None,
+        })
+    }
+    
+    /// Last state seen for a validator
+    pub fn get(&self, validator_pubkey: &Pubkey) -> Option<VoteAccountState> {
+        self.states.get(validator_pubkey).map(|state| state.clone())
+    }
+    
+    /// Forget a validator once it is no longer monitored
+    pub fn remove(&self, validator_pubkey: &Pubkey) {
+        self.states.remove(validator_pubkey);
+    }
+}
+
+/// Changes from one update of a vote account to the next
+fn change_between(previous: &VoteAccountState, current: &VoteAccountState) -> VoteStateChange {
+    let slots_elapsed = current.slot.saturating_sub(previous.slot);
+    let root_advance_slots = match (previous.root_slot, current.root_slot) {
+        (Some(previous), Some(current)) => current.saturating_sub(previous),
+        _ => 0,
+    };
+    
+    VoteStateChange {
+        slots_elapsed,
+        root_advance_slots,
+        root_advance_rate: if slots_elapsed == 0 {
+            0.0
+        } else {
+            root_advance_slots as f64 / slots_elapsed as f64
+        },
+        credits_delta: current.total_credits.saturating_sub(previous.total_credits),
+        previous_commission: (previous.commission != current.commission).then_some(previous.commission),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    
+    fn state(validator: Pubkey, slot: u64, root_slot: u64, total_credits: u64, commission: u8) -> VoteAccountState {
+        VoteAccountState {
+            validator_pubkey: validator,
+            vote_pubkey: Pubkey::new_unique(),
+            slot,
+            node_pubkey: validator,
+            commission,
+            root_slot: Some(root_slot),
+            last_voted_slot: Some(slot - 1),
+            lockout_depth: 31,
+            epoch: Some(700),
+            epoch_credits: total_credits % 1_000,
+            total_credits,
+            last_timestamp_slot: slot - 1,
+            last_timestamp: 1_700_000_000,
+            observed_at: Utc::now(),
+        }
+    }
+    
+    #[test]
+    fn test_changes_between_updates() {
+        let tracker = VoteStateTracker::new();
+        let validator = Pubkey::new_unique();
+        
+        let first = tracker.observe(state(validator, 1_000, 968, 5_000, 5)).unwrap();
+        assert_eq!(first.change, None);
+        
+        let update = tracker.observe(state(validator, 1_004, 970, 5_048, 5)).unwrap();
+        let change = update.change.unwrap();
+        assert_eq!(change.slots_elapsed, 4);
+        assert_eq!(change.root_advance_slots, 2);
+        assert_eq!(change.root_advance_rate, 0.5);
+        assert_eq!(change.credits_delta, 48);
+        assert_eq!(change.previous_commission, None);
+        
+        let update = tracker.observe(state(validator, 1_005, 971, 5_064, 100)).unwrap();
+        assert_eq!(update.change.unwrap().previous_commission, Some(5));
+        assert_eq!(tracker.get(&validator).unwrap().commission, 100);
+    }
+    
+    #[test]
+    fn test_stale_updates_ignored() {
+        let tracker = VoteStateTracker::new();
+        let validator = Pubkey::new_unique();
+        
+        assert!(tracker.observe(state(validator, 1_000, 968, 5_000, 5)).is_some());
+        // The same update delivered by another stream
+        assert!(tracker.observe(state(validator, 1_000, 968, 5_000, 5)).is_none());
+        assert!(tracker.observe(state(validator, 999, 967, 4_984, 5)).is_none());
+        
+        tracker.remove(&validator);
+        assert!(tracker.observe(state(validator, 999, 967, 4_984, 5)).unwrap().change.is_none());
+    }
+}
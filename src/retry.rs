@@ -26,6 +26,9 @@ pub struct RetryConfig {
     
     /// Add random jitter to delays
     pub jitter: bool,
+    
+    /// Fraction of the delay used as the jitter range (0.1 = +/-10%)
+    pub jitter_ratio: f64,
 }
 
 impl Default for RetryConfig {
@@ -36,6 +39,7 @@ impl Default for RetryConfig {
             max_delay: Duration::from_secs(30),
             backoff_multiplier: 2.0,
             jitter: true,
+            jitter_ratio: 0.1,
         }
     }
 }
@@ -75,6 +79,12 @@ impl RetryConfig {
         self.jitter = jitter;
         self
     }
+    
+    /// Set the jitter range as a fraction of the delay
+    pub fn with_jitter_ratio(mut self, ratio: f64) -> Self {
+        self.jitter_ratio = ratio.clamp(0.0, 1.0);
+        self
+    }
 }
 
 /// Retry policy trait for custom retry logic
@@ -104,17 +114,17 @@ impl RetryPolicy for DefaultRetryPolicy {
     }
     
     fn next_delay(&self, attempt: u32, base_delay: Duration) -> Duration {
-        let mut delay = base_delay.mul_f64(self.config.backoff_multiplier.powi(attempt as i32));
-        
-        // Cap at max delay
-        if delay > self.config.max_delay {
-            delay = self.config.max_delay;
-        }
+        // Cap at max delay before building the Duration so long-running
+        // retry loops can't overflow it
+        let factor = self.config.backoff_multiplier.powi(attempt.min(i32::MAX as u32) as i32);
+        let mut delay = Duration::from_secs_f64(
+            (base_delay.as_secs_f64() * factor).min(self.config.max_delay.as_secs_f64())
+        );
         
         // Add jitter if enabled
-        if self.config.jitter {
+        if self.config.jitter && self.config.jitter_ratio > 0.0 {
             use rand::Rng;
-            let jitter_range = delay.as_millis() as f64 * 0.1; // 10% jitter
+            let jitter_range = delay.as_millis() as f64 * self.config.jitter_ratio;
             let jitter = rand::thread_rng().gen_range(-jitter_range..=jitter_range);
             let jittered_millis = (delay.as_millis() as f64 + jitter).max(0.0) as u64;
             delay = Duration::from_millis(jittered_millis);
@@ -352,4 +362,29 @@ mod tests {
         assert_eq!(delay1, Duration::from_millis(200));
         assert_eq!(delay2, Duration::from_millis(400));
     }
+    
+    #[test]
+    fn test_backoff_capped_for_large_attempts() {
+        let config = RetryConfig::default()
+            .with_jitter(false)
+            .with_max_delay(Duration::from_secs(60));
+        let policy = DefaultRetryPolicy::new(config);
+        
+        let delay = policy.next_delay(u32::MAX, Duration::from_secs(5));
+        assert_eq!(delay, Duration::from_secs(60));
+    }
+    
+    #[test]
+    fn test_jitter_ratio_bounds_delay() {
+        let config = RetryConfig::default()
+            .with_jitter_ratio(0.5)
+            .with_max_delay(Duration::from_secs(60));
+        let policy = DefaultRetryPolicy::new(config);
+        
+        for _ in 0..100 {
+            let delay = policy.next_delay(0, Duration::from_secs(10));
+            assert!(delay >= Duration::from_secs(5));
+            assert!(delay <= Duration::from_secs(15));
+        }
+    }
 }
\ No newline at end of file
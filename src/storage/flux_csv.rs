@@ -0,0 +1,420 @@
+//! Flux annotated CSV decoding
+//!
+//! InfluxDB answers Flux queries with annotated CSV. Each table opens with
+//! `#datatype`, `#group` and `#default` annotation rows and a header row;
+//! a query with several `yield`s or group keys returns several tables in one
+//! response, separated by a blank line whenever the schema changes. The
+//! decoder is fed the response body as it arrives and hands back complete
+//! rows, so large results never have to be held in memory at once.
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+/// A decoded cell value
+#[derive(Debug, Clone, PartialEq)]
+pub enum FluxValue {
+    /// Empty cell without a default; annotated CSV can't tell null from ""
+    Null,
+    /// `string` cells; `duration` and `base64Binary` are kept as text too
+    String(String),
+    /// `boolean` cells
+    Bool(bool),
+    /// `long` cells
+    Long(i64),
+    /// `unsignedLong` cells
+    UnsignedLong(u64),
+    /// `double` cells, including `+Inf`, `-Inf` and `NaN`
+    Double(f64),
+    /// `dateTime:RFC3339` and `dateTime:RFC3339Nano` cells
+    Time(DateTime<Utc>),
+}
+
+/// One row of a Flux result table
+#[derive(Debug, Clone, PartialEq)]
+pub struct FluxRow {
+    /// Name of the `yield` the row belongs to
+    pub result: String,
+    
+    /// Table index within the result
+    pub table: i64,
+    
+    /// Cell values by column name, without `result` and `table`
+    pub values: HashMap<String, FluxValue>,
+}
+
+impl FluxRow {
+    /// Raw value of a column, `None` if the column is absent or empty
+    pub fn get(&self, column: &str) -> Option<&FluxValue> {
+        self.values.get(column).filter(|value| **value != FluxValue::Null)
+    }
+    
+    /// Value of a `string` column
+    pub fn get_str(&self, column: &str) -> Option<&str> {
+        match self.get(column)? {
+            FluxValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+    
+    /// Value of a `boolean` column
+    pub fn get_bool(&self, column: &str) -> Option<bool> {
+        match self.get(column)? {
+            FluxValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+    
+    /// Integer value of a `long` or `unsignedLong` column
+    pub fn get_i64(&self, column: &str) -> Option<i64> {
+        match self.get(column)? {
+            FluxValue::Long(value) => Some(*value),
+            FluxValue::UnsignedLong(value) => i64::try_from(*value).ok(),
+            _ => None,
+        }
+    }
+    
+    /// Non-negative integer value of a `long` or `unsignedLong` column
+    pub fn get_u64(&self, column: &str) -> Option<u64> {
+        match self.get(column)? {
+            FluxValue::Long(value) => u64::try_from(*value).ok(),
+            FluxValue::UnsignedLong(value) => Some(*value),
+            _ => None,
+        }
+    }
+    
+    /// Numeric value of any number column
+    pub fn get_f64(&self, column: &str) -> Option<f64> {
+        match self.get(column)? {
+            FluxValue::Double(value) => Some(*value),
+            FluxValue::Long(value) => Some(*value as f64),
+            FluxValue::UnsignedLong(value) => Some(*value as f64),
+            _ => None,
+        }
+    }
+    
+    /// Value of a `dateTime` column
+    pub fn get_time(&self, column: &str) -> Option<DateTime<Utc>> {
+        match self.get(column)? {
+            FluxValue::Time(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+/// Column type from the `#datatype` annotation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DataType {
+    String,
+    Bool,
+    Long,
+    UnsignedLong,
+    Double,
+    Time,
+}
+
+impl DataType {
+    fn from_annotation(annotation: &str) -> Self {
+        match annotation {
+            "boolean" => DataType::Bool,
+            "long" => DataType::Long,
+            "unsignedLong" => DataType::UnsignedLong,
+            "double" => DataType::Double,
+            _ if annotation.starts_with("dateTime") => DataType::Time,
+            _ => DataType::String,
+        }
+    }
+    
+    fn parse(self, raw: &str) -> Result<FluxValue> {
+        let value = match self {
+            DataType::String => FluxValue::String(raw.to_string()),
+            DataType::Bool => FluxValue::Bool(raw.parse()?),
+            DataType::Long => FluxValue::Long(raw.parse()?),
+            DataType::UnsignedLong => FluxValue::UnsignedLong(raw.parse()?),
+            DataType::Double => FluxValue::Double(raw.parse()?),
+            DataType::Time => FluxValue::Time(DateTime::parse_from_rfc3339(raw)?.with_timezone(&Utc)),
+        };
+        Ok(value)
+    }
+}
+
+/// A column of the table being decoded
+struct Column {
+    name: String,
+    datatype: DataType,
+    default: Option<String>,
+}
+
+/// Incremental decoder for annotated CSV responses
+#[derive(Default)]
+pub struct FluxCsvDecoder {
+    /// Bytes of the record not yet terminated
+    pending: Vec<u8>,
+    
+    /// Offset in `pending` scanned for a record end so far
+    scanned: usize,
+    
+    /// Whether `scanned` stopped inside a quoted field
+    in_quotes: bool,
+    
+    /// `#datatype` annotation of the next table
+    datatypes: Vec<String>,
+    
+    /// `#default` annotation of the next table
+    defaults: Vec<String>,
+    
+    /// Columns of the current table, once its header row was read
+    columns: Option<Vec<Column>>,
+}
+
+impl FluxCsvDecoder {
+    /// Create a decoder positioned at the start of a response
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Decode the next chunk of the response, returning the rows it completes
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<Vec<FluxRow>> {
+        self.pending.extend_from_slice(chunk);
+        let pending = std::mem::take(&mut self.pending);
+        
+        let mut rows = Vec::new();
+        let mut start = 0;
+        for i in self.scanned..pending.len() {
+            match pending[i] {
+                b'"' => self.in_quotes = !self.in_quotes,
+                b'\n' if !self.in_quotes => {
+                    if let Some(row) = self.decode_record(&pending[start..i])? {
+                        rows.push(row);
+                    }
+                    start = i + 1;
+                }
+                _ => {}
+            }
+        }
+        
+        self.pending = pending;
+        self.pending.drain(..start);
+        self.scanned = self.pending.len();
+        Ok(rows)
+    }
+    
+    /// Decode whatever is left once the response has ended
+    pub fn finish(mut self) -> Result<Vec<FluxRow>> {
+        if self.in_quotes {
+            bail!("Flux response ended inside a quoted field");
+        }
+        let pending = std::mem::take(&mut self.pending);
+        Ok(self.decode_record(&pending)?.into_iter().collect())
+    }
+    
+    /// Decode one CSV record, returning a row if it was a data row
+    fn decode_record(&mut self, record: &[u8]) -> Result<Option<FluxRow>> {
+        let record = std::str::from_utf8(record)?;
+        let record = record.strip_suffix('\r').unwrap_or(record);
+        
+        // A blank line ends the table; the next one brings its own annotations
+        if record.is_empty() {
+            self.columns = None;
+            self.datatypes.clear();
+            self.defaults.clear();
+            return Ok(None);
+        }
+        
+        let fields = split_fields(record)?;
+        
+        if let Some(annotation) = fields[0].strip_prefix('#') {
+            if self.columns.take().is_some() {
+                self.datatypes.clear();
+                self.defaults.clear();
+            }
+            let values = fields[1..].to_vec();
+            match annotation {
+                "datatype" => self.datatypes = values,
+                "default" => self.defaults = values,
+                _ => {}
+            }
+            return Ok(None);
+        }
+        
+        let Some(columns) = &self.columns else {
+            self.columns = Some(self.header(&fields));
+            return Ok(None);
+        };
+        
+        if fields.len() != columns.len() + 1 {
+            bail!("Flux row has {} columns, table has {}", fields.len() - 1, columns.len());
+        }
+        
+        let mut values = HashMap::with_capacity(columns.len());
+        for (column, raw) in columns.iter().zip(&fields[1..]) {
+            let raw = match (raw.is_empty(), &column.default) {
+                (true, Some(default)) => default.as_str(),
+                _ => raw.as_str(),
+            };
+            let value = if raw.is_empty() {
+                FluxValue::Null
+            } else {
+                column.datatype.parse(raw).map_err(|e| {
+                    anyhow!("Invalid value {:?} in Flux column {}: {}", raw, column.name, e)
+                })?
+            };
+            values.insert(column.name.clone(), value);
+        }
+        
+        // Errors after the response has started are sent as an error table
+        if let Some(FluxValue::String(error)) = values.get("error") {
+            if values.contains_key("reference") {
+                bail!("Flux query error: {}", error);
+            }
+        }
+        
+        let result = match values.remove("result") {
+            Some(FluxValue::String(result)) => result,
+            _ => String::new(),
+        };
+        let table = match values.remove("table") {
+            Some(FluxValue::Long(table)) => table,
+            _ => 0,
+        };
+        
+        Ok(Some(FluxRow { result, table, values }))
+    }
+    
+    /// Columns of a table from its header row and the annotations before it
+    fn header(&self, fields: &[String]) -> Vec<Column> {
+        // The first column only ever holds annotation names
+        fields[1..]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| Column {
+                name: name.clone(),
+                datatype: self
+                    .datatypes
+                    .get(i)
+                    .map_or(DataType::String, |annotation| DataType::from_annotation(annotation.as_str())),
+                default: self.defaults.get(i).filter(|default| !default.is_empty()).cloned(),
+            })
+            .collect()
+    }
+}
+
+/// Decode a complete response
+pub fn decode(response: &str) -> Result<Vec<FluxRow>> {
+    let mut decoder = FluxCsvDecoder::new();
+    let mut rows = decoder.feed(response.as_bytes())?;
+    rows.extend(decoder.finish()?);
+    Ok(rows)
+}
+
+/// Split an RFC 4180 record into its unquoted fields
+fn split_fields(record: &str) -> Result<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = record.chars().peekable();
+    let mut quoted = false;
+    
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => fields.push(std::mem::take(&mut field)),
+            (false, c) => field.push(c),
+        }
+    }
+    
+    if quoted {
+        bail!("Unterminated quoted field in Flux row");
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const PIVOTED: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/flux/vote_latency_pivoted.csv"));
+    const METRICS: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/flux/validator_metrics.csv"));
+    const ERROR: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/flux/query_error.csv"));
+    
+    #[test]
+    fn test_decode_pivoted_tables() {
+        let rows = decode(PIVOTED).unwrap();
+        assert_eq!(rows.len(), 4);
+        
+        // Two tables share the first annotation block
+        assert_eq!(rows.iter().map(|row| row.table).collect::<Vec<_>>(), vec![0, 0, 1, 2]);
+        assert!(rows.iter().all(|row| row.result == "_result"));
+        
+        let first = &rows[0];
+        assert_eq!(first.get_str("landing_status"), Some("confirmed"));
+        assert_eq!(first.get_i64("latency_slots"), Some(1));
+        assert_eq!(first.get_bool("fork_vote"), Some(false));
+        assert_eq!(
+            first.get_time("_time").unwrap(),
+            DateTime::parse_from_rfc3339("2024-06-01T12:00:00.123456789Z").unwrap()
+        );
+        
+        // Empty cells, and a column only the second block has
+        assert_eq!(rows[2].get_str("leader"), None);
+        assert_eq!(rows[2].get_i64("slot_latency_ms"), None);
+        assert_eq!(first.get_i64("create_to_land_ms"), None);
+        assert_eq!(rows[3].get_i64("create_to_land_ms"), Some(412));
+    }
+    
+    #[test]
+    fn test_decode_multiple_results() {
+        let rows = decode(METRICS).unwrap();
+        let results: Vec<&str> = rows.iter().map(|row| row.result.as_str()).collect();
+        assert_eq!(results, vec!["stats", "quantiles", "quantiles", "quantiles", "buckets", "buckets", "buckets"]);
+        
+        assert_eq!(rows[0].get_u64("count"), Some(160));
+        assert_eq!(rows[0].get_f64("mean"), Some(1.5625));
+        assert_eq!(rows[1].get_str("quantile"), Some("median"));
+        assert_eq!(rows[6].get_str("bucket"), Some("3plus_slots"));
+        assert_eq!(rows[6].get_u64("_value"), Some(10));
+    }
+    
+    #[test]
+    fn test_streamed_chunks_match_whole_response() {
+        let whole = decode(PIVOTED).unwrap();
+        
+        for chunk_size in [1, 7, 64] {
+            let mut decoder = FluxCsvDecoder::new();
+            let mut rows = Vec::new();
+            for chunk in PIVOTED.as_bytes().chunks(chunk_size) {
+                rows.extend(decoder.feed(chunk).unwrap());
+            }
+            rows.extend(decoder.finish().unwrap());
+            assert_eq!(rows, whole, "chunk size {}", chunk_size);
+        }
+    }
+    
+    #[test]
+    fn test_error_table() {
+        let err = decode(ERROR).unwrap_err();
+        assert!(err.to_string().contains("panic: runtime error"));
+    }
+    
+    #[test]
+    fn test_special_values_and_defaults() {
+        let csv = "#datatype,string,long,double,string\r\n\
+                   #group,false,false,false,true\r\n\
+                   #default,_result,,,mainnet\r\n\
+                   ,result,table,_value,network\r\n\
+                   ,,0,+Inf,\r\n\
+                   ,,0,NaN,\"test,\"\"net\"\"\r\nb\"\r\n";
+        let rows = decode(csv).unwrap();
+        assert_eq!(rows[0].get_f64("_value"), Some(f64::INFINITY));
+        assert!(rows[1].get_f64("_value").unwrap().is_nan());
+        assert_eq!(rows[0].get_str("network"), Some("mainnet"));
+        assert_eq!(rows[1].get_str("network"), Some("test,\"net\"\r\nb"));
+        assert_eq!(rows[0].result, "_result");
+    }
+}
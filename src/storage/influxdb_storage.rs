@@ -6,22 +6,32 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use tokio::sync::mpsc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use influxdb2::{Client, models::DataPoint};
-use influxdb2::models::Query;
-use futures::stream;
+use influxdb2::models::data_point::DataPointBuilder;
+use influxdb2::models::WriteDataPoint;
 use lru::LruCache;
 use parking_lot::Mutex;
+use solana_sdk::pubkey::Pubkey;
+use std::io::Write;
 use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tokio::time::interval;
 use tracing::{debug, error, info, warn};
 
+use super::flux_csv::{FluxCsvDecoder, FluxRow};
+use super::spool::WriteSpool;
+use super::tag_migration::{delete_predicate, ShortKeyMap, TagMigrationReport, LEGACY_NETWORK_TAG, MEASUREMENTS, SHORT_TAG_LEN};
 use crate::config::InfluxConfig;
-use crate::models::{VoteLatency, LatencyMetrics, ValidatorInfo};
+use crate::metrics::METRICS;
+use crate::models::{vote_credits, VOTE_CREDITS_MAXIMUM_PER_SLOT, VoteLatency, LatencyMetrics, LandingStatus, LatencyBreakdown, MissedVote, ForkVote, HashMismatch, ValidatorInfo, VoteAccountUpdate};
 
 /// Maximum number of points to buffer before forcing a flush
 const MAX_BUFFER_SIZE: usize = 5000;
@@ -29,6 +39,21 @@ const MAX_BUFFER_SIZE: usize = 5000;
 /// Maximum time to wait before flushing the buffer
 const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_millis(100);
 
+/// Time allowed to connect to InfluxDB
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Time allowed for a write request, so a stalled InfluxDB can't wedge a worker
+const WRITE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Time allowed for a read query, including streaming its response
+const QUERY_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Time allowed for a tag migration rewrite or delete, which walk the whole bucket
+const MIGRATION_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Time allowed for workers to drain queued batches on shutdown
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Worker handle for background write tasks
 struct WorkerHandle {
     handle: tokio::task::JoinHandle<()>,
@@ -40,36 +65,116 @@ struct WriteBatch {
     created_at: Instant,
 }
 
+/// Why a line protocol write failed
+#[derive(Debug, thiserror::Error)]
+enum WriteError {
+    /// InfluxDB unreachable, failing (5xx) or throttling (429); worth retrying
+    #[error("{0}")]
+    Transient(String),
+    
+    /// InfluxDB refused the batch itself; it would be refused again
+    #[error("{0}")]
+    Rejected(String),
+}
+
+/// Writes line protocol bodies to the InfluxDB write API
+#[derive(Clone)]
+struct LineProtocolWriter {
+    http: reqwest::Client,
+    url: String,
+    org: String,
+    bucket: String,
+    token: String,
+    compress: bool,
+}
+
+impl LineProtocolWriter {
+    fn new(http: reqwest::Client, config: &InfluxConfig) -> Self {
+        Self {
+            http,
+            url: format!("{}/api/v2/write", config.url.trim_end_matches('/')),
+            org: config.org.clone(),
+            bucket: config.bucket.clone(),
+            token: config.token.clone(),
+            compress: config.enable_compression,
+        }
+    }
+    
+    /// Write a body, gzipped if compression is enabled
+    async fn write(&self, body: &[u8]) -> std::result::Result<(), WriteError> {
+        let mut request = self
+            .http
+            .post(&self.url)
+            .timeout(WRITE_TIMEOUT)
+            .query(&[("org", self.org.as_str()), ("bucket", self.bucket.as_str()), ("precision", "ns")])
+            .header(reqwest::header::AUTHORIZATION, format!("Token {}", self.token))
+            .header(reqwest::header::CONTENT_TYPE, "text/plain; charset=utf-8");
+        
+        request = if self.compress {
+            request
+                .header(reqwest::header::CONTENT_ENCODING, "gzip")
+                .body(gzip(body).map_err(|e| WriteError::Rejected(format!("Failed to compress batch: {}", e)))?)
+        } else {
+            request.body(body.to_vec())
+        };
+        
+        let response = request
+            .send()
+            .await
+            .map_err(|e| WriteError::Transient(format!("InfluxDB write failed: {}", e)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            let message = format!("InfluxDB write failed with {}: {}", status, body);
+            return Err(if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                WriteError::Transient(message)
+            } else {
+                WriteError::Rejected(message)
+            });
+        }
+        
+        Ok(())
+    }
+}
+
 /// InfluxDB storage implementation
 pub struct InfluxDBStorage {
-    /// InfluxDB client
-    client: Arc<Client>,
+    /// HTTP client for streaming query responses
+    http: reqwest::Client,
     
     /// Configuration
     config: InfluxConfig,
     
+    /// Solana network tagged on every point
+    network: String,
+    
     /// Write buffer protected by RwLock
     write_buffer: Arc<RwLock<Vec<DataPoint>>>,
     
-    /// Channel for sending batches to workers
-    batch_sender: mpsc::Sender<WriteBatch>,
+    /// Channel for sending batches to workers, dropped on shutdown so they exit
+    batch_sender: Mutex<Option<mpsc::Sender<WriteBatch>>>,
     
     /// Worker handles
-    workers: Vec<WorkerHandle>,
+    workers: Mutex<Vec<WorkerHandle>>,
+    
+    /// Spool replay task handle
+    replay_handle: Mutex<Option<WorkerHandle>>,
     
     /// Deduplication cache (signature -> timestamp)
     dedup_cache: Arc<Mutex<LruCache<String, Instant>>>,
     
     /// Flush task handle
-    flush_handle: Option<tokio::task::JoinHandle<()>>,
+    flush_handle: Mutex<Option<tokio::task::JoinHandle<()>>>,
     
     /// Shutdown flag
     shutdown: Arc<AtomicBool>,
 }
 
 impl InfluxDBStorage {
-    /// Create a new InfluxDB storage instance
-    pub async fn new(config: InfluxConfig) -> Result<Self> {
+    /// Create a new InfluxDB storage instance for a Solana network
+    ///
+    /// Starts even if InfluxDB is unreachable; writes are spooled until it is back.
+    pub async fn new(config: InfluxConfig, network: String) -> Result<Self> {
         info!("Initializing InfluxDB storage with URL: {}", config.url);
         
         // Create InfluxDB client
@@ -78,38 +183,64 @@ impl InfluxDBStorage {
         // Test connection
         match client.ready().await {
             Ok(_) => info!("Successfully connected to InfluxDB"),
-            Err(e) => {
-                error!("Failed to connect to InfluxDB: {}", e);
-                return Err(anyhow::anyhow!("InfluxDB connection failed: {}", e));
-            }
+            Err(e) => warn!("InfluxDB is not reachable yet, spooling writes until it is: {}", e),
         }
         
+        let spool = if config.spool_max_mb > 0 {
+            let (dir, max_bytes, max_batches) = (
+                PathBuf::from(&config.spool_dir),
+                config.spool_max_mb * 1024 * 1024,
+                config.spool_max_batches,
+            );
+            let spool = tokio::task::spawn_blocking(move || WriteSpool::open(&dir, max_bytes, max_batches)).await??;
+            let depth = spool.depth();
+            METRICS.set_influx_spool_depth(depth.batches, depth.bytes);
+            Some(Arc::new(spool))
+        } else {
+            warn!("InfluxDB write spool disabled; batches are dropped while InfluxDB is unavailable");
+            None
+        };
+        
         // Create write channel
         let (batch_sender, batch_receiver) = mpsc::channel::<WriteBatch>(100);
         
-        // Clone client before moving it to spawn_workers
-        let client_arc = Arc::new(client.clone());
+        let http = reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .build()?;
+        let writer = LineProtocolWriter::new(http.clone(), &config);
         
         // Create workers
         let workers = Self::spawn_workers(
-            client,
+            writer.clone(),
             batch_receiver,
             config.num_workers,
-            config.bucket.clone(),
+            spool.clone(),
         );
         
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let replay_handle = spool.map(|spool| {
+            Self::spawn_replay_task(
+                writer,
+                spool,
+                Duration::from_secs(config.spool_replay_interval_secs),
+                shutdown.clone(),
+            )
+        });
+        
         // Create deduplication cache (10k entries)
         let dedup_cache = Arc::new(Mutex::new(LruCache::new(NonZeroUsize::new(10_000).unwrap())));
         
         let mut storage = Self {
-            client: client_arc,
+            http,
             config: config.clone(),
+            network,
             write_buffer: Arc::new(RwLock::new(Vec::with_capacity(MAX_BUFFER_SIZE))),
-            batch_sender,
-            workers,
+            batch_sender: Mutex::new(Some(batch_sender)),
+            workers: Mutex::new(workers),
+            replay_handle: Mutex::new(replay_handle),
             dedup_cache,
-            flush_handle: None,
-            shutdown: Arc::new(AtomicBool::new(false)),
+            flush_handle: Mutex::new(None),
+            shutdown,
         };
         
         // Start flush task
@@ -118,67 +249,171 @@ impl InfluxDBStorage {
         Ok(storage)
     }
     
-    /// Spawn worker threads for writing to InfluxDB
+    /// Spawn the pool of workers writing batches to InfluxDB
+    ///
+    /// Workers take turns on the shared receiver, so each batch goes to the
+    /// next idle worker.
     fn spawn_workers(
-        client: Client,
-        mut receiver: mpsc::Receiver<WriteBatch>,
-        _num_workers: usize,
-        bucket: String,
+        writer: LineProtocolWriter,
+        receiver: mpsc::Receiver<WriteBatch>,
+        num_workers: usize,
+        spool: Option<Arc<WriteSpool>>,
     ) -> Vec<WorkerHandle> {
-        let mut workers = Vec::new();
-        let client = Arc::new(client);
+        let receiver = Arc::new(tokio::sync::Mutex::new(receiver));
         
-        // For now, use a single worker to avoid complexity with mpsc single receiver
+        (0..num_workers.max(1))
+            .map(|worker| {
+                let writer = writer.clone();
+                let receiver = receiver.clone();
+                let spool = spool.clone();
+                
+                let handle = tokio::spawn(async move {
+                    info!("InfluxDB write worker {} started", worker);
+                    
+                    loop {
+                        // Held only while waiting, released before the write
+                        let Some(batch) = receiver.lock().await.recv().await else {
+                            break;
+                        };
+                        Self::write_batch(worker, &writer, spool.as_deref(), batch).await;
+                    }
+                    
+                    info!("InfluxDB write worker {} shutting down", worker);
+                });
+                
+                WorkerHandle { handle }
+            })
+            .collect()
+    }
+    
+    /// Write one batch with retries, spooling it if InfluxDB won't take it
+    async fn write_batch(
+        worker: usize,
+        writer: &LineProtocolWriter,
+        spool: Option<&WriteSpool>,
+        batch: WriteBatch,
+    ) {
+        let points_count = batch.points.len();
+        let batch_age = batch.created_at.elapsed();
+                
+        debug!(
+            "Worker {} processing batch with {} points (age: {:?})",
+            worker, points_count, batch_age
+        );
+                
+        let body = match line_protocol(&batch.points) {
+            Ok(body) => body,
+            Err(e) => {
+                error!("Failed to encode batch of {} points: {}", points_count, e);
+                return;
+            }
+        };
+        
+        // Batches are already waiting on InfluxDB; queue up behind them
+        if let Some(spool) = spool.filter(|spool| !spool.is_empty()) {
+            spool_batch(spool, &body, points_count).await;
+            return;
+        }
+        
+        // Write to InfluxDB with retry
+        let mut retries = 0;
+        loop {
+            let started = Instant::now();
+            let result = writer.write(&body).await;
+            METRICS.record_influx_write(worker, result.is_ok(), started.elapsed());
+            
+            match result {
+                Ok(()) => {
+                    debug!("Worker {} successfully wrote {} points", worker, points_count);
+                    break;
+                }
+                Err(WriteError::Rejected(e)) => {
+                    retries += 1;
+                    error!("Worker {} dropped a batch of {} points InfluxDB rejected: {}", worker, points_count, e);
+                    METRICS.record_influx_rejected("write");
+                    break;
+                }
+                Err(e) => {
+                    retries += 1;
+                    if retries > 3 {
+                        match spool {
+                            Some(spool) => {
+                                warn!("Worker {} failed to write batch after {} retries, spooling it: {}", worker, retries, e);
+                                spool_batch(spool, &body, points_count).await;
+                            }
+                            None => error!(
+                                "Worker {} failed to write batch after {} retries: {}",
+                                worker, retries, e
+                            ),
+                        }
+                        break;
+                    }
+                    warn!(
+                        "Worker {} write failed (retry {}): {}",
+                        worker, retries, e
+                    );
+                    tokio::time::sleep(Duration::from_millis(100 * u64::from(retries))).await;
+                }
+            }
+        }
+        
+        METRICS.record_influx_batch(worker, points_count, retries);
+    }
+    
+    /// Spawn the task replaying spooled batches once InfluxDB takes writes again
+    fn spawn_replay_task(
+        writer: LineProtocolWriter,
+        spool: Arc<WriteSpool>,
+        replay_interval: Duration,
+        shutdown: Arc<AtomicBool>,
+    ) -> WorkerHandle {
         let handle = tokio::spawn(async move {
-            info!("InfluxDB write worker started");
+            let mut ticker = interval(replay_interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
             
-            while let Some(batch) = receiver.recv().await {
-                let points_count = batch.points.len();
-                let batch_age = batch.created_at.elapsed();
-                
-                debug!(
-                    "Worker processing batch with {} points (age: {:?})",
-                    points_count, batch_age
-                );
+            while !shutdown.load(Ordering::Relaxed) {
+                ticker.tick().await;
                 
-                // Write to InfluxDB with retry
-                let mut retries = 0;
-                loop {
-                    match client.write(&bucket, stream::iter(batch.points.clone())).await {
-                        Ok(_) => {
-                            debug!("Worker successfully wrote {} points", points_count);
-                            break;
+                let (mut replayed, mut rejected) = (0, 0);
+                while let Some(batch) = spool.oldest().await {
+                    match writer.write(&batch.body).await {
+                        Ok(()) => replayed += 1,
+                        // Retrying won't help and it would hold up every batch behind it
+                        Err(WriteError::Rejected(e)) => {
+                            error!("Dropping spooled batch InfluxDB rejected: {}", e);
+                            METRICS.record_influx_rejected("replay");
+                            rejected += 1;
                         }
                         Err(e) => {
-                            retries += 1;
-                            if retries > 3 {
-                                error!(
-                                    "Worker failed to write batch after {} retries: {}",
-                                    retries, e
-                                );
-                                break;
-                            }
-                            warn!(
-                                "Worker write failed (retry {}): {}",
-                                retries, e
-                            );
-                            tokio::time::sleep(Duration::from_millis(100 * retries)).await;
+                            debug!("InfluxDB still unavailable, {} batches spooled: {}", spool.depth().batches, e);
+                            break;
                         }
                     }
+                    spool.remove(&batch).await;
+                    
+                    if shutdown.load(Ordering::Relaxed) {
+                        break;
+                    }
+                }
+                
+                if replayed + rejected > 0 {
+                    let depth = spool.depth();
+                    info!("Replayed {} spooled batches to InfluxDB, {} left", replayed, depth.batches);
+                    METRICS.record_influx_spool("replayed", replayed);
+                    METRICS.set_influx_spool_depth(depth.batches, depth.bytes);
                 }
             }
-            
-            info!("InfluxDB write worker shutting down");
         });
         
-        workers.push(WorkerHandle { handle });
-        workers
+        WorkerHandle { handle }
     }
     
     /// Start the periodic flush task
     fn start_flush_task(&mut self) {
         let buffer = self.write_buffer.clone();
-        let sender = self.batch_sender.clone();
+        let Some(sender) = self.batch_sender.lock().clone() else {
+            return;
+        };
         let flush_interval = Duration::from_millis(self.config.flush_interval_ms);
         let shutdown = self.shutdown.clone();
         
@@ -214,7 +449,7 @@ impl InfluxDBStorage {
             info!("Flush task shutting down");
         });
         
-        self.flush_handle = Some(handle);
+        *self.flush_handle.lock() = Some(handle);
     }
     
     /// Write a vote latency record
@@ -231,44 +466,213 @@ impl InfluxDBStorage {
             cache.put(latency.signature.clone(), Instant::now());
         }
         
-        // Create data point
-        let point = DataPoint::builder("vote_latency")
-            .tag("validator_id", &latency.validator_pubkey.to_string()[..8])
-            .tag("vote_account", &latency.vote_pubkey.to_string()[..8])
-            .tag("network", "mainnet") // TODO: Get from config
-            .field("latency_slots", latency.latency_slot() as i64)
-            .field("voted_slot", latency.voted_on_slot() as i64)
-            .field("landed_slot", latency.landed_slot as i64)
-            .field("latency_ms", latency.latency_ms as i64)
-            .timestamp(latency.received_timestamp.timestamp_nanos_opt().unwrap_or(0))
+        for point in self.vote_latency_points(latency)? {
+            self.buffer_point(point).await;
+        }
+        Ok(())
+    }
+    
+    /// One `vote_latency` point per newly voted slot
+    ///
+    /// A catch-up vote lands several slots at once, each with its own latency.
+    /// The points are a nanosecond apart, the newest slot at the receive time,
+    /// so they don't overwrite each other; the transaction's wall-clock fields
+    /// are repeated on each.
+    fn vote_latency_points(&self, latency: &VoteLatency) -> Result<Vec<DataPoint>> {
+        let received = latency.received_timestamp.timestamp_nanos_opt().unwrap_or(0);
+        let slots: Vec<(u64, u8)> = if latency.voted_on_slots.len() == latency.latency_slots.len() {
+            latency.voted_on_slots.iter().copied().zip(latency.latency_slots.iter().copied()).collect()
+        } else {
+            vec![(latency.voted_on_slot(), latency.latency_slot())]
+        };
+        
+        let mut points = Vec::with_capacity(slots.len());
+        for (index, (slot, latency_slots)) in slots.iter().enumerate() {
+            let newest = index + 1 == slots.len();
+            let mut builder = self.point_builder("vote_latency", &latency.validator_pubkey, &latency.vote_pubkey)
+                .tag("landing_status", latency.landing_status.as_str())
+                .field("latency_slots", i64::from(*latency_slots))
+                .field("voted_slot", *slot as i64)
+                .field("landed_slot", latency.landed_slot as i64)
+                .field("latency_ms", latency.latency_ms as i64)
+                .field("credits", vote_credits(*latency_slots) as i64)
+                .field("max_credits", VOTE_CREDITS_MAXIMUM_PER_SLOT as i64)
+                .field("fork_vote", latency.fork_slots.contains(slot))
+                .field("signature", latency.signature.clone());
+        
+            if let Some(leader) = latency.leader {
+                builder = builder.tag("leader", leader.to_string());
+            }
+            
+            // Wall-clock timings, only where the timestamps were known; the
+            // slot latency is measured for the newest slot
+            for (name, value) in [
+                ("create_to_land_ms", latency.breakdown.create_to_land_ms),
+                ("land_to_emit_ms", latency.breakdown.land_to_emit_ms),
+                ("emit_to_receive_ms", latency.breakdown.emit_to_receive_ms),
+                ("slot_latency_ms", latency.slot_latency_ms.filter(|_| newest)),
+            ] {
+                if let Some(value) = value {
+                    builder = builder.field(name, value);
+                }
+            }
+            
+            let older = (slots.len() - 1 - index) as i64;
+            points.push(builder.timestamp(received - older).build()?);
+        }
+        Ok(points)
+    }
+    
+    /// Write a missed vote event
+    pub async fn write_missed_vote(&self, missed: &MissedVote) -> Result<()> {
+        let point = self.point_builder("missed_vote", &missed.validator_pubkey, &missed.vote_pubkey)
+            .field("slot", missed.slot as i64)
+            .field("missed_votes", missed.coverage.missed_votes() as i64)
+            .field("vote_coverage_pct", missed.coverage.coverage_pct())
+            .timestamp(missed.detected_at.timestamp_nanos_opt().unwrap_or(0))
             .build()?;
         
-        // Add to buffer
-        {
-            let mut buffer = self.write_buffer.write().await;
-            buffer.push(point);
+        self.buffer_point(point).await;
+        Ok(())
+    }
+    
+    /// Write a vote for slots that ended up off the finalized chain
+    pub async fn write_fork_vote(&self, fork_vote: &ForkVote) -> Result<()> {
+        let mut builder = self.point_builder("fork_vote", &fork_vote.validator_pubkey, &fork_vote.vote_pubkey)
+            .tag("landing_status", fork_vote.landing_status.as_str())
+            .field("slot", fork_vote.slot as i64)
+            .field("fork_slots", fork_vote.fork_slots.len() as i64)
+            .field("landed_slot", fork_vote.landed_slot as i64)
+            .field("signature", fork_vote.signature.clone());
+        
+        if let Some(vote_hash) = &fork_vote.vote_hash {
+            builder = builder.field("vote_hash", vote_hash.clone());
+        }
+        
+        let point = builder
+            .timestamp(fork_vote.detected_at.timestamp_nanos_opt().unwrap_or(0))
+            .build()?;
+        
+        self.buffer_point(point).await;
+        Ok(())
+    }
+    
+    /// Write a vote whose bank hash disagreed with the cluster's
+    pub async fn write_hash_mismatch(&self, mismatch: &HashMismatch) -> Result<()> {
+        let point = self.point_builder("hash_mismatch", &mismatch.validator_pubkey, &mismatch.vote_pubkey)
+            .field("slot", mismatch.slot as i64)
+            .field("vote_hash", mismatch.vote_hash.clone())
+            .field("cluster_hash", mismatch.cluster_hash.clone())
+            .field("agreeing_votes", mismatch.agreeing_votes as i64)
+            .field("signature", mismatch.signature.clone())
+            .timestamp(mismatch.detected_at.timestamp_nanos_opt().unwrap_or(0))
+            .build()?;
+        
+        self.buffer_point(point).await;
+        Ok(())
+    }
+    
+    /// Write a vote account state update with the changes since the previous one
+    pub async fn write_vote_account_update(&self, update: &VoteAccountUpdate) -> Result<()> {
+        let state = &update.state;
+        let mut builder = self.point_builder("vote_account_state", &state.validator_pubkey, &state.vote_pubkey)
+            .field("slot", state.slot as i64)
+            .field("commission", state.commission as i64)
+            .field("lockout_depth", state.lockout_depth as i64)
+            .field("epoch_credits", state.epoch_credits as i64)
+            .field("total_credits", state.total_credits as i64)
+            .field("last_timestamp", state.last_timestamp);
+        
+        for (name, value) in [
+            ("root_slot", state.root_slot),
+            ("last_voted_slot", state.last_voted_slot),
+            ("epoch", state.epoch),
+            ("finalized_slot", update.finalized_slot),
+            ("root_lag_slots", update.root_lag_slots),
+        ] {
+            if let Some(value) = value {
+                builder = builder.field(name, value as i64);
+            }
+        }
+        
+        // Derived signals, from the second update of an account on
+        if let Some(change) = &update.change {
+            builder = builder
+                .field("root_advance_slots", change.root_advance_slots as i64)
+                .field("root_advance_rate", change.root_advance_rate)
+                .field("credits_delta", change.credits_delta as i64)
+                .field("commission_changed", change.previous_commission.is_some());
+            if let Some(previous_commission) = change.previous_commission {
+                builder = builder.field("previous_commission", previous_commission as i64);
+            }
+        }
+        
+        let point = builder
+            .timestamp(state.observed_at.timestamp_nanos_opt().unwrap_or(0))
+            .build()?;
+        
+        self.buffer_point(point).await;
+        Ok(())
+    }
+    
+    /// Write the epoch credits of a validator, or the totals across validators
+    ///
+    /// Metrics without any credited vote are skipped.
+    pub async fn write_epoch_credits(&self, metrics: &LatencyMetrics, validator: Option<&Pubkey>) -> Result<()> {
+        if let Some(point) = epoch_credits_point(&self.network, self.config.instance.as_deref(), metrics, validator)? {
+            self.buffer_point(point).await;
+        }
+        Ok(())
+    }
+    
+    /// Start a point tagged with its validator's identity and vote account
+    fn point_builder(&self, measurement: &str, validator: &Pubkey, vote_account: &Pubkey) -> DataPointBuilder {
+        let validator_id = validator.to_string();
+        let mut builder = DataPoint::builder(measurement);
+        if self.config.short_validator_tag {
+            builder = builder.tag("validator_short", &validator_id[..SHORT_TAG_LEN]);
+        }
+        if let Some(instance) = &self.config.instance {
+            builder = builder.tag("instance", instance.as_str());
+        }
+        builder
+            .tag("validator_id", validator_id)
+            .tag("vote_account", vote_account.to_string())
+            .tag("network", self.network.as_str())
+    }
+    
+    
+    /// Add a point to the write buffer, sending the batch once it is full
+    async fn buffer_point(&self, point: DataPoint) {
+        let mut buffer = self.write_buffer.write().await;
+        buffer.push(point);
             
-            // Force flush if buffer is full
-            if buffer.len() >= self.config.batch_size {
-                let points = std::mem::take(&mut *buffer);
-                drop(buffer); // Release lock before sending
+        // Force flush if buffer is full
+        if buffer.len() >= self.config.batch_size {
+            let points = std::mem::take(&mut *buffer);
+            drop(buffer); // Release lock before sending
                 
-                let batch = WriteBatch {
-                    points,
-                    created_at: Instant::now(),
-                };
+            let batch = WriteBatch {
+                points,
+                created_at: Instant::now(),
+            };
                 
-                // Use blocking try_send since we're in an async context but don't want to await
-                if let Err(e) = self.batch_sender.try_send(batch) {
-                    warn!("Failed to send batch immediately: {}", e);
+            // Use blocking try_send since we're in an async context but don't want to await
+            match &*self.batch_sender.lock() {
+                Some(sender) => {
+                    if let Err(e) = sender.try_send(batch) {
+                        warn!("Failed to send batch immediately: {}", e);
+                    }
                 }
+                None => warn!("Dropping batch of {} points written after shutdown", batch.points.len()),
             }
         }
-        
-        Ok(())
     }
     
     /// Query vote latencies for a time range
+    ///
+    /// Points still carrying truncated pubkey tags come back with
+    /// `Pubkey::default()` keys until they are migrated.
     pub async fn query_latencies(
         &self,
         validator_pubkey: Option<&str>,
@@ -280,28 +684,36 @@ impl InfluxDBStorage {
             from(bucket: "{}")
                 |> range(start: {}, stop: {})
                 |> filter(fn: (r) => r._measurement == "vote_latency")
+                {}
             "#,
             self.config.bucket,
             start_time.to_rfc3339(),
-            end_time.to_rfc3339()
+            end_time.to_rfc3339(),
+            series_filter(&self.network, self.config.instance.as_deref())
         );
         
         if let Some(validator) = validator_pubkey {
             query.push_str(&format!(
                 r#"|> filter(fn: (r) => r.validator_id == "{}")"#,
-                &validator[..8]
+                validator
             ));
         }
         
-        // Execute query
-        let query_obj = Query::new(query);
-        // For now, use query_raw to get string results
-        let result = self.client.query_raw(Some(query_obj)).await?;
+        // One row per point, with a column per field; validators can share a timestamp
+        query.push_str(r#"|> pivot(rowKey: ["_time", "validator_id", "vote_account"], columnKey: ["_field"], valueColumn: "_value")"#);
         
-        // Parse results (simplified for now)
-        // TODO: Implement proper result parsing from CSV format
-        warn!("Query result parsing not yet implemented, got {} bytes", result.len());
-        Ok(vec![])
+        let mut latencies = Vec::new();
+        let mut skipped = 0usize;
+        self.query_rows(query, QUERY_TIMEOUT, |row| match latency_from_row(&row) {
+            Some(latency) => latencies.push(latency),
+            None => skipped += 1,
+        })
+        .await?;
+        
+        if skipped > 0 {
+            warn!("Skipped {} vote latency rows missing slot fields", skipped);
+        }
+        Ok(latencies)
     }
     
     /// Get aggregated metrics for a validator
@@ -312,47 +724,39 @@ impl InfluxDBStorage {
     ) -> Result<LatencyMetrics> {
         let query = format!(
             r#"
-            import "math"
-            
             data = from(bucket: "{}")
                 |> range(start: -{}s)
                 |> filter(fn: (r) => r._measurement == "vote_latency")
+                {}
                 |> filter(fn: (r) => r.validator_id == "{}")
                 |> filter(fn: (r) => r._field == "latency_slots")
-                
-            // Calculate basic statistics
-            stats = data
                 |> group()
+                |> keep(columns: ["_value"])
+            
+            // Calculate basic statistics
+            data
                 |> reduce(
-                    identity: {{
-                        count: 0,
-                        sum: 0.0,
-                        sum_squares: 0.0,
-                        min: 999999.0,
-                        max: 0.0,
-                        values: []
-                    }},
+                    identity: {{count: 0, sum: 0.0, min: 0.0, max: 0.0}},
                     fn: (r, accumulator) => ({{
                         count: accumulator.count + 1,
                         sum: accumulator.sum + float(v: r._value),
-                        sum_squares: accumulator.sum_squares + float(v: r._value) * float(v: r._value),
-                        min: if r._value < accumulator.min then float(v: r._value) else accumulator.min,
-                        max: if r._value > accumulator.max then float(v: r._value) else accumulator.max,
-                        values: accumulator.values
+                        min: if accumulator.count == 0 or float(v: r._value) < accumulator.min then float(v: r._value) else accumulator.min,
+                        max: if float(v: r._value) > accumulator.max then float(v: r._value) else accumulator.max
                     }})
                 )
-                |> map(fn: (r) => ({{
-                    count: r.count,
-                    mean: r.sum / float(v: r.count),
-                    min: r.min,
-                    max: r.max,
-                    stddev: math.sqrt(x: (r.sum_squares / float(v: r.count)) - (r.sum / float(v: r.count)) * (r.sum / float(v: r.count)))
-                }}))
+                |> map(fn: (r) => ({{count: r.count, mean: r.sum / float(v: r.count), min: r.min, max: r.max}}))
                 |> yield(name: "stats")
-                
+            
+            // Percentiles, as latencies actually observed
+            union(tables: [
+                data |> quantile(q: 0.5, method: "exact_selector") |> set(key: "quantile", value: "median"),
+                data |> quantile(q: 0.95, method: "exact_selector") |> set(key: "quantile", value: "p95"),
+                data |> quantile(q: 0.99, method: "exact_selector") |> set(key: "quantile", value: "p99"),
+            ])
+                |> yield(name: "quantiles")
+            
             // Count by latency buckets
-            buckets = data
-                |> group()
+            data
                 |> map(fn: (r) => ({{
                     r with
                     bucket: if r._value <= 1 then "1_slot" 
@@ -365,37 +769,134 @@ impl InfluxDBStorage {
             "#,
             self.config.bucket,
             window.as_secs(),
-            &validator_pubkey[..8]
+            series_filter(&self.network, self.config.instance.as_deref()),
+            validator_pubkey
         );
         
-        // Execute query
-        let query_obj = Query::new(query);
-        let result = self.client.query_raw(Some(query_obj)).await?;
-        
-        // Parse CSV results
-        // TODO: Implement proper CSV parsing
-        warn!("Metrics query parsing not yet implemented, got {} bytes", result.len());
-        
-        // For now, return default metrics
-        Ok(LatencyMetrics {
-            mean_ms: 0.0,
-            median_ms: 0.0,
-            p95_ms: 0.0,
-            p99_ms: 0.0,
-            min_ms: 0.0,
-            max_ms: 0.0,
-            mean_slots: 2.0,
-            median_slots: 2.0,
-            p95_slots: 3.0,
-            p99_slots: 4.0,
-            min_slots: 1.0,
-            max_slots: 5.0,
-            votes_1_slot: 100,
-            votes_2_slots: 50,
-            votes_3plus_slots: 10,
-            sample_count: 160,
+        let mut metrics = LatencyMetrics {
             timestamp: Utc::now(),
-        })
+            ..Default::default()
+        };
+        self.query_rows(query, QUERY_TIMEOUT, |row| apply_metrics_row(&mut metrics, &row)).await?;
+        
+        Ok(metrics)
+    }
+    
+    /// Run a Flux query, handing each row to `on_row` as the response streams in
+    ///
+    /// `timeout` covers the whole query, from sending it to the last row.
+    async fn query_rows(&self, query: String, timeout: Duration, mut on_row: impl FnMut(FluxRow)) -> Result<()> {
+        let url = format!("{}/api/v2/query", self.config.url.trim_end_matches('/'));
+        let mut response = self
+            .http
+            .post(url)
+            .timeout(timeout)
+            .query(&[("org", &self.config.org)])
+            .header(reqwest::header::AUTHORIZATION, format!("Token {}", self.config.token))
+            .header(reqwest::header::ACCEPT, "application/csv")
+            .json(&serde_json::json!({
+                "query": query,
+                "type": "flux",
+                "dialect": {
+                    "header": true,
+                    "annotations": ["datatype", "group", "default"],
+                },
+            }))
+            .send()
+            .await?;
+        
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("InfluxDB query failed with {}: {}", status, body));
+        }
+        
+        let mut decoder = FluxCsvDecoder::new();
+        let mut rows = 0usize;
+        while let Some(chunk) = response.chunk().await? {
+            for row in decoder.feed(&chunk)? {
+                rows += 1;
+                on_row(row);
+            }
+        }
+        for row in decoder.finish()? {
+            rows += 1;
+            on_row(row);
+        }
+        
+        debug!("Flux query returned {} rows", rows);
+        Ok(())
+    }
+    
+    /// Rewrite points tagged with truncated pubkeys to carry the full keys
+    ///
+    /// A dry run only counts the points that would be rewritten.
+    pub async fn migrate_short_tags(&self, keys: &ShortKeyMap, dry_run: bool) -> Result<TagMigrationReport> {
+        let stop = Utc::now();
+        let mut report = TagMigrationReport::default();
+        
+        for measurement in MEASUREMENTS {
+            let query = keys.rewrite_query(
+                &self.config.bucket,
+                measurement,
+                &self.network,
+                stop,
+                self.config.short_validator_tag,
+                !dry_run,
+            );
+            
+            let mut migrated = Vec::new();
+            self.query_rows(query, MIGRATION_TIMEOUT, |row| {
+                report.points += row.get_u64("_value").unwrap_or(0);
+                if let (Some(validator), Some(vote_account)) = (row.get_str("validator_id"), row.get_str("vote_account")) {
+                    if let (Ok(validator), Ok(vote_account)) = (Pubkey::from_str(validator), Pubkey::from_str(vote_account)) {
+                        migrated.push((validator, vote_account));
+                    }
+                }
+            })
+            .await?;
+            
+            migrated.sort_unstable();
+            migrated.dedup();
+            info!("{}: {} key pairs with truncated tags", measurement, migrated.len());
+            
+            if dry_run {
+                continue;
+            }
+            
+            // Only once the rewrite succeeded for every pair of the measurement
+            for (validator, vote_account) in migrated {
+                self.delete_series(&delete_predicate(measurement, &validator, &vote_account), stop).await?;
+                report.deleted_series += 1;
+            }
+        }
+        
+        Ok(report)
+    }
+    
+    /// Delete the points matching a predicate written before `stop`
+    async fn delete_series(&self, predicate: &str, stop: DateTime<Utc>) -> Result<()> {
+        let url = format!("{}/api/v2/delete", self.config.url.trim_end_matches('/'));
+        let response = self
+            .http
+            .post(url)
+            .timeout(MIGRATION_TIMEOUT)
+            .query(&[("org", &self.config.org), ("bucket", &self.config.bucket)])
+            .header(reqwest::header::AUTHORIZATION, format!("Token {}", self.config.token))
+            .json(&serde_json::json!({
+                "start": "1970-01-01T00:00:00Z",
+                "stop": stop.to_rfc3339(),
+                "predicate": predicate,
+            }))
+            .send()
+            .await?;
+        
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow::anyhow!("InfluxDB delete failed with {}: {}", status, body));
+        }
+        Ok(())
     }
     
     /// Flush any pending writes
@@ -415,12 +916,18 @@ impl InfluxDBStorage {
             created_at: Instant::now(),
         };
         
-        self.batch_sender.send(batch).await?;
+        let Some(sender) = self.batch_sender.lock().clone() else {
+            anyhow::bail!("InfluxDB storage is shut down");
+        };
+        sender.send(batch).await?;
         Ok(())
     }
     
     /// Shutdown the storage system
-    pub async fn shutdown(&mut self) -> Result<()> {
+    ///
+    /// Buffered points are flushed and the workers finish the queued batches,
+    /// spooling the ones InfluxDB doesn't take.
+    pub async fn shutdown(&self) -> Result<()> {
         info!("Shutting down InfluxDB storage");
         
         // Set shutdown flag
@@ -429,29 +936,192 @@ impl InfluxDBStorage {
         // Flush remaining data
         self.flush().await?;
         
-        // Stop flush task
-        if let Some(handle) = self.flush_handle.take() {
+        // Stop flush task, which holds a sender of its own
+        let flush_handle = self.flush_handle.lock().take();
+        if let Some(mut handle) = flush_handle {
             // Give it a moment to finish
-            let _ = tokio::time::timeout(Duration::from_secs(2), handle).await;
+            if tokio::time::timeout(Duration::from_secs(2), &mut handle).await.is_err() {
+                handle.abort();
+            }
+        }
+        
+        // Spooled batches stay on disk for the next run
+        if let Some(replay) = self.replay_handle.lock().take() {
+            replay.handle.abort();
         }
         
-        // Drop our sender to signal workers to stop
-        // With mpsc, we can't easily replace it, so we'll rely on the shutdown flag
+        // Drop our sender so workers exit once the queue is drained
+        self.batch_sender.lock().take();
         
         // Wait for workers to finish
-        for (i, worker) in self.workers.drain(..).enumerate() {
-            match tokio::time::timeout(Duration::from_secs(5), worker.handle).await {
+        let deadline = tokio::time::Instant::now() + SHUTDOWN_TIMEOUT;
+        let workers = std::mem::take(&mut *self.workers.lock());
+        futures::future::join_all(workers.into_iter().enumerate().map(|(i, worker)| async move {
+            match tokio::time::timeout_at(deadline, worker.handle).await {
                 Ok(Ok(_)) => debug!("Worker {} shut down cleanly", i),
                 Ok(Err(e)) => error!("Worker {} panicked: {}", i, e),
                 Err(_) => error!("Worker {} timed out during shutdown", i),
             }
-        }
+        }))
+        .await;
         
         info!("InfluxDB storage shutdown complete");
         Ok(())
     }
 }
 
+/// Encode points as a line protocol request body
+fn line_protocol(points: &[DataPoint]) -> std::io::Result<Vec<u8>> {
+    let mut body = Vec::new();
+    for point in points {
+        point.write_data_point_to(&mut body)?;
+    }
+    Ok(body)
+}
+
+/// Gzip a request body
+fn gzip(body: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::with_capacity(body.len() / 4), Compression::fast());
+    encoder.write_all(body)?;
+    encoder.finish()
+}
+
+/// Spool a batch InfluxDB could not take, dropping it if the spool can't
+async fn spool_batch(spool: &WriteSpool, body: &[u8], points: usize) {
+    match spool.push(body).await {
+        Ok(evicted) => {
+            METRICS.record_influx_spool("spooled", 1);
+            if evicted > 0 {
+                warn!("InfluxDB write spool full, evicted {} oldest batches", evicted);
+                METRICS.record_influx_spool("evicted", evicted);
+            }
+        }
+        Err(e) => error!("Failed to spool batch of {} points, dropping it: {}", points, e),
+    }
+    let depth = spool.depth();
+    METRICS.set_influx_spool_depth(depth.batches, depth.bytes);
+}
+
+/// Epoch credits point, `None` when no vote was credited yet
+fn epoch_credits_point(
+    network: &str,
+    instance: Option<&str>,
+    metrics: &LatencyMetrics,
+    validator: Option<&Pubkey>,
+) -> Result<Option<DataPoint>> {
+    if metrics.epoch_max_credits == 0 {
+        return Ok(None);
+    }
+    
+    let mut builder = DataPoint::builder("epoch_credits");
+    if let Some(instance) = instance {
+        builder = builder.tag("instance", instance);
+    }
+    if let Some(validator) = validator {
+        builder = builder.tag("validator_id", validator.to_string());
+    }
+    let point = builder
+        .tag("network", network)
+        .field("epoch", metrics.epoch as i64)
+        .field("credits", metrics.epoch_credits as i64)
+        .field("max_credits", metrics.epoch_max_credits as i64)
+        .field("mean_credits", f64::from(metrics.mean_credits))
+        .timestamp(metrics.timestamp.timestamp_nanos_opt().unwrap_or(0))
+        .build()?;
+    Ok(Some(point))
+}
+
+/// Flux filter keeping the points of one network and monitor instance
+///
+/// Mainnet queries also match points still carrying the legacy `mainnet`
+/// tag, until the tag migration retags them.
+fn series_filter(network: &str, instance: Option<&str>) -> String {
+    let mut predicate = if network == "mainnet-beta" {
+        format!(r#"(r.network == "{}" or r.network == "{}")"#, network, LEGACY_NETWORK_TAG)
+    } else {
+        format!(r#"r.network == "{}""#, network)
+    };
+    if let Some(instance) = instance {
+        predicate.push_str(&format!(r#" and r.instance == "{}""#, instance));
+    }
+    format!("|> filter(fn: (r) => {})", predicate)
+}
+
+/// Rebuild a vote latency from a pivoted `vote_latency` row
+fn latency_from_row(row: &FluxRow) -> Option<VoteLatency> {
+    let voted_slot = row.get_u64("voted_slot")?;
+    let landed_slot = row.get_u64("landed_slot")?;
+    let received_timestamp = row.get_time("_time")?;
+    let latency_ms = row.get_i64("latency_ms").unwrap_or(0);
+    
+    let mut latency = VoteLatency::new_single_vote(
+        pubkey_tag(row, "validator_id"),
+        pubkey_tag(row, "vote_account"),
+        voted_slot,
+        received_timestamp - chrono::Duration::milliseconds(latency_ms),
+        received_timestamp,
+        row.get_str("signature").unwrap_or_default().to_string(),
+        landed_slot,
+    );
+    
+    latency.landing_status = row
+        .get_str("landing_status")
+        .and_then(LandingStatus::from_label)
+        .unwrap_or_default();
+    latency.leader = row.get_str("leader").and_then(|leader| Pubkey::from_str(leader).ok());
+    latency.breakdown = LatencyBreakdown {
+        create_to_land_ms: row.get_i64("create_to_land_ms"),
+        land_to_emit_ms: row.get_i64("land_to_emit_ms"),
+        emit_to_receive_ms: row.get_i64("emit_to_receive_ms"),
+    };
+    latency.slot_latency_ms = row.get_i64("slot_latency_ms");
+    
+    // Only the flag is stored, not which of the voted slots were abandoned
+    if row.get_bool("fork_vote") == Some(true) {
+        latency.fork_slots = vec![voted_slot];
+    }
+    
+    Some(latency)
+}
+
+/// Pubkey of a tag, `Pubkey::default()` if it is missing or truncated
+fn pubkey_tag(row: &FluxRow, tag: &str) -> Pubkey {
+    row.get_str(tag)
+        .and_then(|value| Pubkey::from_str(value).ok())
+        .unwrap_or_default()
+}
+
+/// Fold one row of the validator metrics query into `metrics`
+fn apply_metrics_row(metrics: &mut LatencyMetrics, row: &FluxRow) {
+    match row.result.as_str() {
+        "stats" => {
+            metrics.sample_count = row.get_u64("count").unwrap_or(0);
+            metrics.mean_slots = row.get_f64("mean").unwrap_or(0.0) as f32;
+            metrics.min_slots = row.get_f64("min").unwrap_or(0.0) as f32;
+            metrics.max_slots = row.get_f64("max").unwrap_or(0.0) as f32;
+        }
+        "quantiles" => {
+            let value = row.get_f64("_value").unwrap_or(0.0) as f32;
+            match row.get_str("quantile") {
+                Some("median") => metrics.median_slots = value,
+                Some("p95") => metrics.p95_slots = value,
+                Some("p99") => metrics.p99_slots = value,
+                _ => {}
+            }
+        }
+        "buckets" => {
+            let count = row.get_u64("_value").unwrap_or(0);
+            match row.get_str("bucket") {
+                Some("1_slot") => metrics.votes_1_slot = count,
+                Some("2_slots") => metrics.votes_2_slots = count,
+                Some("3plus_slots") => metrics.votes_3plus_slots = count,
+                _ => {}
+            }
+        }
+        _ => {}
+    }
+}
+
 /// Storage trait implementation for compatibility
 #[async_trait]
 impl crate::modules::storage::StorageManagerTrait for InfluxDBStorage {
@@ -465,13 +1135,35 @@ impl crate::modules::storage::StorageManagerTrait for InfluxDBStorage {
             .map_err(|e| crate::error::Error::internal(format!("InfluxDB write error: {}", e)))
     }
     
+    async fn store_missed_vote(&self, missed: &MissedVote) -> crate::error::Result<()> {
+        self.write_missed_vote(missed).await
+            .map_err(|e| crate::error::Error::internal(format!("InfluxDB write error: {}", e)))
+    }
+    
+    async fn store_fork_vote(&self, fork_vote: &ForkVote) -> crate::error::Result<()> {
+        self.write_fork_vote(fork_vote).await
+            .map_err(|e| crate::error::Error::internal(format!("InfluxDB write error: {}", e)))
+    }
+    
+    async fn store_hash_mismatch(&self, mismatch: &HashMismatch) -> crate::error::Result<()> {
+        self.write_hash_mismatch(mismatch).await
+            .map_err(|e| crate::error::Error::internal(format!("InfluxDB write error: {}", e)))
+    }
+    
+    async fn store_vote_account_update(&self, update: &VoteAccountUpdate) -> crate::error::Result<()> {
+        self.write_vote_account_update(update).await
+            .map_err(|e| crate::error::Error::internal(format!("InfluxDB write error: {}", e)))
+    }
+    
     async fn store_metrics(
         &self,
-        _metrics: &LatencyMetrics,
-        _validator_pubkey: Option<&solana_sdk::pubkey::Pubkey>,
+        metrics: &LatencyMetrics,
+        validator_pubkey: Option<&solana_sdk::pubkey::Pubkey>,
     ) -> crate::error::Result<()> {
-        // Metrics are calculated by InfluxDB queries, not stored separately
-        Ok(())
+        // Latency statistics are calculated by InfluxDB queries; credits
+        // depend on epoch boundaries and are stored as computed
+        self.write_epoch_credits(metrics, validator_pubkey).await
+            .map_err(|e| crate::error::Error::internal(format!("InfluxDB write error: {}", e)))
     }
     
     async fn query_latencies(
@@ -498,4 +1190,217 @@ impl crate::modules::storage::StorageManagerTrait for InfluxDBStorage {
         // Validator info is not stored in InfluxDB
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::flux_csv::{decode, FluxValue};
+    
+    const PIVOTED: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/flux/vote_latency_pivoted.csv"));
+    const METRICS: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/flux/validator_metrics.csv"));
+    
+    #[test]
+    fn test_latencies_from_pivoted_rows() {
+        let validator = Pubkey::from_str("7Np41oeYqPefeNQEHSv1UDhYrehxin3NStELsSKCT4K2").unwrap();
+        let latencies: Vec<VoteLatency> = decode(PIVOTED)
+            .unwrap()
+            .iter()
+            .filter_map(latency_from_row)
+            .collect();
+        assert_eq!(latencies.len(), 4);
+        
+        let first = &latencies[0];
+        assert_eq!(first.validator_pubkey, validator);
+        assert_eq!(first.vote_pubkey.to_string(), "5ZWgXcyqrrNpQHCme5SdC5hCeYb2o3fEJhF7Gok3bTVN");
+        assert_eq!(first.leader.unwrap().to_string(), "DRpbCBMxVnDK7maPM5tGv6MvB3v1sRMC86PZ8okm21hy");
+        assert_eq!(first.voted_on_slot(), 275_000_000);
+        assert_eq!(first.landed_slot, 275_000_001);
+        assert_eq!(first.latency_slot(), 1);
+        assert_eq!(first.latency_ms, 520);
+        assert_eq!(first.landing_status, LandingStatus::Confirmed);
+        assert_eq!(first.slot_latency_ms, Some(400));
+        assert_eq!(first.signature.len(), 88);
+        
+        assert_eq!(latencies[2].landing_status, LandingStatus::Processed);
+        assert_eq!(latencies[2].slot_latency_ms, None);
+        assert_eq!(latencies[2].leader, None);
+        
+        let last = &latencies[3];
+        assert_eq!(last.latency_slot(), 3);
+        assert_eq!(last.breakdown.create_to_land_ms, Some(412));
+        assert!(last.is_fork_vote());
+        
+        // Truncated tags of points not yet migrated
+        let mut row = decode(PIVOTED).unwrap().remove(0);
+        row.values.insert("validator_id".to_string(), FluxValue::String("7Np41oeY".to_string()));
+        assert_eq!(latency_from_row(&row).unwrap().validator_pubkey, Pubkey::default());
+    }
+    
+    #[test]
+    fn test_series_filter() {
+        assert_eq!(
+            series_filter("testnet", None),
+            r#"|> filter(fn: (r) => r.network == "testnet")"#
+        );
+        assert_eq!(
+            series_filter("mainnet-beta", Some("eu-west")),
+            r#"|> filter(fn: (r) => (r.network == "mainnet-beta" or r.network == "mainnet") and r.instance == "eu-west")"#
+        );
+    }
+    
+    /// URL of a local server answering every request with `status`
+    async fn respond_with(status: &'static str) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = [0u8; 4096];
+                let _ = socket.read(&mut request).await;
+                let response = format!("HTTP/1.1 {}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n", status);
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        url
+    }
+    
+    #[tokio::test]
+    async fn test_write_errors_classified() {
+        let writer = |url: String| {
+            let mut config = crate::config::Config::default().influxdb;
+            config.url = url;
+            LineProtocolWriter::new(reqwest::Client::new(), &config)
+        };
+        let body = b"vote_latency latency_ms=1 1\n";
+        
+        assert!(writer(respond_with("204 No Content").await).write(body).await.is_ok());
+        for status in ["400 Bad Request", "401 Unauthorized", "413 Payload Too Large"] {
+            let result = writer(respond_with(status).await).write(body).await;
+            assert!(matches!(result, Err(WriteError::Rejected(_))), "{}", status);
+        }
+        for status in ["429 Too Many Requests", "503 Service Unavailable"] {
+            let result = writer(respond_with(status).await).write(body).await;
+            assert!(matches!(result, Err(WriteError::Transient(_))), "{}", status);
+        }
+        
+        // Nothing listening
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+        assert!(matches!(writer(url).write(body).await, Err(WriteError::Transient(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_shutdown_stops_workers() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut config = crate::config::Config::default().influxdb;
+        config.url = format!("http://{}", listener.local_addr().unwrap());
+        config.spool_max_mb = 0;
+        config.num_workers = 4;
+        drop(listener);
+        
+        let storage = InfluxDBStorage::new(config, "testnet".to_string()).await.unwrap();
+        let started = Instant::now();
+        storage.shutdown().await.unwrap();
+        assert!(started.elapsed() < Duration::from_secs(2), "{:?}", started.elapsed());
+        assert!(storage.batch_sender.lock().is_none());
+        assert!(storage.workers.lock().is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_point_per_voted_slot() {
+        let mut config = crate::config::Config::default().influxdb;
+        config.url = "http://127.0.0.1:1".to_string();
+        config.spool_max_mb = 0;
+        let storage = InfluxDBStorage::new(config, "testnet".to_string()).await.unwrap();
+        
+        let received = DateTime::from_timestamp(1_000, 0).unwrap();
+        let mut latency = VoteLatency::new_with_slots(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            110,
+            received,
+            received,
+            "sig".to_string(),
+            vec![100, 105, 110],
+            112,
+        );
+        latency.fork_slots = vec![105];
+        latency.slot_latency_ms = Some(800);
+        
+        let points = storage.vote_latency_points(&latency).unwrap();
+        let lines = String::from_utf8(line_protocol(&points).unwrap()).unwrap();
+        let lines: Vec<&str> = lines.lines().collect();
+        assert_eq!(lines.len(), 3);
+        
+        assert!(lines[0].contains("latency_slots=12i,") && lines[0].contains("voted_slot=100i"));
+        assert!(lines[0].contains(" credits=6i,") && lines[0].ends_with(" 999999999998"));
+        assert!(lines[1].contains("fork_vote=t,") && lines[1].ends_with(" 999999999999"));
+        assert!(lines[2].contains("latency_slots=2i,") && lines[2].ends_with(" 1000000000000"));
+        assert!(!lines[0].contains("slot_latency_ms") && lines[2].contains("slot_latency_ms=800i"));
+        storage.shutdown().await.unwrap();
+    }
+    
+    #[test]
+    fn test_epoch_credits_point() {
+        let validator = Pubkey::new_unique();
+        let mut metrics = LatencyMetrics {
+            timestamp: DateTime::from_timestamp(1, 0).unwrap(),
+            ..Default::default()
+        };
+        assert!(epoch_credits_point("testnet", None, &metrics, Some(&validator)).unwrap().is_none());
+        
+        metrics.epoch = 700;
+        metrics.epoch_credits = 29;
+        metrics.epoch_max_credits = 32;
+        metrics.mean_credits = 14.5;
+        let point = epoch_credits_point("testnet", None, &metrics, Some(&validator)).unwrap().unwrap();
+        assert_eq!(
+            String::from_utf8(line_protocol(&[point]).unwrap()).unwrap(),
+            format!(
+                "epoch_credits,network=testnet,validator_id={} credits=29i,epoch=700i,max_credits=32i,mean_credits=14.5 1000000000\n",
+                validator
+            )
+        );
+    }
+    
+    #[test]
+    fn test_gzipped_line_protocol() {
+        use std::io::Read;
+        
+        let points = vec![
+            DataPoint::builder("vote_latency").tag("network", "testnet").field("latency_ms", 12.5).timestamp(1).build().unwrap(),
+            DataPoint::builder("vote_latency").tag("network", "testnet").field("latency_ms", 40.0).timestamp(2).build().unwrap(),
+        ];
+        let body = line_protocol(&points).unwrap();
+        assert_eq!(
+            String::from_utf8(body.clone()).unwrap(),
+            "vote_latency,network=testnet latency_ms=12.5 1\nvote_latency,network=testnet latency_ms=40 2\n"
+        );
+        
+        let mut decoded = Vec::new();
+        flate2::read::GzDecoder::new(&gzip(&body).unwrap()[..]).read_to_end(&mut decoded).unwrap();
+        assert_eq!(decoded, body);
+    }
+    
+    #[test]
+    fn test_metrics_from_rows() {
+        let mut metrics = LatencyMetrics::default();
+        for row in decode(METRICS).unwrap() {
+            apply_metrics_row(&mut metrics, &row);
+        }
+        
+        assert_eq!(metrics.sample_count, 160);
+        assert_eq!(metrics.mean_slots, 1.5625);
+        assert_eq!(metrics.min_slots, 1.0);
+        assert_eq!(metrics.max_slots, 5.0);
+        assert_eq!(metrics.median_slots, 1.0);
+        assert_eq!(metrics.p95_slots, 3.0);
+        assert_eq!(metrics.p99_slots, 4.0);
+        assert_eq!(metrics.votes_1_slot, 100);
+        assert_eq!(metrics.votes_2_slots, 50);
+        assert_eq!(metrics.votes_3plus_slots, 10);
+    }
+}
@@ -1,5 +1,8 @@
 //! Storage module for InfluxDB backend
 
+pub mod flux_csv;
 pub mod influxdb_storage;
+pub mod spool;
+pub mod tag_migration;
 
 pub use influxdb_storage::InfluxDBStorage;
\ No newline at end of file
@@ -0,0 +1,323 @@
+//! Write spool
+//!
+//! Batches InfluxDB could not take are kept in a local directory as line
+//! protocol, one file per batch, and replayed oldest first once the database
+//! is reachable again. The spool is capped by total size and batch count;
+//! when either cap is hit the oldest batches are evicted to make room.
+//! Batches left by a previous run are picked up on open, so restarting the
+//! database loses nothing short of an eviction. On a monitor shutdown the
+//! storage flushes its buffer and the workers spool what InfluxDB doesn't
+//! take; batches still being written when the shutdown timeout expires are
+//! lost. File I/O goes through `tokio::fs`; the lock only covers the
+//! bookkeeping.
+
+use anyhow::{anyhow, Result};
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+use tracing::{info, warn};
+
+/// Extension of spooled batch files
+const BATCH_EXTENSION: &str = "lp";
+
+/// Extension of batch files still being written
+const PARTIAL_EXTENSION: &str = "partial";
+
+/// A batch waiting on disk
+struct SpoolEntry {
+    seq: u64,
+    bytes: u64,
+}
+
+#[derive(Default)]
+struct SpoolState {
+    /// Spooled batches, oldest first
+    entries: VecDeque<SpoolEntry>,
+    /// Total size of spooled batches
+    bytes: u64,
+    /// Sequence number of the next batch
+    next_seq: u64,
+    /// Batches being written
+    pending: usize,
+}
+
+/// A spooled batch read back for replay
+pub struct SpooledBatch {
+    seq: u64,
+    
+    /// Line protocol of the batch
+    pub body: Vec<u8>,
+}
+
+/// Batches and bytes waiting in the spool
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpoolDepth {
+    /// Spooled batches
+    pub batches: usize,
+    
+    /// Their total size
+    pub bytes: u64,
+}
+
+/// On-disk queue of line protocol batches
+pub struct WriteSpool {
+    dir: PathBuf,
+    max_bytes: u64,
+    max_batches: usize,
+    state: Mutex<SpoolState>,
+}
+
+impl WriteSpool {
+    /// Open the spool directory, picking up batches left by a previous run
+    pub fn open(dir: &Path, max_bytes: u64, max_batches: usize) -> Result<Self> {
+        fs::create_dir_all(dir)
+            .map_err(|e| anyhow!("Failed to create spool directory {}: {}", dir.display(), e))?;
+        
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            match path.extension().and_then(|ext| ext.to_str()) {
+                Some(BATCH_EXTENSION) => {}
+                // Interrupted while writing; the batch was never acknowledged as spooled
+                Some(PARTIAL_EXTENSION) => {
+                    let _ = fs::remove_file(&path);
+                    continue;
+                }
+                _ => continue,
+            }
+            let Some(seq) = path.file_stem().and_then(|stem| stem.to_str()?.parse().ok()) else {
+                continue;
+            };
+            entries.push(SpoolEntry { seq, bytes: fs::metadata(&path)?.len() });
+        }
+        entries.sort_unstable_by_key(|entry| entry.seq);
+        
+        let state = SpoolState {
+            bytes: entries.iter().map(|entry| entry.bytes).sum(),
+            next_seq: entries.last().map_or(0, |entry| entry.seq + 1),
+            entries: entries.into(),
+            pending: 0,
+        };
+        if !state.entries.is_empty() {
+            info!(
+                "Recovered {} spooled InfluxDB batches ({} bytes) from {}",
+                state.entries.len(),
+                state.bytes,
+                dir.display()
+            );
+        }
+        
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            max_bytes,
+            max_batches,
+            state: Mutex::new(state),
+        })
+    }
+    
+    /// Spool a batch, returning how many older batches were evicted for it
+    pub async fn push(&self, body: &[u8]) -> Result<usize> {
+        let bytes = body.len() as u64;
+        if bytes > self.max_bytes {
+            return Err(anyhow!("Batch of {} bytes exceeds the spool size cap", bytes));
+        }
+        
+        let seq = {
+            let mut state = self.state.lock();
+            state.pending += 1;
+            state.next_seq += 1;
+            state.next_seq - 1
+        };
+        let written = self.write(seq, body).await;
+        
+        let evicted = {
+            let mut state = self.state.lock();
+            state.pending -= 1;
+            written?;
+            
+            // Concurrent pushes can finish out of order
+            let index = state.entries.partition_point(|entry| entry.seq < seq);
+            state.entries.insert(index, SpoolEntry { seq, bytes });
+            state.bytes += bytes;
+            
+            let mut evicted = Vec::new();
+            while state.bytes > self.max_bytes || state.entries.len() > self.max_batches {
+                let Some(oldest) = state.entries.pop_front() else {
+                    break;
+                };
+                state.bytes -= oldest.bytes;
+                evicted.push(oldest.seq);
+            }
+            evicted
+        };
+        
+        for seq in &evicted {
+            self.delete(*seq).await;
+        }
+        Ok(evicted.len())
+    }
+    
+    /// Read back the oldest batch, if any
+    ///
+    /// The batch stays spooled until passed to `remove`.
+    pub async fn oldest(&self) -> Option<SpooledBatch> {
+        loop {
+            let seq = self.state.lock().entries.front()?.seq;
+            let error = match tokio::fs::read(self.path(seq)).await {
+                Ok(body) => return Some(SpooledBatch { seq, body }),
+                Err(e) => e,
+            };
+            
+            // Evicted while being read, the next oldest takes its place
+            let unreadable = {
+                let mut state = self.state.lock();
+                match state.entries.front() {
+                    Some(entry) if entry.seq == seq => {
+                        let bytes = entry.bytes;
+                        state.entries.pop_front();
+                        state.bytes -= bytes;
+                        true
+                    }
+                    _ => false,
+                }
+            };
+            if unreadable {
+                warn!("Dropping unreadable spooled batch {}: {}", self.path(seq).display(), error);
+                self.delete(seq).await;
+            }
+        }
+    }
+    
+    /// Remove a batch once it was written
+    pub async fn remove(&self, batch: &SpooledBatch) {
+        let removed = {
+            let mut state = self.state.lock();
+            // Gone already if it was evicted while being replayed
+            match state.entries.iter().position(|entry| entry.seq == batch.seq) {
+                Some(index) => {
+                    if let Some(entry) = state.entries.remove(index) {
+                        state.bytes -= entry.bytes;
+                    }
+                    true
+                }
+                None => false,
+            }
+        };
+        if removed {
+            self.delete(batch.seq).await;
+        }
+    }
+    
+    /// Whether no batches are waiting or being spooled
+    pub fn is_empty(&self) -> bool {
+        let state = self.state.lock();
+        state.entries.is_empty() && state.pending == 0
+    }
+    
+    /// Batches and bytes waiting
+    pub fn depth(&self) -> SpoolDepth {
+        let state = self.state.lock();
+        SpoolDepth {
+            batches: state.entries.len(),
+            bytes: state.bytes,
+        }
+    }
+    
+    fn path(&self, seq: u64) -> PathBuf {
+        self.dir.join(format!("{:020}.{}", seq, BATCH_EXTENSION))
+    }
+    
+    /// Write a batch aside and rename it, so a crash never leaves half a batch to replay
+    async fn write(&self, seq: u64, body: &[u8]) -> Result<()> {
+        let partial = self.path(seq).with_extension(PARTIAL_EXTENSION);
+        let mut file = tokio::fs::File::create(&partial).await?;
+        file.write_all(body).await?;
+        file.sync_all().await?;
+        tokio::fs::rename(&partial, self.path(seq)).await?;
+        Ok(())
+    }
+    
+    async fn delete(&self, seq: u64) {
+        if let Err(e) = tokio::fs::remove_file(self.path(seq)).await {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("Failed to delete spooled batch {}: {}", self.path(seq).display(), e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    async fn drain(spool: &WriteSpool) -> Vec<Vec<u8>> {
+        let mut bodies = Vec::new();
+        while let Some(batch) = spool.oldest().await {
+            spool.remove(&batch).await;
+            bodies.push(batch.body);
+        }
+        bodies
+    }
+    
+    #[tokio::test]
+    async fn test_replays_oldest_first_across_restarts() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        {
+            let spool = WriteSpool::open(temp_dir.path(), 1024, 10).unwrap();
+            for body in ["a 1\n", "b 2\n", "c 3\n"] {
+                assert_eq!(spool.push(body.as_bytes()).await.unwrap(), 0);
+            }
+            
+            // Read but not removed: still there after a restart
+            let first = spool.oldest().await.unwrap();
+            assert_eq!(first.body, b"a 1\n");
+            assert_eq!(spool.depth(), SpoolDepth { batches: 3, bytes: 12 });
+        }
+        
+        // A batch interrupted mid-write is discarded
+        fs::write(temp_dir.path().join("00000000000000000003.partial"), "d 4").unwrap();
+        
+        let spool = WriteSpool::open(temp_dir.path(), 1024, 10).unwrap();
+        assert_eq!(spool.depth(), SpoolDepth { batches: 3, bytes: 12 });
+        spool.push(b"e 5\n").await.unwrap();
+        assert_eq!(drain(&spool).await, vec![b"a 1\n".to_vec(), b"b 2\n".to_vec(), b"c 3\n".to_vec(), b"e 5\n".to_vec()]);
+        assert!(spool.is_empty());
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+    }
+    
+    #[tokio::test]
+    async fn test_caps_evict_oldest() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        
+        // Size cap
+        let spool = WriteSpool::open(&temp_dir.path().join("bytes"), 10, 100).unwrap();
+        spool.push(b"aaaa").await.unwrap();
+        spool.push(b"bbbb").await.unwrap();
+        assert_eq!(spool.push(b"cccc").await.unwrap(), 1);
+        assert_eq!(spool.depth(), SpoolDepth { batches: 2, bytes: 8 });
+        assert!(spool.push(&[b'x'; 11]).await.is_err());
+        
+        // Batch count cap
+        let spool = WriteSpool::open(&temp_dir.path().join("batches"), 1024, 2).unwrap();
+        for body in [b"1", b"2", b"3", b"4"] {
+            spool.push(body).await.unwrap();
+        }
+        assert_eq!(drain(&spool).await, vec![b"3".to_vec(), b"4".to_vec()]);
+    }
+    
+    #[tokio::test]
+    async fn test_remove_after_eviction() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let spool = WriteSpool::open(temp_dir.path(), 1024, 1).unwrap();
+        spool.push(b"old").await.unwrap();
+        
+        let replaying = spool.oldest().await.unwrap();
+        assert_eq!(spool.push(b"new").await.unwrap(), 1);
+        
+        // Removing the evicted batch leaves the new one alone
+        spool.remove(&replaying).await;
+        assert_eq!(drain(&spool).await, vec![b"new".to_vec()]);
+    }
+}
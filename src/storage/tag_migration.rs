@@ -0,0 +1,247 @@
+//! Truncated pubkey tag migration
+//!
+//! Points used to be tagged with the first 8 characters of the validator
+//! identity, vote account and leader pubkeys. A prefix can't be turned back
+//! into a key on its own, so the migration resolves prefixes against the
+//! cluster's vote accounts: Flux rewrites the points of every resolved
+//! identity and vote account pair with full keys, after which the truncated
+//! series are deleted. Pairs whose prefixes collide with another key, and
+//! validators no longer in the vote account list, are left untouched.
+//!
+//! Points of that era were tagged `network=mainnet` whatever the cluster, so
+//! the rewrite can't be scoped to a network. It retags the points with the
+//! configured network instead, which is what queries filter on; run it with
+//! the configuration and RPC endpoint of the cluster the bucket was
+//! collecting from.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Length of the truncated tags
+pub const SHORT_TAG_LEN: usize = 8;
+
+/// Network tag of points written before the network was configurable
+pub const LEGACY_NETWORK_TAG: &str = "mainnet";
+
+/// Measurements tagged with validator pubkeys
+pub const MEASUREMENTS: [&str; 5] = [
+    "vote_latency",
+    "missed_vote",
+    "fork_vote",
+    "hash_mismatch",
+    "vote_account_state",
+];
+
+/// Truncated tags resolved against known vote accounts
+#[derive(Debug, Default)]
+pub struct ShortKeyMap {
+    /// Full identity and vote account keys by their truncated pair
+    pub pairs: BTreeMap<(String, String), (Pubkey, Pubkey)>,
+    
+    /// Full leader identities by prefix
+    pub leaders: BTreeMap<String, Pubkey>,
+    
+    /// Vote accounts left out because a prefix collides with another key
+    pub ambiguous: usize,
+}
+
+impl ShortKeyMap {
+    /// Resolve the prefixes of `(identity, vote account)` pairs
+    pub fn new(vote_accounts: &[(Pubkey, Pubkey)]) -> Self {
+        let mut identities: HashMap<String, HashSet<Pubkey>> = HashMap::new();
+        let mut votes: HashMap<String, HashSet<Pubkey>> = HashMap::new();
+        for (identity, vote_account) in vote_accounts {
+            identities.entry(short(identity)).or_default().insert(*identity);
+            votes.entry(short(vote_account)).or_default().insert(*vote_account);
+        }
+        
+        let mut map = Self::default();
+        for (prefix, keys) in &identities {
+            if let [identity] = keys.iter().collect::<Vec<_>>()[..] {
+                map.leaders.insert(prefix.clone(), *identity);
+            }
+        }
+        
+        for (identity, vote_account) in vote_accounts {
+            let key = (short(identity), short(vote_account));
+            if identities[&key.0].len() > 1 || votes[&key.1].len() > 1 {
+                map.ambiguous += 1;
+                continue;
+            }
+            map.pairs.insert(key, (*identity, *vote_account));
+        }
+        
+        map
+    }
+    
+    /// Flux rewriting the points of `measurement` written before `stop`,
+    /// tagged with `network`
+    ///
+    /// Yields the rewritten point count per full key pair, counting one field
+    /// every point of the measurement carries; `write` set to false only
+    /// counts them.
+    pub fn rewrite_query(
+        &self,
+        bucket: &str,
+        measurement: &str,
+        network: &str,
+        stop: DateTime<Utc>,
+        short_validator_tag: bool,
+        write: bool,
+    ) -> String {
+        let dict = |entries: Vec<(String, String)>| {
+            let pairs: Vec<String> = entries
+                .into_iter()
+                .map(|(key, value)| format!(r#"{{key: "{}", value: "{}"}}"#, key, value))
+                .collect();
+            format!("dict.fromList(pairs: [{}])", pairs.join(", "))
+        };
+        let pair_key = |(validator, vote): &(String, String)| format!("{}/{}", validator, vote);
+        
+        let validators = dict(self.pairs.iter().map(|(key, (validator, _))| (pair_key(key), validator.to_string())).collect());
+        let vote_accounts = dict(self.pairs.iter().map(|(key, (_, vote))| (pair_key(key), vote.to_string())).collect());
+        let leaders = dict(self.leaders.iter().map(|(prefix, leader)| (prefix.clone(), leader.to_string())).collect());
+        
+        let count_field = point_field(measurement);
+        let short_tag = if short_validator_tag { "validator_short: r.validator_id, " } else { "" };
+        let to = if write { format!(r#"|> to(bucket: "{}")"#, bucket) } else { String::new() };
+        
+        format!(
+            r#"
+            import "dict"
+            
+            validators = {validators}
+            vote_accounts = {vote_accounts}
+            leaders = {leaders}
+            
+            points = from(bucket: "{bucket}")
+                |> range(start: 0, stop: {stop})
+                |> filter(fn: (r) => r._measurement == "{measurement}")
+                |> map(fn: (r) => ({{r with _pair: r.validator_id + "/" + r.vote_account}}))
+                |> filter(fn: (r) => dict.get(dict: validators, key: r._pair, default: "") != "")
+                |> map(fn: (r) => ({{r with
+                    {short_tag}validator_id: dict.get(dict: validators, key: r._pair, default: ""),
+                    vote_account: dict.get(dict: vote_accounts, key: r._pair, default: ""),
+                    network: "{network}"
+                }}))
+                |> drop(columns: ["_pair"])
+            
+            points
+                |> filter(fn: (r) => not exists r.leader)
+                {to}
+                |> filter(fn: (r) => r._field == "{count_field}")
+                |> group(columns: ["validator_id", "vote_account"])
+                |> count()
+                |> yield(name: "without_leader")
+            
+            points
+                |> filter(fn: (r) => exists r.leader)
+                |> map(fn: (r) => ({{r with leader: dict.get(dict: leaders, key: r.leader, default: r.leader)}}))
+                {to}
+                |> filter(fn: (r) => r._field == "{count_field}")
+                |> group(columns: ["validator_id", "vote_account"])
+                |> count()
+                |> yield(name: "with_leader")
+            "#,
+            stop = stop.to_rfc3339_opts(SecondsFormat::Nanos, true),
+        )
+    }
+}
+
+/// Delete predicate for the truncated series of one pair
+pub fn delete_predicate(measurement: &str, validator: &Pubkey, vote_account: &Pubkey) -> String {
+    format!(
+        r#"_measurement="{}" AND validator_id="{}" AND vote_account="{}""#,
+        measurement,
+        short(validator),
+        short(vote_account)
+    )
+}
+
+/// Outcome of a migration run
+#[derive(Debug, Default)]
+pub struct TagMigrationReport {
+    /// Points rewritten with full keys (or that would be, on a dry run)
+    pub points: u64,
+    
+    /// Truncated series deleted, one per measurement and key pair
+    pub deleted_series: usize,
+}
+
+/// A field every point of `measurement` carries, so points are counted once
+/// rather than once per field
+fn point_field(measurement: &str) -> &'static str {
+    match measurement {
+        "vote_latency" => "latency_ms",
+        _ => "slot",
+    }
+}
+
+fn short(pubkey: &Pubkey) -> String {
+    pubkey.to_string()[..SHORT_TAG_LEN].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    /// A key whose base58 form starts with `prefix`
+    fn key_with_prefix(prefix: &str, seed: u8) -> Pubkey {
+        let mut encoded = Pubkey::new_from_array([seed; 32]).to_string();
+        encoded.replace_range(..prefix.len(), prefix);
+        encoded.parse().unwrap()
+    }
+    
+    #[test]
+    fn test_colliding_prefixes_left_out() {
+        let unique = (Pubkey::new_from_array([1; 32]), Pubkey::new_from_array([2; 32]));
+        let colliding = [
+            (key_with_prefix("Dup1dup1", 200), Pubkey::new_from_array([3; 32])),
+            (key_with_prefix("Dup1dup1", 201), Pubkey::new_from_array([4; 32])),
+        ];
+        
+        let map = ShortKeyMap::new(&[unique, colliding[0], colliding[1]]);
+        assert_eq!(map.pairs.len(), 1);
+        assert_eq!(map.pairs[&(short(&unique.0), short(&unique.1))], unique);
+        assert_eq!(map.ambiguous, 2);
+        assert!(map.leaders.contains_key(&short(&unique.0)));
+        assert!(!map.leaders.contains_key("Dup1dup1"));
+    }
+    
+    #[test]
+    fn test_rewrite_query() {
+        let (validator, vote_account) = (Pubkey::new_from_array([1; 32]), Pubkey::new_from_array([2; 32]));
+        let map = ShortKeyMap::new(&[(validator, vote_account)]);
+        let stop = DateTime::parse_from_rfc3339("2024-06-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        
+        let query = map.rewrite_query("raw", "vote_latency", "testnet", stop, true, true);
+        assert!(query.contains(&format!(
+            r#"{{key: "{}/{}", value: "{}"}}"#,
+            short(&validator),
+            short(&vote_account),
+            validator
+        )));
+        assert!(query.contains("stop: 2024-06-01T00:00:00.000000000Z"));
+        assert!(query.contains("validator_short: r.validator_id"));
+        assert!(query.contains(r#"network: "testnet""#));
+        assert_eq!(query.matches(r#"|> to(bucket: "raw")"#).count(), 2);
+        assert_eq!(query.matches(r#"r._field == "latency_ms""#).count(), 2);
+        
+        let missed = map.rewrite_query("raw", "missed_vote", "testnet", stop, false, true);
+        assert_eq!(missed.matches(r#"r._field == "slot""#).count(), 2);
+        
+        let dry_run = map.rewrite_query("raw", "vote_latency", "testnet", stop, false, false);
+        assert!(!dry_run.contains("to(bucket"));
+        assert!(!dry_run.contains("validator_short"));
+        
+        assert_eq!(
+            delete_predicate("missed_vote", &validator, &vote_account),
+            format!(
+                r#"_measurement="missed_vote" AND validator_id="{}" AND vote_account="{}""#,
+                &validator.to_string()[..8],
+                &vote_account.to_string()[..8]
+            )
+        );
+    }
+}